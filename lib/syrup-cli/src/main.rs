@@ -0,0 +1,245 @@
+//! `syrup`, a command-line tool for inspecting and converting syrup data.
+//!
+//! Every command reads a file, or stdin, holding any number of syrup values one after another,
+//! such as captured `CapTP` frames, and handles each value in turn.
+
+use std::{
+    io::{Read, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use clap::{Parser, Subcommand};
+use syrup::{
+    TokenTree,
+    de::{LimitExceeded, LimitedParseError, NotCanonical, ParseError, ParseLimits, TreePath},
+    json,
+};
+
+mod path;
+
+mod stats;
+use stats::Stats;
+
+#[cfg(test)]
+mod test;
+
+type Error = Box<dyn std::error::Error>;
+
+#[derive(Parser, Debug)]
+#[command(name = "syrup", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print each value in the text syntax, indented
+    Pretty {
+        /// Print each value on one line instead
+        #[arg(long)]
+        compact: bool,
+        /// Read from this file rather than stdin
+        file: Option<PathBuf>,
+    },
+    /// Check that each value is in canonical form, reporting where it isn't
+    Validate { file: Option<PathBuf> },
+    /// Re-encode each value in canonical form
+    Canonicalize { file: Option<PathBuf> },
+    /// Convert each value to JSON, one per line
+    ToJson {
+        /// Use the lossy mapping meant for reading, rather than the tagged one
+        #[arg(long)]
+        natural: bool,
+        file: Option<PathBuf>,
+    },
+    /// Convert JSON values, in the tagged mapping, to syrup
+    FromJson { file: Option<PathBuf> },
+    /// Print the part of each value at a path
+    ///
+    /// A path is a list of steps separated by `/`: `N` is the element at index N of a list or
+    /// record (not counting its label), `[N]` the element at index N of a list, `{key}` the value
+    /// for a key of a dictionary, and `<label>` only matches a record with that label. Keys and
+    /// labels are written in the text syntax, as in `<op:deliver>/1/{"id"}`; see
+    /// `syrup::de::TreePath`.
+    Get {
+        path: String,
+        /// Write the values as syrup rather than text
+        #[arg(long)]
+        raw: bool,
+        file: Option<PathBuf>,
+    },
+    /// Count the values in the input, by kind, along with their depth and largest literal
+    Stats { file: Option<PathBuf> },
+}
+
+impl Command {
+    fn file(&self) -> Option<&std::path::Path> {
+        match self {
+            Command::Pretty { file, .. }
+            | Command::Validate { file }
+            | Command::Canonicalize { file }
+            | Command::ToJson { file, .. }
+            | Command::FromJson { file }
+            | Command::Get { file, .. }
+            | Command::Stats { file } => file.as_deref(),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let input = match read_input(cli.command.file()) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("syrup: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut stdout = std::io::stdout().lock();
+    match run(&cli.command, &input, &mut stdout).and_then(|()| Ok(stdout.flush()?)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("syrup: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Read all of `file`, or of stdin if there's no file or it's `-`.
+fn read_input(file: Option<&std::path::Path>) -> Result<Vec<u8>, Error> {
+    let mut input = Vec::new();
+    match file {
+        Some(file) if file != std::path::Path::new("-") => {
+            input = std::fs::read(file).map_err(|e| format!("{}: {e}", file.display()))?;
+        }
+        _ => {
+            std::io::stdin().lock().read_to_end(&mut input)?;
+        }
+    }
+    Ok(input)
+}
+
+fn run(command: &Command, input: &[u8], out: &mut impl Write) -> Result<(), Error> {
+    match command {
+        Command::Pretty { compact, .. } => {
+            for tree in parse_all(input)? {
+                if *compact {
+                    writeln!(out, "{tree}")?;
+                } else {
+                    writeln!(out, "{tree:#}")?;
+                }
+            }
+        }
+        Command::Validate { .. } => {
+            let count = validate(input)?;
+            writeln!(out, "ok: {count} canonical value{}", plural(count))?;
+        }
+        Command::Canonicalize { .. } => {
+            for tree in parse_all(input)? {
+                tree.write_bytes(out)?;
+            }
+        }
+        Command::ToJson { natural, .. } => {
+            for tree in parse_all(input)? {
+                let value = if *natural {
+                    json::to_json_natural(&tree)
+                } else {
+                    json::to_json(&tree)?
+                };
+                serde_json::to_writer(&mut *out, &value)?;
+                writeln!(out)?;
+            }
+        }
+        Command::FromJson { .. } => {
+            for value in serde_json::Deserializer::from_slice(input).into_iter() {
+                json::from_json(&value?)?.write_bytes(out)?;
+            }
+        }
+        Command::Get { path, raw, .. } => {
+            let path: TreePath = path.parse()?;
+            for tree in parse_all(input)? {
+                let found = path::get(&path, &tree)?;
+                if *raw {
+                    found.write_bytes(out)?;
+                } else {
+                    writeln!(out, "{found:#}")?;
+                }
+            }
+        }
+        Command::Stats { .. } => {
+            let mut stats = Stats::default();
+            let mut offset = 0;
+            while offset < input.len() {
+                let (rem, tree, spans) = TokenTree::<&[u8]>::parse_spanned::<ParseError<'_>>(
+                    &input[offset..],
+                    &ParseLimits::default(),
+                )
+                .map_err(|e| describe(e, input, offset))?;
+                stats.add(&tree, &spans, offset);
+                offset = input.len() - rem.len();
+            }
+            write!(out, "{stats}")?;
+        }
+    }
+    Ok(())
+}
+
+const fn plural(count: usize) -> &'static str {
+    if count == 1 { "" } else { "s" }
+}
+
+/// Parse each of the values in `input`.
+fn parse_all(input: &[u8]) -> Result<Vec<TokenTree<&[u8]>>, Error> {
+    let mut trees = Vec::new();
+    let mut rem = input;
+    while !rem.is_empty() {
+        let offset = input.len() - rem.len();
+        let (next, tree) =
+            TokenTree::parse_with_limits::<ParseError<'_>>(rem, &ParseLimits::default())
+                .map_err(|e| describe(e, input, offset))?;
+        trees.push(tree);
+        rem = next;
+    }
+    Ok(trees)
+}
+
+/// Parse each of the values in `input` in canonical mode, returning how many there are.
+fn validate(input: &[u8]) -> Result<usize, Error> {
+    let mut count = 0;
+    let mut rem = input;
+    while !rem.is_empty() {
+        let offset = input.len() - rem.len();
+        (rem, _) =
+            TokenTree::<&[u8]>::parse_canonical::<ParseError<'_>>(rem, &ParseLimits::default())
+                .map_err(|e| describe(e, input, offset))?;
+        count += 1;
+    }
+    match count {
+        0 => Err("no syrup values in the input".into()),
+        count => Ok(count),
+    }
+}
+
+/// Describe an error from parsing the value at `offset` in `input`, with offsets into all of
+/// `input`.
+fn describe(error: LimitedParseError<ParseError<'_>>, input: &[u8], offset: usize) -> Error {
+    match error {
+        LimitedParseError::Parse(syrup::nom::Err::Error(e) | syrup::nom::Err::Failure(e)) => {
+            let e = e.with_origin(input);
+            format!("{e}\n{}", e.hex_context(8)).into()
+        }
+        LimitedParseError::Parse(syrup::nom::Err::Incomplete(_)) => {
+            format!("unexpected end of input at byte {}", input.len()).into()
+        }
+        LimitedParseError::Limit(e) => Box::new(LimitExceeded {
+            offset: offset + e.offset,
+            ..e
+        }),
+        LimitedParseError::NotCanonical(e) => Box::new(NotCanonical {
+            offset: offset + e.offset,
+            ..e
+        }),
+    }
+}
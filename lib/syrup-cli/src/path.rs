@@ -0,0 +1,44 @@
+use syrup::{
+    TokenTree,
+    de::{PathStep, TreePath},
+};
+
+use crate::Error;
+
+/// Follow `path` from `tree`, saying where and why it leads nowhere if it does.
+pub(crate) fn get<'t, 'i>(
+    path: &TreePath,
+    mut tree: &'t TokenTree<&'i [u8]>,
+) -> Result<&'t TokenTree<&'i [u8]>, Error> {
+    for (i, step) in path.steps().iter().enumerate() {
+        tree = step.get(tree).ok_or_else(|| {
+            let at = TreePath::new(path.steps()[..=i].to_vec());
+            format!("at `{at}`: {}", missing(step, tree))
+        })?;
+    }
+    Ok(tree)
+}
+
+/// Why `step` leads nowhere from `tree`
+fn missing(step: &PathStep, tree: &TokenTree<&[u8]>) -> String {
+    match (step, tree) {
+        (PathStep::Element(_) | PathStep::ListElement(_), TokenTree::List(list)) => {
+            format!(
+                "out of range for a list of {} elements",
+                list.elements.len()
+            )
+        }
+        (PathStep::Element(_), TokenTree::Record(record)) => format!(
+            "out of range for a record of {} elements",
+            record.elements.len()
+        ),
+        (PathStep::Label(_), TokenTree::Record(record)) => {
+            format!("found a record labelled {}", record.label)
+        }
+        (PathStep::Key(_), TokenTree::Dictionary(_)) => "no such key in the dictionary".to_owned(),
+        (PathStep::Element(_), _) => format!("expected a list or record, found {}", tree.kind()),
+        (PathStep::ListElement(_), _) => format!("expected a list, found {}", tree.kind()),
+        (PathStep::Label(_), _) => format!("expected a record, found {}", tree.kind()),
+        (PathStep::Key(_), _) => format!("expected a dictionary, found {}", tree.kind()),
+    }
+}
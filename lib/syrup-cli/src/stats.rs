@@ -0,0 +1,138 @@
+use std::fmt;
+
+use syrup::{
+    TokenTree,
+    de::{Literal, SpanTree},
+};
+
+/// Counts of the values in some syrup input
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Stats {
+    values: usize,
+    booleans: usize,
+    integers: usize,
+    floats: usize,
+    strings: usize,
+    symbols: usize,
+    byte_strings: usize,
+    lists: usize,
+    records: usize,
+    sets: usize,
+    dictionaries: usize,
+    /// The most levels of values nested inside one another; a lone literal has a depth of 1
+    depth: usize,
+    /// The encoded length, kind and offset of the longest literal
+    largest: Option<(usize, &'static str, usize)>,
+}
+
+impl Stats {
+    /// Count the nodes of `tree`, which was parsed from the input at `offset`, into `spans`.
+    pub(crate) fn add(&mut self, tree: &TokenTree<&[u8]>, spans: &SpanTree, offset: usize) {
+        self.values += 1;
+        nodes(tree, spans, 1, &mut |tree, spans, depth| {
+            self.depth = self.depth.max(depth);
+            let TokenTree::Literal(literal) = tree else {
+                match tree {
+                    TokenTree::List(_) => self.lists += 1,
+                    TokenTree::Record(_) => self.records += 1,
+                    TokenTree::Set(_) => self.sets += 1,
+                    TokenTree::Dictionary(_) => self.dictionaries += 1,
+                    TokenTree::Literal(_) => unreachable!(),
+                }
+                return;
+            };
+            let (count, kind) = match literal {
+                Literal::Bool(_) => (&mut self.booleans, "boolean"),
+                Literal::Int(_) => (&mut self.integers, "integer"),
+                Literal::F32(_) | Literal::F64(_) => (&mut self.floats, "float"),
+                Literal::String(_) => (&mut self.strings, "string"),
+                Literal::Symbol(_) => (&mut self.symbols, "symbol"),
+                Literal::Bytes(_) => (&mut self.byte_strings, "byte string"),
+            };
+            *count += 1;
+            let span = spans.span();
+            if self.largest.is_none_or(|(len, ..)| span.len() > len) {
+                self.largest = Some((span.len(), kind, offset + span.start));
+            }
+        });
+    }
+
+    fn literals(&self) -> usize {
+        self.booleans
+            + self.integers
+            + self.floats
+            + self.strings
+            + self.symbols
+            + self.byte_strings
+    }
+
+    fn nodes(&self) -> usize {
+        self.literals() + self.lists + self.records + self.sets + self.dictionaries
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "values: {}", self.values)?;
+        writeln!(f, "nodes: {}", self.nodes())?;
+        writeln!(f, "  literals: {}", self.literals())?;
+        writeln!(f, "    booleans: {}", self.booleans)?;
+        writeln!(f, "    integers: {}", self.integers)?;
+        writeln!(f, "    floats: {}", self.floats)?;
+        writeln!(f, "    strings: {}", self.strings)?;
+        writeln!(f, "    symbols: {}", self.symbols)?;
+        writeln!(f, "    byte strings: {}", self.byte_strings)?;
+        writeln!(f, "  lists: {}", self.lists)?;
+        writeln!(f, "  records: {}", self.records)?;
+        writeln!(f, "  sets: {}", self.sets)?;
+        writeln!(f, "  dictionaries: {}", self.dictionaries)?;
+        writeln!(f, "depth: {}", self.depth)?;
+        match self.largest {
+            Some((len, kind, offset)) => {
+                writeln!(f, "largest literal: {len} bytes, a {kind} at byte {offset}")
+            }
+            None => writeln!(f, "largest literal: none"),
+        }
+    }
+}
+
+/// Call `f` with each node of `tree` and its span, in pre-order, along with its depth.
+fn nodes<'t, Data>(
+    tree: &'t TokenTree<Data>,
+    spans: &'t SpanTree,
+    depth: usize,
+    f: &mut impl FnMut(&'t TokenTree<Data>, &'t SpanTree, usize),
+) {
+    f(tree, spans, depth);
+    let depth = depth + 1;
+    match (tree, spans) {
+        (TokenTree::List(list), SpanTree::List { elements, .. }) => {
+            for (tree, spans) in list.elements.iter().zip(elements) {
+                nodes(tree, spans, depth, f);
+            }
+        }
+        (
+            TokenTree::Record(record),
+            SpanTree::Record {
+                label, elements, ..
+            },
+        ) => {
+            nodes(&record.label, label, depth, f);
+            for (tree, spans) in record.elements.iter().zip(elements) {
+                nodes(tree, spans, depth, f);
+            }
+        }
+        (TokenTree::Set(set), SpanTree::Set { entries, .. }) => {
+            for (tree, spans) in set.into_iter().zip(entries) {
+                nodes(tree, spans, depth, f);
+            }
+        }
+        (TokenTree::Dictionary(dict), SpanTree::Dictionary { entries, .. }) => {
+            for ((key, value), (key_spans, value_spans)) in dict.into_iter().zip(entries) {
+                nodes(key, key_spans, depth, f);
+                nodes(value, value_spans, depth, f);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,99 @@
+use clap::Parser;
+
+use crate::{Cli, run};
+
+/// Run `syrup` with `args` on `input`, returning what it writes or the error it reports.
+fn syrup(args: &[&str], input: &[u8]) -> Result<Vec<u8>, String> {
+    let cli = Cli::try_parse_from(std::iter::once("syrup").chain(args.iter().copied()))
+        .unwrap_or_else(|e| panic!("invalid arguments {args:?}: {e}"));
+    let mut out = Vec::new();
+    run(&cli.command, input, &mut out).map_err(|e| e.to_string())?;
+    Ok(out)
+}
+
+fn text(args: &[&str], input: &[u8]) -> String {
+    String::from_utf8(syrup(args, input).unwrap()).unwrap()
+}
+
+#[test]
+fn prints_each_value() {
+    assert_eq!(
+        text(&["pretty", "--compact"], b"[1+2'hi]<3'foo0+>"),
+        "[1 hi]\n<foo 0>\n"
+    );
+    assert_eq!(text(&["pretty"], b"").lines().count(), 0);
+    let err = syrup(&["pretty"], b"[1+2'hi").unwrap_err();
+    assert!(err.contains("unexpected end of input at byte 7"), "{err}");
+}
+
+#[test]
+fn validates_canonical_form() {
+    assert_eq!(
+        text(&["validate"], b"{1'a1+1'b2+}[]"),
+        "ok: 2 canonical values\n"
+    );
+    let err = syrup(&["validate"], b"[]{1'b2+1'a1+}").unwrap_err();
+    // the out-of-order key is at byte 8 of the whole input, not of the second value
+    assert!(err.contains("at byte 8"), "{err}");
+    assert!(syrup(&["validate"], b"").is_err());
+}
+
+#[test]
+fn canonicalizes() {
+    assert_eq!(
+        syrup(&["canonicalize"], b"{1'b02+1'a1+}#1+0+$").unwrap(),
+        b"{1'a1+1'b2+}#0+1+$"
+    );
+}
+
+#[test]
+fn converts_json_both_ways() {
+    let input = b"<5'point1+2+>[3'abc]";
+    let json = text(&["to-json"], input);
+    assert_eq!(json.lines().count(), 2);
+    assert_eq!(syrup(&["from-json"], json.as_bytes()).unwrap(), input);
+    assert_eq!(
+        text(&["to-json", "--natural"], input),
+        "{\"point\":[1,2]}\n[\"abc\"]\n"
+    );
+}
+
+#[test]
+fn gets_parts_of_values() {
+    let input = b"<10'op:deliver3+[4'ping{2'id5+}]>";
+    assert_eq!(text(&["get", "0"], input), "3\n");
+    assert_eq!(text(&["get", "<op:deliver>/1/[0]"], input), "ping\n");
+    assert_eq!(text(&["get", "/1/1/{id}/"], input), "5\n");
+    assert_eq!(syrup(&["get", "--raw", "1/1"], input).unwrap(), b"{2'id5+}");
+
+    let err = syrup(&["get", "<op:abort>/0"], input).unwrap_err();
+    assert!(err.starts_with("at `<op:abort>`"), "{err}");
+    let err = syrup(&["get", "1/[5]"], input).unwrap_err();
+    assert!(err.starts_with("at `1/[5]`"), "{err}");
+    assert!(syrup(&["get", "[0]"], input).is_err());
+    assert!(syrup(&["get", "x"], input).is_err());
+}
+
+#[test]
+fn counts_values() {
+    let stats = text(&["stats"], b"[1+t3:abc]<3'foo{1'a1\"x}>");
+    let lines = [
+        "values: 2",
+        "nodes: 9",
+        "  literals: 6",
+        "    booleans: 1",
+        "    integers: 1",
+        "    floats: 0",
+        "    strings: 1",
+        "    symbols: 2",
+        "    byte strings: 1",
+        "  lists: 1",
+        "  records: 1",
+        "  dictionaries: 1",
+        "depth: 3",
+        "largest literal: 5 bytes, a byte string at byte 4",
+    ];
+    for line in lines {
+        assert!(stats.lines().any(|l| l == line), "{line:?} in:\n{stats}");
+    }
+}
@@ -2,70 +2,143 @@ use proc_macro2::Span;
 use quote::quote;
 use syn::{
     parse_quote, parse_quote_spanned, punctuated::Punctuated, spanned::Spanned, token::Comma,
-    DeriveInput, Expr, ExprStruct, FieldValue, FieldsNamed, FieldsUnnamed, GenericParam, Ident,
-    ImplItemFn, Index, Lifetime, LifetimeParam, LitStr, Member, Signature, Token, TypeParam,
-    WhereClause,
+    DeriveInput, Expr, ExprStruct, FieldValue, Fields, GenericParam, Ident, ImplItemFn, Lifetime,
+    LifetimeParam, LitByteStr, LitStr, Path, Signature, Token, TypeParam, WhereClause,
 };
 
-use crate::{Context, FieldAttr, OuterAttr};
+use crate::{
+    decoded_tys, dictionary_keys, dictionary_slot, max_elements, plan_fields, variant_labels,
+    Context, FieldSource, OuterAttr, PlannedField,
+};
 
 pub(crate) fn generate_decode(input: DeriveInput) -> syn::Result<proc_macro::TokenStream> {
-    fn generate_fields<'f>(
-        context @ Context {
-            outer: OuterAttr { syrup, .. },
+    fn generate_fields(
+        Context {
             decode_error_ty,
             result_ty,
             ..
         }: &Context,
-        _tree_lt: &Lifetime,
-        _idata_ty: &Ident,
-        fields: impl IntoIterator<Item = &'f syn::Field>,
-    ) -> syn::Result<Punctuated<FieldValue, Comma>> {
+        source: FieldSource,
+        label: Option<&LitStr>,
+        fields: &[PlannedField<'_>],
+    ) -> Punctuated<FieldValue, Comma> {
+        let label = label.map(|label| quote! { .at_label(#label) });
+        // index into `elements`, which doesn't advance for skipped fields
+        let mut position: usize = 0;
         let mut res = Punctuated::new();
-        for (i, field) in fields.into_iter().enumerate() {
-            let (member, expected): (Member, Expr) = match field.ident.as_ref() {
-                Some(id) => {
-                    let id_str = LitStr::new(&id.to_string(), id.span());
-                    (
-                        syn::Member::Named(id.clone()),
-                        parse_quote_spanned! {field.span()=>#syrup::de::SyrupKind::Unknown(#id_str)},
-                    )
-                }
-                None => {
-                    let exp_str = LitStr::new(&format!("{i}th field"), field.span());
-                    (
-                        syn::Member::Unnamed(Index {
-                            index: i as u32,
-                            span: field.span(),
-                        }),
-                        parse_quote_spanned! {field.span()=>#syrup::de::SyrupKind::Unknown(#exp_str)},
-                    )
+        for PlannedField {
+            field,
+            attr,
+            member,
+            expected,
+            at_field,
+        } in fields
+        {
+            let expr: Expr = if attr.skip {
+                attr.default.clone().unwrap_or_else(|| {
+                    parse_quote_spanned! {field.span()=>::std::default::Default::default()}
+                })
+            } else if attr.rest {
+                parse_quote_spanned! {field.span()=>
+                    elements
+                        .get(#position..)
+                        .unwrap_or_default()
+                        .iter()
+                        .enumerate()
+                        .map(|(i, el)| el.decode().map_err(|e: #decode_error_ty| e.at_index(i) #at_field))
+                        .collect::<#result_ty<_, #decode_error_ty>>()?
                 }
-            };
-            let attr = FieldAttr::new(context, field)?;
+            } else {
+                let lookup: Expr = match source {
+                    FieldSource::Elements | FieldSource::Empty => {
+                        let lookup = parse_quote_spanned! {field.span()=>elements.get(#position)};
+                        position += 1;
+                        lookup
+                    }
+                    FieldSource::Input => {
+                        parse_quote_spanned! {field.span()=>::std::option::Option::Some(input)}
+                    }
+                    FieldSource::Dictionary => {
+                        let slot = dictionary_slot(field);
+                        parse_quote_spanned! {field.span()=>#slot}
+                    }
+                };
 
-            let mut expr = parse_quote_spanned! {field.span()=>
-                match elements.get(#i) {
-                    Some(el) => el,
-                    None => return #result_ty::Err(#decode_error_ty::Missing(#expected))
-                }
-            };
+                let decoded: Expr = if let Some(decode) = &attr.decode {
+                    let dec = decode(&parse_quote! { el });
+                    parse_quote_spanned! {dec.span()=>#dec.map_err(|e: #decode_error_ty| e #at_field)?}
+                } else {
+                    parse_quote_spanned! {field.span()=>el.decode().map_err(|e: #decode_error_ty| e #at_field)?}
+                };
 
-            expr = if let Some(decode) = attr.decode {
-                let dec = decode(&expr);
-                parse_quote_spanned! {dec.span()=>#dec?}
-            } else {
-                parse_quote_spanned! {field.span()=>#expr.decode()?}
+                let missing: Expr = match &attr.default {
+                    Some(default) => default.clone(),
+                    None => parse_quote_spanned! {field.span()=>
+                        return #result_ty::Err(#decode_error_ty::missing(#expected) #label)
+                    },
+                };
+
+                parse_quote_spanned! {field.span()=>
+                    match #lookup {
+                        ::std::option::Option::Some(el) => #decoded,
+                        ::std::option::Option::None => #missing,
+                    }
+                }
             };
 
             res.push(FieldValue {
                 attrs: Vec::with_capacity(0),
-                member,
+                member: member.clone(),
                 colon_token: Some(Token![:](field.span())),
                 expr,
             });
         }
-        Ok(res)
+        res
+    }
+
+    /// Construct `path` (either `Self` or `Self::Variant`) from the values found in `source`
+    ///
+    /// `label` is the label of the record being decoded, if any.
+    fn generate_constructor(
+        context: &Context,
+        source: FieldSource,
+        label: Option<&LitStr>,
+        path: Path,
+        fields: &Fields,
+    ) -> syn::Result<Expr> {
+        let planned = plan_fields(context, source, label, fields)?;
+        let ctor = match fields {
+            syn::Fields::Unit => parse_quote! { #path },
+            _ => Expr::Struct(ExprStruct {
+                attrs: Vec::with_capacity(0),
+                qself: None,
+                path,
+                brace_token: Default::default(),
+                fields: generate_fields(context, source, label, &planned),
+                dot2_token: None,
+                rest: None,
+            }),
+        };
+        let Context {
+            decode_error_ty,
+            result_ty,
+            ..
+        } = context;
+        if !matches!(source, FieldSource::Elements) {
+            return Ok(ctor);
+        }
+        let Some(arity) = max_elements(context, &planned)? else {
+            return Ok(ctor);
+        };
+        let at_label = label.map(|label| quote! { .at_label(#label) });
+        Ok(parse_quote! {{
+            if elements.len() > #arity {
+                return #result_ty::Err(
+                    #decode_error_ty::extra_elements(#arity, elements.len()) #at_label
+                );
+            }
+            #ctor
+        }})
     }
 
     let input_lt = Lifetime::new("'__input", Span::call_site());
@@ -98,15 +171,19 @@ pub(crate) fn generate_decode(input: DeriveInput) -> syn::Result<proc_macro::Tok
         default: None,
     };
 
-    let id = input.ident;
+    let context = Context::new(OuterAttr::new(&input)?);
 
-    let context = Context::new(OuterAttr::new(&id, &input.attrs)?);
+    let id = input.ident;
 
     let Context {
         outer:
             OuterAttr {
                 syrup,
                 label,
+                with,
+                transparent,
+                dictionary,
+                deny_unknown_keys,
                 decode_where,
                 ..
             },
@@ -130,26 +207,15 @@ pub(crate) fn generate_decode(input: DeriveInput) -> syn::Result<proc_macro::Tok
         #idata_ty: #syrup::borrow_or_share::BorrowOrShare<#input_lt, #output_lt, [u8]>
     });
 
-    if decode_where.is_empty() {
-        match &input.data {
-            syn::Data::Enum(_) => todo!("derive(Decode) for enums"),
-            syn::Data::Union(_) => todo!("derive(Decode) for unions"),
-            syn::Data::Struct(data) => match &data.fields {
-                syn::Fields::Unit => {}
-                syn::Fields::Named(FieldsNamed { named: fields, .. })
-                | syn::Fields::Unnamed(FieldsUnnamed {
-                    unnamed: fields, ..
-                }) => {
-                    for field in fields {
-                        let ty = &field.ty;
-                        where_clause
-                            .predicates
-                            .push(parse_quote_spanned! {ty.span()=>
-                                #ty: #syrup::Decode<#input_lt, #idata_ty>
-                            });
-                    }
-                }
-            },
+    // when decoding `with` another module, that module's `decode` is responsible for the fields
+    if decode_where.is_empty() && with.is_none() {
+        // skipped fields aren't decoded, and `rest` fields are decoded element by element
+        for ty in decoded_tys(&context, &input.data, false)? {
+            where_clause
+                .predicates
+                .push(parse_quote_spanned! {ty.span()=>
+                    #ty: #syrup::Decode<#input_lt, #idata_ty>
+                });
         }
     }
 
@@ -160,6 +226,82 @@ pub(crate) fn generate_decode(input: DeriveInput) -> syn::Result<proc_macro::Tok
     let decode_sig: Signature = parse_quote! {fn decode(input: &#input_lt #syrup::de::TokenTree<#idata_ty>) -> #de_result_ty };
 
     let decode_fn: ImplItemFn = match input.data {
+        _ if with.is_some() => parse_quote! {
+            #decode_sig {
+                #with::decode(input)
+            }
+        },
+        syn::Data::Struct(data) if *transparent => {
+            let res = generate_constructor(
+                &context,
+                FieldSource::Input,
+                None,
+                parse_quote! { Self },
+                &data.fields,
+            )?;
+            parse_quote! {
+                #decode_sig {
+                    #result_ty::Ok(#res)
+                }
+            }
+        }
+        syn::Data::Struct(data) if *dictionary => {
+            let fields = dictionary_keys(&context, &data.fields)?;
+            let keys = fields.iter().map(|(_, _, key)| key);
+            let slots: Vec<Ident> = fields
+                .iter()
+                .map(|(field, _, _)| dictionary_slot(field))
+                .collect();
+            let arms = fields.iter().zip(&slots).map(|((_, _, key), slot)| {
+                let key_bytes = LitByteStr::new(key.value().as_bytes(), key.span());
+                quote! { #key_bytes => #slot = ::std::option::Option::Some(value) }
+            });
+            let (non_symbol_key, unknown_key) = if *deny_unknown_keys {
+                (
+                    quote! {
+                        return #result_ty::Err(#decode_error_ty::unexpected(
+                            #syrup::de::SyrupKind::Symbol(::std::option::Option::None),
+                            key,
+                        ))
+                    },
+                    quote! {
+                        found => return #result_ty::Err(#decode_error_ty::unknown_key(KEYS, found))
+                    },
+                )
+            } else {
+                (quote! { continue }, quote! { _ => {} })
+            };
+            let res = generate_constructor(
+                &context,
+                FieldSource::Dictionary,
+                None,
+                parse_quote! { Self },
+                &data.fields,
+            )?;
+
+            parse_quote! {
+                #decode_sig {
+                    const KEYS: &[&::std::primitive::str] = &[#(#keys),*];
+                    let #token_tree_ty::Dictionary(dict) = input else {
+                        return #result_ty::Err(#decode_error_ty::unexpected(
+                            #syrup::de::SyrupKind::Dictionary,
+                            input,
+                        ))
+                    };
+                    #(let mut #slots = ::std::option::Option::None;)*
+                    for (key, value) in dict {
+                        let #token_tree_ty::Literal(#literal_ty::Symbol(key_sym)) = key else {
+                            #non_symbol_key
+                        };
+                        match key_sym.borrow_or_share() {
+                            #(#arms,)*
+                            #unknown_key
+                        }
+                    }
+                    #result_ty::Ok(#res)
+                }
+            }
+        }
         syn::Data::Union(u) => {
             return Err(syn::Error::new_spanned(
                 u.union_token,
@@ -167,10 +309,47 @@ pub(crate) fn generate_decode(input: DeriveInput) -> syn::Result<proc_macro::Tok
             ))
         }
         syn::Data::Enum(data) => {
-            return Err(syn::Error::new_spanned(
-                data.enum_token,
-                "not yet implemented: enum decode derivation",
-            ))
+            let variants = variant_labels(&data)?;
+            let labels = variants.iter().map(|(_, label)| label);
+            let mut arms: Vec<proc_macro2::TokenStream> = Vec::with_capacity(variants.len());
+            for (variant, label) in &variants {
+                let label_bytes = LitByteStr::new(label.value().as_bytes(), label.span());
+                let var_id = &variant.ident;
+                let ctor = generate_constructor(
+                    &context,
+                    FieldSource::Elements,
+                    Some(label),
+                    parse_quote! { Self::#var_id },
+                    &variant.fields,
+                )?;
+                arms.push(quote! { #label_bytes => #result_ty::Ok(#ctor) });
+            }
+
+            parse_quote! {
+                #decode_sig {
+                    const LABELS: &[&::std::primitive::str] = &[#(#labels),*];
+                    // unit variants may also be encoded as bare symbols, so treat a symbol as if it
+                    // were an empty record
+                    let (label, elements) = match input {
+                        #token_tree_ty::Record(record) => (&record.label, record.elements.as_slice()),
+                        #token_tree_ty::Literal(#literal_ty::Symbol(_)) => (input, [].as_slice()),
+                        _ => return #result_ty::Err(#decode_error_ty::unexpected(
+                            #syrup::de::SyrupKind::Record { label: ::std::option::Option::None },
+                            input,
+                        ))
+                    };
+                    let #token_tree_ty::Literal(#literal_ty::Symbol(label_sym)) = label else {
+                        return #result_ty::Err(#decode_error_ty::unexpected(
+                            #syrup::de::SyrupKind::Symbol(::std::option::Option::None),
+                            label,
+                        ))
+                    };
+                    match label_sym.borrow_or_share() {
+                        #(#arms,)*
+                        found => #result_ty::Err(#decode_error_ty::unexpected_label(LABELS, found)),
+                    }
+                }
+            }
         }
         syn::Data::Struct(data) => {
             let label_expr: Expr = parse_quote_spanned! {label.span()=> {
@@ -188,26 +367,14 @@ pub(crate) fn generate_decode(input: DeriveInput) -> syn::Result<proc_macro::Tok
                         ))
                 }
             }};
-            let res_expr: Expr = match &data.fields {
-                syn::Fields::Named(FieldsNamed { named: fields, .. })
-                | syn::Fields::Unnamed(FieldsUnnamed {
-                    unnamed: fields, ..
-                }) => {
-                    let res = ExprStruct {
-                        attrs: Vec::with_capacity(0),
-                        qself: None,
-                        path: parse_quote! { Self },
-                        brace_token: Default::default(),
-                        fields: generate_fields(&context, &input_lt, &idata_ty, fields)?,
-                        dot2_token: None,
-                        rest: None,
-                    };
-                    parse_quote! { #result_ty::Ok(#res) }
-                }
-                syn::Fields::Unit => parse_quote! {
-                    #result_ty::Ok(Self)
-                },
-            };
+            let res = generate_constructor(
+                &context,
+                FieldSource::Elements,
+                Some(label),
+                parse_quote! { Self },
+                &data.fields,
+            )?;
+            let res_expr: Expr = parse_quote! { #result_ty::Ok(#res) };
 
             parse_quote! {
                 #decode_sig {
@@ -0,0 +1,451 @@
+use proc_macro2::Span;
+use quote::quote;
+use syn::{
+    parse_quote, parse_quote_spanned, punctuated::Punctuated, spanned::Spanned, token::Comma,
+    DeriveInput, Expr, ExprStruct, FieldValue, Fields, Ident, ImplItemFn, Lifetime, LifetimeParam,
+    LitByteStr, LitStr, Path, Signature, Token, Type, WhereClause,
+};
+
+use crate::{
+    decoded_tys, dictionary_keys, dictionary_slot, max_elements, plan_fields, variant_labels,
+    Context, FieldSource, OuterAttr, PlannedField,
+};
+
+pub(crate) fn generate_decode_from(input: DeriveInput) -> syn::Result<proc_macro::TokenStream> {
+    fn generate_fields(
+        Context {
+            decode_error_ty,
+            result_ty,
+            ..
+        }: &Context,
+        read_error_ty: &Type,
+        source: FieldSource,
+        label: Option<&LitStr>,
+        fields: &[PlannedField<'_>],
+    ) -> Punctuated<FieldValue, Comma> {
+        let label = label.map(|label| quote! { .at_label(#label) });
+        let mut res = Punctuated::new();
+        for PlannedField {
+            field,
+            attr,
+            member,
+            expected,
+            at_field,
+        } in fields
+        {
+            let expr: Expr = if attr.skip {
+                attr.default.clone().unwrap_or_else(|| {
+                    parse_quote_spanned! {field.span()=>::std::default::Default::default()}
+                })
+            } else if attr.rest {
+                match source {
+                    FieldSource::Empty => {
+                        parse_quote_spanned! {field.span()=>::std::iter::empty().collect()}
+                    }
+                    _ => parse_quote_spanned! {field.span()=>{
+                        let mut index = 0;
+                        ::std::iter::from_fn(|| match reader.at_end() {
+                            #result_ty::Ok(true) => ::std::option::Option::None,
+                            #result_ty::Ok(false) => {
+                                let el = reader
+                                    .read()
+                                    .map_err(|e: #read_error_ty| e.at_index(index) #at_field);
+                                index += 1;
+                                ::std::option::Option::Some(el)
+                            }
+                            #result_ty::Err(e) => ::std::option::Option::Some(#result_ty::Err(e)),
+                        })
+                        .collect::<#result_ty<_, #read_error_ty>>()?
+                    }},
+                }
+            } else {
+                // fields overriding how they're decoded do so from a tree
+                let decoded: Expr = if let Some(decode) = &attr.decode {
+                    let dec = decode(&parse_quote! { el });
+                    parse_quote_spanned! {dec.span()=>{
+                        let el = &reader.read_tree()?;
+                        #dec.map_err(|e: #decode_error_ty| e #at_field)?
+                    }}
+                } else {
+                    parse_quote_spanned! {field.span()=>
+                        reader.read().map_err(|e: #read_error_ty| e #at_field)?
+                    }
+                };
+
+                let missing: Expr = match &attr.default {
+                    Some(default) => default.clone(),
+                    None => parse_quote_spanned! {field.span()=>
+                        return #result_ty::Err(#read_error_ty::from(
+                            #decode_error_ty::missing(#expected) #label
+                        ))
+                    },
+                };
+
+                match source {
+                    FieldSource::Elements => parse_quote_spanned! {field.span()=>
+                        if reader.at_end()? {
+                            #missing
+                        } else {
+                            #decoded
+                        }
+                    },
+                    FieldSource::Empty => missing,
+                    FieldSource::Input => decoded,
+                    FieldSource::Dictionary => {
+                        let slot = dictionary_slot(field);
+                        parse_quote_spanned! {field.span()=>
+                            match #slot {
+                                ::std::option::Option::Some(el) => el,
+                                ::std::option::Option::None => #missing,
+                            }
+                        }
+                    }
+                }
+            };
+
+            res.push(FieldValue {
+                attrs: Vec::with_capacity(0),
+                member: member.clone(),
+                colon_token: Some(Token![:](field.span())),
+                expr,
+            });
+        }
+        res
+    }
+
+    /// Construct `path` (either `Self` or `Self::Variant`) from the values read from `source`,
+    /// leaving the reader after the value they came from
+    ///
+    /// `label` is the label of the record being decoded, if any.
+    fn generate_constructor(
+        context: &Context,
+        read_error_ty: &Type,
+        source: FieldSource,
+        label: Option<&LitStr>,
+        path: Path,
+        fields: &Fields,
+    ) -> syn::Result<Expr> {
+        let planned = plan_fields(context, source, label, fields)?;
+        let ctor = match fields {
+            syn::Fields::Unit => parse_quote! { #path },
+            _ => Expr::Struct(ExprStruct {
+                attrs: Vec::with_capacity(0),
+                qself: None,
+                path,
+                brace_token: Default::default(),
+                fields: generate_fields(context, read_error_ty, source, label, &planned),
+                dot2_token: None,
+                rest: None,
+            }),
+        };
+        let Context {
+            decode_error_ty,
+            result_ty,
+            ..
+        } = context;
+        if !matches!(source, FieldSource::Elements) {
+            return Ok(ctor);
+        }
+        let Some(arity) = max_elements(context, &planned)? else {
+            // surplus elements are skipped without being parsed
+            return Ok(parse_quote! {{
+                let res = #ctor;
+                reader.skip_rest()?;
+                res
+            }});
+        };
+        let at_label = label.map(|label| quote! { .at_label(#label) });
+        Ok(parse_quote! {{
+            let res = #ctor;
+            let extra = reader.skip_rest()?;
+            if extra > 0 {
+                return #result_ty::Err(#read_error_ty::from(
+                    #decode_error_ty::extra_elements(#arity, #arity + extra) #at_label
+                ));
+            }
+            res
+        }})
+    }
+
+    let input_lt = Lifetime::new("'__input", Span::call_site());
+    let input_lt_param = LifetimeParam {
+        attrs: Vec::with_capacity(0),
+        lifetime: input_lt.clone(),
+        colon_token: Default::default(),
+        bounds: input
+            .generics
+            .lifetimes()
+            .map(|param| param.lifetime.clone())
+            .collect(),
+    };
+
+    let context = Context::new(OuterAttr::new(&input)?);
+
+    let id = input.ident;
+
+    let Context {
+        outer:
+            OuterAttr {
+                syrup,
+                label,
+                with,
+                transparent,
+                dictionary,
+                deny_unknown_keys,
+                decode_where,
+                ..
+            },
+        token_tree_ty,
+        decode_error_ty,
+        result_ty,
+        literal_ty,
+        ..
+    } = &context;
+    let read_error_ty: Type = parse_quote! { #syrup::de::ReadError };
+    let event_ty: Type = parse_quote! { #syrup::de::Event };
+
+    let impl_params = &input.generics.params;
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut where_clause = where_clause.cloned().unwrap_or(WhereClause {
+        where_token: Default::default(),
+        predicates: Default::default(),
+    });
+
+    // when decoding `with` another module, that module's `decode` is responsible for the fields
+    if decode_where.is_empty() && with.is_none() {
+        // skipped fields aren't decoded, fields overriding their decoding are decoded from a tree,
+        // and `rest` fields are decoded element by element
+        for ty in decoded_tys(&context, &input.data, true)? {
+            where_clause
+                .predicates
+                .push(parse_quote_spanned! {ty.span()=>
+                    #ty: #syrup::de::DecodeFrom<#input_lt>
+                });
+        }
+    }
+
+    for pred in decode_where {
+        where_clause.predicates.push(pred.clone());
+    }
+
+    let decode_sig: Signature = parse_quote! {
+        fn decode_from(reader: &mut #syrup::de::Reader<#input_lt>) -> #result_ty<Self, #read_error_ty>
+    };
+
+    let decode_fn: ImplItemFn = match input.data {
+        _ if with.is_some() => parse_quote! {
+            #decode_sig {
+                #result_ty::Ok(#with::decode(&reader.read_tree()?)?)
+            }
+        },
+        syn::Data::Struct(data) if *transparent => {
+            let res = generate_constructor(
+                &context,
+                &read_error_ty,
+                FieldSource::Input,
+                None,
+                parse_quote! { Self },
+                &data.fields,
+            )?;
+            parse_quote! {
+                #decode_sig {
+                    #result_ty::Ok(#res)
+                }
+            }
+        }
+        syn::Data::Struct(data) if *dictionary => {
+            let fields = dictionary_keys(&context, &data.fields)?;
+            let keys = fields.iter().map(|(_, _, key)| key);
+            let slots: Vec<Ident> = fields
+                .iter()
+                .map(|(field, _, _)| dictionary_slot(field))
+                .collect();
+            let mut arms: Vec<proc_macro2::TokenStream> = Vec::with_capacity(fields.len());
+            for ((field, attr, key), slot) in fields.iter().zip(&slots) {
+                let key_bytes = LitByteStr::new(key.value().as_bytes(), key.span());
+                let decoded: Expr = match &attr.decode {
+                    Some(decode) => {
+                        let dec = decode(&parse_quote! { el });
+                        parse_quote_spanned! {dec.span()=>{
+                            let el = &reader.read_tree()?;
+                            #dec.map_err(|e: #decode_error_ty| e.at_field(#key))?
+                        }}
+                    }
+                    None => parse_quote_spanned! {field.span()=>
+                        reader.read().map_err(|e: #read_error_ty| e.at_field(#key))?
+                    },
+                };
+                arms.push(quote! { #key_bytes => #slot = ::std::option::Option::Some(#decoded) });
+            }
+            let (non_symbol_key, unknown_key) = if *deny_unknown_keys {
+                (
+                    quote! {
+                        return #result_ty::Err(#read_error_ty::from(#decode_error_ty::unexpected(
+                            #syrup::de::SyrupKind::Symbol(::std::option::Option::None),
+                            &key,
+                        )))
+                    },
+                    quote! {
+                        found => return #result_ty::Err(#read_error_ty::from(
+                            #decode_error_ty::unknown_key(KEYS, found)
+                        ))
+                    },
+                )
+            } else {
+                // the values of keys we don't know are skipped without being parsed
+                (
+                    quote! {{
+                        reader.skip_value()?;
+                        continue;
+                    }},
+                    quote! { _ => reader.skip_value()? },
+                )
+            };
+            let res = generate_constructor(
+                &context,
+                &read_error_ty,
+                FieldSource::Dictionary,
+                None,
+                parse_quote! { Self },
+                &data.fields,
+            )?;
+
+            parse_quote! {
+                #decode_sig {
+                    const KEYS: &[&::std::primitive::str] = &[#(#keys),*];
+                    reader.begin_dictionary(#syrup::de::SyrupKind::Dictionary)?;
+                    #(let mut #slots = ::std::option::Option::None;)*
+                    while !reader.at_end()? {
+                        let key = reader.read_tree()?;
+                        let #token_tree_ty::Literal(#literal_ty::Symbol(key_sym)) = key else {
+                            #non_symbol_key
+                        };
+                        match key_sym {
+                            #(#arms,)*
+                            #unknown_key
+                        }
+                    }
+                    reader.skip_rest()?;
+                    #result_ty::Ok(#res)
+                }
+            }
+        }
+        syn::Data::Union(u) => {
+            return Err(syn::Error::new_spanned(
+                u.union_token,
+                "not yet implemented: union decode derivation",
+            ))
+        }
+        syn::Data::Enum(data) => {
+            let variants = variant_labels(&data)?;
+            let labels = variants.iter().map(|(_, label)| label);
+            let mut record_arms: Vec<proc_macro2::TokenStream> = Vec::with_capacity(variants.len());
+            let mut symbol_arms: Vec<proc_macro2::TokenStream> = Vec::with_capacity(variants.len());
+            for (variant, label) in &variants {
+                let label_bytes = LitByteStr::new(label.value().as_bytes(), label.span());
+                let var_id = &variant.ident;
+                let ctor = generate_constructor(
+                    &context,
+                    &read_error_ty,
+                    FieldSource::Elements,
+                    Some(label),
+                    parse_quote! { Self::#var_id },
+                    &variant.fields,
+                )?;
+                record_arms.push(quote! { #label_bytes => #result_ty::Ok(#ctor) });
+                // a bare symbol can only stand in for a variant whose fields may all be missing
+                let planned =
+                    plan_fields(&context, FieldSource::Empty, Some(label), &variant.fields)?;
+                let required = planned.iter().find(|PlannedField { attr, .. }| {
+                    !(attr.skip || attr.rest || attr.default.is_some())
+                });
+                let res: Expr = match required {
+                    Some(PlannedField { expected, .. }) => parse_quote! {
+                        #result_ty::Err(#read_error_ty::from(
+                            #decode_error_ty::missing(#expected).at_label(#label)
+                        ))
+                    },
+                    None => {
+                        let ctor = generate_constructor(
+                            &context,
+                            &read_error_ty,
+                            FieldSource::Empty,
+                            Some(label),
+                            parse_quote! { Self::#var_id },
+                            &variant.fields,
+                        )?;
+                        parse_quote! { #result_ty::Ok(#ctor) }
+                    }
+                };
+                symbol_arms.push(quote! { #label_bytes => #res });
+            }
+
+            parse_quote! {
+                #decode_sig {
+                    const LABELS: &[&::std::primitive::str] = &[#(#labels),*];
+                    // unit variants may also be encoded as bare symbols, so treat a symbol as if it
+                    // were an empty record
+                    if let ::std::option::Option::Some(#event_ty::Literal(#literal_ty::Symbol(label_sym))) = reader.peek_event()? {
+                        reader.next_event()?;
+                        return match label_sym {
+                            #(#symbol_arms,)*
+                            found => #result_ty::Err(#read_error_ty::from(
+                                #decode_error_ty::unexpected_label(LABELS, found)
+                            )),
+                        };
+                    }
+                    let label = reader.begin_record(
+                        #syrup::de::SyrupKind::Record { label: ::std::option::Option::None },
+                    )?;
+                    let #token_tree_ty::Literal(#literal_ty::Symbol(label_sym)) = label else {
+                        return #result_ty::Err(#read_error_ty::from(#decode_error_ty::unexpected(
+                            #syrup::de::SyrupKind::Symbol(::std::option::Option::None),
+                            &label,
+                        )))
+                    };
+                    match label_sym {
+                        #(#record_arms,)*
+                        found => #result_ty::Err(#read_error_ty::from(
+                            #decode_error_ty::unexpected_label(LABELS, found)
+                        )),
+                    }
+                }
+            }
+        }
+        syn::Data::Struct(data) => {
+            let label_bytes = LitByteStr::new(label.value().as_bytes(), label.span());
+            let res = generate_constructor(
+                &context,
+                &read_error_ty,
+                FieldSource::Elements,
+                Some(label),
+                parse_quote! { Self },
+                &data.fields,
+            )?;
+
+            parse_quote! {
+                #decode_sig {
+                    let label = reader.begin_record(
+                        #syrup::de::SyrupKind::Record { label: ::std::option::Option::Some(#label) },
+                    )?;
+                    if !matches!(label, #token_tree_ty::Literal(#literal_ty::Symbol(#label_bytes))) {
+                        return #result_ty::Err(#read_error_ty::from(#decode_error_ty::unexpected(
+                            #syrup::de::SyrupKind::Symbol(::std::option::Option::Some(#label)),
+                            &label,
+                        )));
+                    }
+                    #result_ty::Ok(#res)
+                }
+            }
+        }
+    };
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl<#input_lt_param, #impl_params> #syrup::de::DecodeFrom<#input_lt> for #id #ty_generics #where_clause {
+            #decode_fn
+        }
+    }
+    .into())
+}
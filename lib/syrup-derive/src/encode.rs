@@ -2,58 +2,152 @@ use proc_macro2::Span;
 use quote::quote;
 use syn::{
     parse_quote, parse_quote_spanned, spanned::Spanned, DeriveInput, Expr, Field, GenericParam,
-    Ident, Index, Lifetime, LifetimeParam, PredicateType, Token, Type, TypeParam, TypeReference,
-    WhereClause,
+    Ident, Index, Lifetime, LifetimeParam, LitStr, PredicateType, Token, Type, TypeParam,
+    TypeReference, WhereClause,
 };
 
-use crate::{Context, FieldAttr, OuterAttr};
+use crate::{rest_element_ty, Context, FieldAttr, OuterAttr, VariantAttr};
+
+/// The identifier a field is bound to when destructuring an enum variant
+fn field_binding(i: usize, field: &Field) -> Ident {
+    match &field.ident {
+        Some(id) => id.clone(),
+        None => Ident::new(&format!("__{i}"), field.span()),
+    }
+}
 
 pub(crate) fn generate_encode(input: DeriveInput) -> syn::Result<proc_macro::TokenStream> {
+    /// If `bound`, fields are accessed through the bindings created by [`field_binding`] rather
+    /// than through `self`. Skipped fields are left out.
     fn generate_fields<'f, Fields>(
         input_lt: &'f Lifetime,
         context @ Context { .. }: &'f Context,
         fields: Fields,
-    ) -> impl Iterator<Item = syn::Result<Expr>> + 'f + use<'f, Fields>
+        bound: bool,
+    ) -> impl Iterator<Item = syn::Result<(&'f Field, Expr)>> + 'f + use<'f, Fields>
     where
         Fields: IntoIterator<Item = &'f Field>,
         <Fields as IntoIterator>::IntoIter: 'f,
     {
-        fields.into_iter().enumerate().map(|(i, field)| {
-            let Field { ident, .. } = field;
-            let attr = FieldAttr::new(context, field)?;
-            let member = match ident.as_ref() {
-                Some(id) => syn::Member::Named(id.clone()),
-                None => syn::Member::Unnamed(Index {
-                    index: i as u32,
-                    span: field.span(),
-                }),
-            };
-            let (access, accessed_ty): (Expr, Type) = {
-                (
-                    parse_quote_spanned! {field.span()=>&self.#member},
-                    Type::Reference(TypeReference {
-                        and_token: Token![&](field.span()),
-                        lifetime: Some(input_lt.clone()),
-                        mutability: None,
-                        elem: Box::new(field.ty.clone()),
+        fields
+            .into_iter()
+            .enumerate()
+            .filter_map(move |(i, field)| {
+                let Field { ident, .. } = field;
+                let attr = match FieldAttr::new(context, field) {
+                    Ok(attr) if attr.skip => return None,
+                    Ok(attr) => attr,
+                    Err(e) => return Some(Err(e)),
+                };
+                let member = match ident.as_ref() {
+                    Some(id) => syn::Member::Named(id.clone()),
+                    None => syn::Member::Unnamed(Index {
+                        index: i as u32,
+                        span: field.span(),
                     }),
-                )
-            };
-            if let Some(encode) = attr.encode {
-                Ok(encode(&access, &accessed_ty))
+                };
+                let (access, accessed_ty): (Expr, Type) = {
+                    (
+                        if bound {
+                            let binding = field_binding(i, field);
+                            parse_quote_spanned! {field.span()=>#binding}
+                        } else {
+                            parse_quote_spanned! {field.span()=>&self.#member}
+                        },
+                        Type::Reference(TypeReference {
+                            and_token: Token![&](field.span()),
+                            lifetime: Some(input_lt.clone()),
+                            mutability: None,
+                            elem: Box::new(field.ty.clone()),
+                        }),
+                    )
+                };
+                let expr = if attr.rest {
+                    // an iterator over the encoded elements, rather than a single element
+                    parse_quote_spanned! {field.span()=>
+                        ::std::iter::IntoIterator::into_iter(#access).map(|el| el.encode())
+                    }
+                } else if let Some(encode) = attr.encode {
+                    encode(&access, &accessed_ty)
+                } else if bound {
+                    parse_quote_spanned! {field.span()=>#access.encode()}
+                } else {
+                    parse_quote_spanned! {field.span()=>self.#member.encode()}
+                };
+                Some(Ok((field, expr)))
+            })
+    }
+
+    /// `<label fields...>`
+    fn generate_record(
+        input_lt: &Lifetime,
+        context: &Context,
+        label: &LitStr,
+        fields: &syn::Fields,
+        bound: bool,
+    ) -> syn::Result<Expr> {
+        let syrup = &context.outer.syrup;
+        let mut res = Vec::with_capacity(fields.len());
+        let mut rest: Option<Expr> = None;
+        for field in generate_fields(input_lt, context, fields, bound) {
+            let (field, expr) = field?;
+            if FieldAttr::new(context, field)?.rest {
+                rest = Some(expr);
             } else {
-                Ok(parse_quote_spanned! {field.span()=>self.#member.encode()})
+                res.push(expr);
             }
+        }
+        let elements: Expr = match rest {
+            Some(rest) => parse_quote! {{
+                let mut elements = ::std::vec![#(#res),*];
+                elements.extend(#rest);
+                elements
+            }},
+            None => parse_quote! { ::std::vec![#(#res),*] },
+        };
+        Ok(parse_quote! {
+            #syrup::TokenTree::Record(::std::boxed::Box::new(#syrup::de::Record {
+                label: #syrup::TokenTree::Literal(#syrup::de::Literal::Symbol(#label.as_bytes().into())),
+                elements: #elements,
+            }))
         })
     }
 
-    let context = Context::new(OuterAttr::new(&input.ident, &input.attrs)?);
+    /// `{key: field...}`, keyed by [`FieldAttr::key`]
+    fn generate_dictionary(
+        input_lt: &Lifetime,
+        context: &Context,
+        fields: &syn::Fields,
+    ) -> syn::Result<Expr> {
+        let syrup = &context.outer.syrup;
+        let mut res = Vec::with_capacity(fields.len());
+        for field in generate_fields(input_lt, context, fields, false) {
+            let (field, value) = field?;
+            let key = FieldAttr::new(context, field)?.key(field);
+            res.push(quote! {
+                (
+                    #syrup::TokenTree::Literal(#syrup::de::Literal::Symbol(#key.as_bytes().into())),
+                    #value,
+                )
+            });
+        }
+        Ok(parse_quote! {
+            #syrup::TokenTree::Dictionary(#syrup::de::Dictionary::new(::std::vec![
+                #(#res),*
+            ]))
+        })
+    }
+
+    let context = Context::new(OuterAttr::new(&input)?);
 
     let Context {
         outer:
             OuterAttr {
                 syrup,
                 label,
+                with,
+                transparent,
+                dictionary,
                 encode_where,
                 ..
             },
@@ -105,44 +199,46 @@ pub(crate) fn generate_encode(input: DeriveInput) -> syn::Result<proc_macro::Tok
         predicates: Default::default(),
     });
 
-    // default where predicates (for each field in self, `Field: Encode`)
-    if encode_where.is_empty() {
-        // ensure label can be converted into odata
-        where_clause
-            .predicates
-            .push(syn::WherePredicate::Type(PredicateType {
-                lifetimes: None,
-                bounded_ty: parse_quote! { &#label_lt [u8] },
-                colon_token: Token![:](Span::call_site()),
-                bounds: parse_quote! { ::std::convert::Into<#odata_ty> },
-            }));
-
-        match &input.data {
-            syn::Data::Enum(_) => todo!("derive(Encode) for enums"),
-            syn::Data::Union(_) => todo!("derive(Encode) for unions"),
-            syn::Data::Struct(data) => match &data.fields {
-                syn::Fields::Unit => Default::default(),
-                syn::Fields::Named(fields) => {
-                    for field in &fields.named {
-                        let ty = &field.ty;
-                        where_clause
-                            .predicates
-                            .push(parse_quote_spanned! {ty.span()=>
-                                #ty: #syrup::Encode<#input_lt, #odata_ty>
-                            });
-                    }
-                }
-                syn::Fields::Unnamed(fields) => {
-                    for field in &fields.unnamed {
-                        let ty = &field.ty;
-                        where_clause
-                            .predicates
-                            .push(parse_quote_spanned! {ty.span()=>
-                                #ty: #syrup::Encode<#input_lt, #odata_ty>
-                            });
-                    }
-                }
-            },
+    // default where predicates (for each field in self, `Field: Encode`); when encoding `with`
+    // another module, that module's `encode` is responsible for the fields
+    if encode_where.is_empty() && with.is_none() {
+        // ensure label (or dictionary keys) can be converted into odata
+        if !transparent {
+            where_clause
+                .predicates
+                .push(syn::WherePredicate::Type(PredicateType {
+                    lifetimes: None,
+                    bounded_ty: parse_quote! { &#label_lt [u8] },
+                    colon_token: Token![:](Span::call_site()),
+                    bounds: parse_quote! { ::std::convert::Into<#odata_ty> },
+                }));
+        }
+
+        let fields: Vec<&Field> = match &input.data {
+            syn::Data::Enum(data) => data
+                .variants
+                .iter()
+                .flat_map(|variant| &variant.fields)
+                .collect(),
+            syn::Data::Union(_) => Vec::with_capacity(0),
+            syn::Data::Struct(data) => data.fields.iter().collect(),
+        };
+        for field in fields {
+            // skipped fields aren't encoded, and `rest` fields are encoded element by element
+            let attr = FieldAttr::new(&context, field)?;
+            let ty = match (attr.skip, attr.rest) {
+                (true, _) => continue,
+                (false, true) => match rest_element_ty(field) {
+                    Some(ty) => ty,
+                    None => continue,
+                },
+                (false, false) => &field.ty,
+            };
+            where_clause
+                .predicates
+                .push(parse_quote_spanned! {ty.span()=>
+                    #ty: #syrup::Encode<#input_lt, #odata_ty>
+                });
         }
     }
 
@@ -150,47 +246,82 @@ pub(crate) fn generate_encode(input: DeriveInput) -> syn::Result<proc_macro::Tok
         where_clause.predicates.push(pred.clone());
     }
 
-    match input.data {
-        syn::Data::Union(u) => Err(syn::Error::new_spanned(
-            u.union_token,
-            "not yet implemented: union encode derivation",
-        )),
-        syn::Data::Enum(data) => Err(syn::Error::new_spanned(
-            data.enum_token,
-            "not yet implemented: enum encode derivation",
-        )),
-        syn::Data::Struct(data) => {
-            let fields: Vec<Expr> = match &data.fields {
-                syn::Fields::Named(fields) => {
-                    let mut res = Vec::with_capacity(fields.named.len());
-                    for field in generate_fields(&input_lt, &context, &fields.named) {
-                        res.push(field?);
-                    }
-                    res
-                }
-                syn::Fields::Unnamed(fields) => {
-                    let mut res = Vec::with_capacity(fields.unnamed.len());
-                    for field in generate_fields(&input_lt, &context, &fields.unnamed) {
-                        res.push(field?);
-                    }
-                    res
+    // dictionaries sort their entries by encoding, so need to read the encoded keys
+    if *dictionary && with.is_none() && !transparent {
+        where_clause.predicates.push(parse_quote! {
+            #odata_ty: #syrup::borrow_or_share::Bos<[u8]>
+        });
+    }
+
+    let encode_expr: Expr = match input.data {
+        _ if with.is_some() => parse_quote! { #with::encode(self) },
+        syn::Data::Struct(data) if *transparent => {
+            // `OuterAttr::new` has already checked that there's exactly one field
+            generate_fields(&input_lt, &context, &data.fields, false)
+                .next()
+                .ok_or_else(|| {
+                    syn::Error::new_spanned(&data.fields, "expected exactly one field")
+                })??
+                .1
+        }
+        syn::Data::Struct(data) if *dictionary => {
+            generate_dictionary(&input_lt, &context, &data.fields)?
+        }
+        syn::Data::Union(u) => {
+            return Err(syn::Error::new_spanned(
+                u.union_token,
+                "not yet implemented: union encode derivation",
+            ))
+        }
+        syn::Data::Enum(data) => {
+            let mut arms: Vec<proc_macro2::TokenStream> = Vec::with_capacity(data.variants.len());
+            for variant in &data.variants {
+                let VariantAttr { label, symbol } = VariantAttr::new(variant)?;
+                let var_id = &variant.ident;
+                let mut bindings = Vec::with_capacity(variant.fields.len());
+                for (i, field) in variant.fields.iter().enumerate() {
+                    let binding = field_binding(i, field);
+                    // don't bind skipped fields, so they aren't reported as unused
+                    bindings.push(
+                        match (FieldAttr::new(&context, field)?.skip, &field.ident) {
+                            (false, _) => quote! { #binding },
+                            (true, Some(_)) => quote! { #binding: _ },
+                            (true, None) => quote! { _ },
+                        },
+                    );
                 }
-                syn::Fields::Unit => Vec::<Expr>::with_capacity(0),
-            };
-            let res = quote! {
-                #[automatically_derived]
-                impl<#label_lt_param, #input_lt_param, #output_lt_param, #impl_params> #syrup::Encode<#input_lt, #odata_ty> for #id #ty_generics #where_clause {
-                    fn encode(&#input_lt self) -> #syrup::TokenTree<#odata_ty> {
-                        #syrup::TokenTree::Record(::std::boxed::Box::new(#syrup::de::Record {
-                            label: #syrup::TokenTree::Literal(#syrup::de::Literal::Symbol(#label.as_bytes().into())),
-                            elements: ::std::vec![
-                                #(#fields),*
-                            ]
-                        }))
+                let pattern = match &variant.fields {
+                    syn::Fields::Named(_) => quote! { Self::#var_id { #(#bindings),* } },
+                    syn::Fields::Unnamed(_) => quote! { Self::#var_id ( #(#bindings),* ) },
+                    syn::Fields::Unit => quote! { Self::#var_id },
+                };
+                let res = if symbol {
+                    parse_quote! {
+                        #syrup::TokenTree::Literal(#syrup::de::Literal::Symbol(#label.as_bytes().into()))
                     }
+                } else {
+                    generate_record(&input_lt, &context, &label, &variant.fields, true)?
+                };
+                arms.push(quote! { #pattern => #res });
+            }
+            parse_quote! {
+                match self {
+                    #(#arms,)*
                 }
-            };
-            Ok(res.into())
+            }
+        }
+        syn::Data::Struct(data) => {
+            generate_record(&input_lt, &context, label, &data.fields, false)?
+        }
+    };
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl<#label_lt_param, #input_lt_param, #output_lt_param, #impl_params> #syrup::Encode<#input_lt, #odata_ty> for #id #ty_generics #where_clause {
+            fn encode(&#input_lt self) -> #syrup::TokenTree<#odata_ty> {
+                #encode_expr
+            }
         }
     }
+    .into())
 }
@@ -1,19 +1,31 @@
+use proc_macro2::Span;
+use quote::{format_ident, quote};
 use syn::{
     braced, parse_macro_input, parse_quote, parse_quote_spanned, punctuated::Punctuated,
-    spanned::Spanned, Attribute, DeriveInput, Expr, Field, Ident, LitStr, Path, Token, Type,
-    TypePath, WherePredicate,
+    spanned::Spanned, DataEnum, DeriveInput, Expr, Field, Fields, Ident, Index, LitStr, Member,
+    Path, Token, Type, TypePath, Variant, WherePredicate,
 };
 
 mod decode;
 pub(crate) use decode::*;
 
+mod decode_from;
+pub(crate) use decode_from::*;
+
 mod encode;
 pub(crate) use encode::*;
 
 struct OuterAttr {
     syrup: Path,
     label: LitStr,
-    // with: Option<Path>,
+    with: Option<Path>,
+    transparent: bool,
+    /// Encode/decode the struct as a dictionary keyed by field name rather than as a record
+    dictionary: bool,
+    /// Whether decoding a `dictionary` struct fails on keys that don't name a field
+    deny_unknown_keys: bool,
+    /// Whether decoding fails on records with more elements than there are fields
+    deny_extra: bool,
     decode_where: Punctuated<WherePredicate, Token![,]>,
     encode_where: Punctuated<WherePredicate, Token![,]>,
 }
@@ -46,18 +58,30 @@ impl Context {
 }
 
 impl OuterAttr {
-    fn new(ident: &Ident, attrs: &[Attribute]) -> syn::Result<Self> {
+    fn new(
+        DeriveInput {
+            ident, attrs, data, ..
+        }: &DeriveInput,
+    ) -> syn::Result<Self> {
         let mut label: Option<LitStr> = None;
         let mut syrup: Option<Path> = None;
         let mut with: Option<Path> = None;
         let mut decode_where: Option<Punctuated<WherePredicate, Token![,]>> = None;
         let mut encode_where: Option<Punctuated<WherePredicate, Token![,]>> = None;
-        let mut transparent = false;
+        let mut transparent: Option<Span> = None;
+        let mut dictionary: Option<Span> = None;
+        let mut deny_unknown_keys: Option<Span> = None;
+        let mut deny_extra: Option<Span> = None;
         for attr in attrs.iter() {
             if attr.path().is_ident("syrup") {
                 attr.parse_nested_meta(|meta| {
                     // the string to use as the record label
                     if meta.path.is_ident("label") {
+                        if let syn::Data::Enum(_) = data {
+                            return Err(meta.error(
+                                "`label` may not be used on enums; apply it to each variant instead",
+                            ));
+                        }
                         label = Some(meta.value()?.parse()?);
                         Ok(())
                     // use the given module or type's encode/decode functions instead of generating
@@ -73,7 +97,21 @@ impl OuterAttr {
                     // when applied to a struct with a single field, encode/decode it as if it were
                     // that field
                     } else if meta.path.is_ident("transparent") {
-                        transparent = true;
+                        transparent = Some(meta.path.span());
+                        Ok(())
+                    // encode/decode a struct with named fields as a dictionary with symbol keys
+                    } else if meta.path.is_ident("dictionary") {
+                        dictionary = Some(meta.path.span());
+                        Ok(())
+                    // when decoding a `dictionary` struct, fail on keys that don't name a field
+                    // instead of ignoring them
+                    } else if meta.path.is_ident("deny_unknown_keys") {
+                        deny_unknown_keys = Some(meta.path.span());
+                        Ok(())
+                    // fail to decode records with more elements than there are fields, instead of
+                    // ignoring the surplus
+                    } else if meta.path.is_ident("deny_extra") {
+                        deny_extra = Some(meta.path.span());
                         Ok(())
                     } else if meta.path.is_ident("decode_where") {
                         let content;
@@ -91,15 +129,356 @@ impl OuterAttr {
                 })?;
             }
         }
+        if let Some(span) = transparent {
+            match data {
+                syn::Data::Struct(data) if data.fields.len() == 1 => {}
+                syn::Data::Struct(_) => {
+                    return Err(syn::Error::new(
+                        span,
+                        "`transparent` requires a struct with exactly one field",
+                    ))
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        span,
+                        "`transparent` may only be used on structs",
+                    ))
+                }
+            }
+            if let Some(with) = &with {
+                return Err(syn::Error::new_spanned(
+                    with,
+                    "`with` may not be combined with `transparent`",
+                ));
+            }
+            if let Some(label) = &label {
+                return Err(syn::Error::new_spanned(
+                    label,
+                    "`label` has no effect on `transparent` structs",
+                ));
+            }
+        }
+        if let Some(span) = deny_extra
+            && (transparent.is_some() || dictionary.is_some() || with.is_some())
+        {
+            return Err(syn::Error::new(
+                span,
+                "`deny_extra` may only be used on types encoded as records",
+            ));
+        }
+        if let Some(span) = dictionary {
+            match data {
+                syn::Data::Struct(syn::DataStruct {
+                    fields: Fields::Named(_),
+                    ..
+                }) => {}
+                _ => {
+                    return Err(syn::Error::new(
+                        span,
+                        "`dictionary` requires a struct with named fields",
+                    ))
+                }
+            }
+            if transparent.is_some() {
+                return Err(syn::Error::new(
+                    span,
+                    "`dictionary` may not be combined with `transparent`",
+                ));
+            }
+            if let Some(with) = &with {
+                return Err(syn::Error::new_spanned(
+                    with,
+                    "`with` may not be combined with `dictionary`",
+                ));
+            }
+            if let Some(label) = &label {
+                return Err(syn::Error::new_spanned(
+                    label,
+                    "`label` has no effect on `dictionary` structs",
+                ));
+            }
+        } else if let Some(span) = deny_unknown_keys {
+            return Err(syn::Error::new(
+                span,
+                "`deny_unknown_keys` requires `dictionary`",
+            ));
+        }
         Ok(Self {
             label: label.unwrap_or_else(|| LitStr::new(ident.to_string().as_str(), ident.span())),
             syrup: syrup.unwrap_or_else(|| parse_quote! { ::syrup }),
+            with,
+            transparent: transparent.is_some(),
+            dictionary: dictionary.is_some(),
+            deny_unknown_keys: deny_unknown_keys.is_some(),
+            deny_extra: deny_extra.is_some(),
             decode_where: decode_where.unwrap_or_default(),
             encode_where: encode_where.unwrap_or_default(),
         })
     }
 }
 
+struct VariantAttr {
+    label: LitStr,
+    /// Whether this (unit) variant encodes as a bare symbol instead of an empty record
+    symbol: bool,
+}
+
+impl VariantAttr {
+    fn new(variant: &Variant) -> syn::Result<Self> {
+        let mut label: Option<LitStr> = None;
+        let mut symbol = false;
+        for attr in variant.attrs.iter() {
+            if attr.path().is_ident("syrup") {
+                attr.parse_nested_meta(|meta| {
+                    // the string to use as the record label
+                    if meta.path.is_ident("label") {
+                        label = Some(meta.value()?.parse()?);
+                        Ok(())
+                    // encode this variant as a bare symbol rather than as `<label>`
+                    } else if meta.path.is_ident("symbol") {
+                        if !matches!(variant.fields, Fields::Unit) {
+                            return Err(meta.error("`symbol` may only be used on unit variants"));
+                        }
+                        symbol = true;
+                        Ok(())
+                    } else {
+                        Err(meta.error("unrecognized syrup attribute"))
+                    }
+                })?;
+            }
+        }
+        let ident = &variant.ident;
+        Ok(Self {
+            label: label.unwrap_or_else(|| LitStr::new(ident.to_string().as_str(), ident.span())),
+            symbol,
+        })
+    }
+}
+
+/// The element type of a `rest` field, if it's spelled like a collection (`Vec<T>`, etc.), so that
+/// bounds can be placed on the elements rather than on the collection.
+fn rest_element_ty(field: &Field) -> Option<&Type> {
+    let Type::Path(TypePath { path, .. }) = &field.ty else {
+        return None;
+    };
+    let syn::PathArguments::AngleBracketed(args) = &path.segments.last()?.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        syn::GenericArgument::Type(ty) if args.args.len() == 1 => Some(ty),
+        _ => None,
+    }
+}
+
+/// Where the derived decode implementations find the syrup value for each field
+#[derive(Clone, Copy)]
+enum FieldSource {
+    /// The field's positional element of the record
+    Elements,
+    /// Nowhere; a bare symbol standing in for an empty record has no elements, so every field
+    /// takes its default
+    Empty,
+    /// The entire value (for `transparent` structs)
+    Input,
+    /// The value collected for the field's key into its [`dictionary_slot`]
+    Dictionary,
+}
+
+/// The local variable holding the value found for `field`'s key while decoding a `dictionary`
+/// struct
+fn dictionary_slot(field: &Field) -> Ident {
+    format_ident!(
+        "__field_{}",
+        field
+            .ident
+            .as_ref()
+            .map_or_else(String::new, Ident::to_string)
+    )
+}
+
+/// A field to decode, along with what's the same however it's decoded
+struct PlannedField<'f> {
+    field: &'f Field,
+    attr: FieldAttr,
+    member: Member,
+    /// What's reported missing when the field is absent
+    expected: Expr,
+    /// Records where an error decoding the field happened
+    at_field: proc_macro2::TokenStream,
+}
+
+/// Plan how each of `fields` is decoded from `source`, checking that only skipped fields follow a
+/// `rest` field, and only `default` elements follow a `default` element.
+///
+/// `label` is the label of the record being decoded, if any.
+fn plan_fields<'f>(
+    context: &Context,
+    source: FieldSource,
+    label: Option<&LitStr>,
+    fields: impl IntoIterator<Item = &'f Field>,
+) -> syn::Result<Vec<PlannedField<'f>>> {
+    let syrup = &context.outer.syrup;
+    let at_label = label.map(|label| quote! { .at_label(#label) });
+    let mut res = Vec::new();
+    let mut trailing_default: Option<&Field> = None;
+    let mut rest: Option<&Field> = None;
+    for (i, field) in fields.into_iter().enumerate() {
+        let attr = FieldAttr::new(context, field)?;
+        let (member, name, description) = match &field.ident {
+            Some(id) => {
+                let id_str = LitStr::new(&id.to_string(), id.span());
+                (Member::Named(id.clone()), id_str.clone(), id_str)
+            }
+            None => (
+                Member::Unnamed(Index {
+                    index: i as u32,
+                    span: field.span(),
+                }),
+                LitStr::new(&i.to_string(), field.span()),
+                LitStr::new(&format!("{i}th field"), field.span()),
+            ),
+        };
+        let (expected, at_field) = match source {
+            FieldSource::Dictionary => {
+                let key = attr.key(field);
+                (
+                    parse_quote_spanned! {field.span()=>
+                        #syrup::de::SyrupKind::Symbol(::std::option::Option::Some(#key))
+                    },
+                    quote! { .at_field(#key) },
+                )
+            }
+            FieldSource::Input => (
+                parse_quote_spanned! {field.span()=>#syrup::de::SyrupKind::Unknown(#description)},
+                quote! {},
+            ),
+            FieldSource::Elements | FieldSource::Empty => (
+                parse_quote_spanned! {field.span()=>#syrup::de::SyrupKind::Unknown(#description)},
+                quote! { .at_field(#name) #at_label },
+            ),
+        };
+
+        if let (Some(prev), false) = (rest, attr.skip) {
+            let mut err = syn::Error::new_spanned(field, "`rest` must be the last field");
+            err.combine(syn::Error::new_spanned(prev, "`rest` used here"));
+            return Err(err);
+        }
+        if attr.rest {
+            rest = Some(field);
+        } else if !attr.skip && matches!(source, FieldSource::Elements | FieldSource::Empty) {
+            // once an element may be missing, all elements following it may be too
+            match (&trailing_default, &attr.default) {
+                (None, Some(_)) => trailing_default = Some(field),
+                (Some(prev), None) => {
+                    let mut err = syn::Error::new_spanned(
+                        field,
+                        "fields following a `default` field must also be `default`",
+                    );
+                    err.combine(syn::Error::new_spanned(prev, "`default` used here"));
+                    return Err(err);
+                }
+                _ => {}
+            }
+        }
+
+        res.push(PlannedField {
+            field,
+            attr,
+            member,
+            expected,
+            at_field,
+        });
+    }
+    Ok(res)
+}
+
+/// How many elements a record decoded into `fields` may have, if `deny_extra` limits it.
+fn max_elements(context: &Context, fields: &[PlannedField<'_>]) -> syn::Result<Option<usize>> {
+    if !context.outer.deny_extra {
+        return Ok(None);
+    }
+    if let Some(rest) = fields.iter().find(|planned| planned.attr.rest) {
+        return Err(syn::Error::new_spanned(
+            rest.field,
+            "`deny_extra` has no effect on a type with a `rest` field",
+        ));
+    }
+    Ok(Some(
+        fields.iter().filter(|planned| !planned.attr.skip).count(),
+    ))
+}
+
+/// The fields of a `dictionary` struct that are decoded, along with their keys, checking that no
+/// two share a key.
+fn dictionary_keys<'f>(
+    context: &Context,
+    fields: &'f Fields,
+) -> syn::Result<Vec<(&'f Field, FieldAttr, LitStr)>> {
+    let mut res: Vec<(&Field, FieldAttr, LitStr)> = Vec::with_capacity(fields.len());
+    for field in fields {
+        let attr = FieldAttr::new(context, field)?;
+        if attr.skip {
+            continue;
+        }
+        let key = attr.key(field);
+        if let Some((_, _, prev)) = res.iter().find(|(_, _, prev)| prev.value() == key.value()) {
+            let mut err = syn::Error::new_spanned(&key, "duplicate dictionary key");
+            err.combine(syn::Error::new_spanned(prev, "first used here"));
+            return Err(err);
+        }
+        res.push((field, attr, key));
+    }
+    Ok(res)
+}
+
+/// The variants of an enum, along with their labels, checking that no two share a label.
+fn variant_labels(data: &DataEnum) -> syn::Result<Vec<(&Variant, LitStr)>> {
+    let mut res: Vec<(&Variant, LitStr)> = Vec::with_capacity(data.variants.len());
+    for variant in &data.variants {
+        let VariantAttr { label, .. } = VariantAttr::new(variant)?;
+        if let Some((_, prev)) = res.iter().find(|(_, prev)| prev.value() == label.value()) {
+            let mut err = syn::Error::new_spanned(&label, "duplicate variant label");
+            err.combine(syn::Error::new_spanned(prev, "first used here"));
+            return Err(err);
+        }
+        res.push((variant, label));
+    }
+    Ok(res)
+}
+
+/// The types of the fields of `data` that are decoded by their own implementation (the element
+/// type, for `rest` fields), and so need bounds.
+///
+/// Skipped fields are left out, as are those with their own `decode` if `skip_overridden`.
+fn decoded_tys<'d>(
+    context: &Context,
+    data: &'d syn::Data,
+    skip_overridden: bool,
+) -> syn::Result<Vec<&'d Type>> {
+    let fields: Vec<&Field> = match data {
+        syn::Data::Enum(data) => data
+            .variants
+            .iter()
+            .flat_map(|variant| &variant.fields)
+            .collect(),
+        syn::Data::Union(_) => Vec::with_capacity(0),
+        syn::Data::Struct(data) => data.fields.iter().collect(),
+    };
+    let mut res = Vec::with_capacity(fields.len());
+    for field in fields {
+        let attr = FieldAttr::new(context, field)?;
+        if attr.skip || (skip_overridden && attr.decode.is_some()) {
+            continue;
+        }
+        if !attr.rest {
+            res.push(&field.ty);
+        } else if let Some(ty) = rest_element_ty(field) {
+            res.push(ty);
+        }
+    }
+    Ok(res)
+}
+
 pub(crate) trait FieldEncodeTransform: Fn(&Expr, &Type) -> Expr {}
 impl<F> FieldEncodeTransform for F where F: Fn(&Expr, &Type) -> Expr {}
 
@@ -109,12 +488,25 @@ impl<F> FieldDecodeTransform for F where F: Fn(&Expr) -> Expr {}
 struct FieldAttr {
     decode: Option<Box<dyn FieldDecodeTransform>>,
     encode: Option<Box<dyn FieldEncodeTransform>>,
+    rename: Option<LitStr>,
+    /// Value to use when the field is absent from the input
+    default: Option<Expr>,
+    /// Never encode this field, and always decode it as its `default`
+    skip: bool,
+    /// Capture (and encode) any elements following the other fields
+    rest: bool,
 }
 
 impl FieldAttr {
     fn new(
         Context {
-            outer: OuterAttr { syrup, .. },
+            outer:
+                OuterAttr {
+                    syrup,
+                    dictionary,
+                    transparent,
+                    ..
+                },
             ..
         }: &Context,
         field: &Field,
@@ -125,7 +517,7 @@ impl FieldAttr {
             as_ty: TypePath,
         ) -> impl FieldDecodeTransform {
             move |tokens| {
-                parse_quote_spanned! {as_ty.span()=> <#as_ty as ::std::convert::Into<#base_ty>>::into(<#as_ty as #syrup::Decode<'_, _>>::decode(#tokens)) }
+                parse_quote_spanned! {as_ty.span()=> <#as_ty as #syrup::Decode<'_, _>>::decode(#tokens).map(<#as_ty as ::std::convert::Into<#base_ty>>::into) }
             }
         }
         fn expr_encode_into(as_ty: TypePath) -> impl FieldEncodeTransform {
@@ -136,6 +528,14 @@ impl FieldAttr {
         let mut decode: Option<Box<dyn FieldDecodeTransform>> = None;
         // path to overriding encode function
         let mut encode: Option<Box<dyn FieldEncodeTransform>> = None;
+        // key to use for this field in a `dictionary` struct
+        let mut rename: Option<LitStr> = None;
+        // expression to fall back on when this field is missing
+        let mut default: Option<Expr> = None;
+        // whether this field is left off the wire entirely
+        let mut skip = false;
+        // whether this field collects the surplus record elements
+        let mut rest = false;
         for attr in attrs.iter() {
             if attr.path().is_ident("syrup") {
                 attr.parse_nested_meta(|meta| {
@@ -182,6 +582,36 @@ impl FieldAttr {
                             parse_quote_spanned! {encode_expr.span() => #encode_expr}
                         }));
                         Ok(())
+                    // use the given string as this field's dictionary key instead of its name
+                    } else if meta.path.is_ident("rename") {
+                        if !dictionary {
+                            return Err(meta.error("`rename` may only be used on fields of `dictionary` structs"));
+                        }
+                        rename = Some(meta.value()?.parse()?);
+                        Ok(())
+                    // when this field is missing from the input, use `Default::default()` (or the
+                    // given expression) instead of failing
+                    } else if meta.path.is_ident("default") {
+                        default = Some(if meta.input.peek(Token![=]) {
+                            meta.value()?.parse()?
+                        } else {
+                            parse_quote_spanned! {meta.path.span()=> ::std::default::Default::default() }
+                        });
+                        Ok(())
+                    // don't encode this field, and decode it as its `default`
+                    } else if meta.path.is_ident("skip") {
+                        if *transparent {
+                            return Err(meta.error("`skip` may not be used on fields of `transparent` structs"));
+                        }
+                        skip = true;
+                        Ok(())
+                    // collect all remaining record elements into this (final) field
+                    } else if meta.path.is_ident("rest") {
+                        if *transparent || *dictionary {
+                            return Err(meta.error("`rest` may only be used on fields of records"));
+                        }
+                        rest = true;
+                        Ok(())
                     // } else if meta.path.is_ident("as_ref") {
                     //     let val: LitBool = meta.value()?.parse()?;
                     //     decode_access_by_ref = val.value;
@@ -197,7 +627,30 @@ impl FieldAttr {
                 })?;
             }
         }
-        Ok(Self { decode, encode })
+        if rest && (skip || default.is_some()) {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`rest` may not be combined with `skip` or `default`",
+            ));
+        }
+        Ok(Self {
+            decode,
+            encode,
+            rename,
+            default,
+            skip,
+            rest,
+        })
+    }
+
+    /// The symbol this field is keyed by in a `dictionary` struct
+    fn key(&self, field: &Field) -> LitStr {
+        match (&self.rename, &field.ident) {
+            (Some(rename), _) => rename.clone(),
+            (None, Some(id)) => LitStr::new(&id.to_string(), id.span()),
+            // `OuterAttr::new` only allows `dictionary` on structs with named fields
+            (None, None) => LitStr::new("", field.span()),
+        }
     }
 }
 
@@ -210,6 +663,15 @@ pub fn derive_decode(input: proc_macro::TokenStream) -> proc_macro::TokenStream
     }
 }
 
+#[proc_macro_derive(DecodeFrom, attributes(syrup))]
+pub fn derive_decode_from(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match generate_decode_from(input) {
+        Ok(res) => res,
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
 #[proc_macro_derive(Encode, attributes(syrup))]
 pub fn derive_encode(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
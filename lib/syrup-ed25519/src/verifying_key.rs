@@ -56,16 +56,24 @@ where
 {
     let ecc = __impl_vkey::Ecc::<'tree, &'tree str>::decode(input)?;
     if ecc.curve.kind.0 != "Ed25519" {
-        return Err(DecodeError::Unexpected {
-            expected: SyrupKind::Symbol(Some("Ed25519")),
-            found: SyrupKind::Symbol(None),
-        });
+        return Err(DecodeError::unexpected(
+            SyrupKind::Symbol(Some("Ed25519")),
+            &syrup::symbol::encode::<&[u8], _>(ecc.curve.kind.0.as_bytes()),
+        )
+        .at_field("kind")
+        .at_label("curve")
+        .at_field("curve")
+        .at_label("ecc"));
     }
     if ecc.flags.flags.0 != "eddsa" {
-        return Err(DecodeError::Unexpected {
-            expected: SyrupKind::Symbol(Some("eddsa")),
-            found: SyrupKind::Symbol(None),
-        });
+        return Err(DecodeError::unexpected(
+            SyrupKind::Symbol(Some("eddsa")),
+            &syrup::symbol::encode::<&[u8], _>(ecc.flags.flags.0.as_bytes()),
+        )
+        .at_field("flags")
+        .at_label("flags")
+        .at_field("flags")
+        .at_label("ecc"));
     }
     Ok(VerifyingKey::from_bytes(ecc.q.q).unwrap())
 }
@@ -5,6 +5,31 @@ use syn::{
     Type, TypeParam, TypeParamBound,
 };
 
+mod notation;
+
+/// Build a `TokenTree<Vec<u8>>` from syrup's text notation, with `#(expr)` interpolating any
+/// value implementing `Encode`.
+#[proc_macro]
+pub fn syrup(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    notation::parse(input.into())
+        .and_then(|value| value.expand())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Encode syrup's text notation as canonical bytes at compile time, giving a `&'static [u8]`.
+#[proc_macro]
+pub fn syrup_bytes(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let mut bytes = Vec::new();
+    match notation::parse(input.into()).and_then(|value| value.encode(&mut bytes)) {
+        Ok(()) => {
+            let bytes = syn::LitByteStr::new(&bytes, Span::call_site());
+            quote!((#bytes as &'static [u8])).into()
+        }
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
 fn gen_tuple_idents(max_arity: usize) -> Vec<Ident> {
     (0..max_arity)
         .map(|id_num| Ident::new(&format!("__{id_num}"), Span::call_site()))
@@ -61,8 +86,8 @@ pub fn impl_decode_for_tuple(max_arity: proc_macro::TokenStream) -> proc_macro::
             let exp_str = LitStr::new(&format!("{i}th element"), Span::call_site());
             quote! {
                 match elements.get(#i) {
-                    Some(el) => el.decode()?,
-                    None => return Err(syrup::de::DecodeError::Missing(syrup::de::SyrupKind::Unknown(#exp_str)))
+                    Some(el) => el.decode().map_err(|e| e.at_index(#i))?,
+                    None => return Err(syrup::de::DecodeError::missing(syrup::de::SyrupKind::Unknown(#exp_str)))
                 }
             }
         })
@@ -141,3 +166,52 @@ pub fn impl_encode_for_tuple(max_arity: proc_macro::TokenStream) -> proc_macro::
     }
     res.into()
 }
+
+#[proc_macro]
+pub fn impl_decode_from_for_tuple(max_arity: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let max_arity: usize = parse_macro_input!(max_arity as LitInt)
+        .base10_parse()
+        .unwrap();
+
+    let input_lt: Lifetime = Lifetime::new("'__input", Span::call_site());
+    let input_lt_param: LifetimeParam = LifetimeParam::new(input_lt.clone());
+
+    let idents = gen_tuple_idents(max_arity);
+    let param_bound: TypeParamBound = parse_quote!(syrup::de::DecodeFrom<#input_lt>);
+    let mut res = TokenStream::new();
+
+    let decodes = idents
+        .iter()
+        .enumerate()
+        .map(|(i, _)| {
+            let exp_str = LitStr::new(&format!("{i}th element"), Span::call_site());
+            quote! {
+                if reader.at_end()? {
+                    return Err(syrup::de::DecodeError::missing(syrup::de::SyrupKind::Unknown(#exp_str)).into());
+                } else {
+                    reader.read().map_err(|e: syrup::de::ReadError| e.at_index(#i))?
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    for (idents, decodes) in (1..=max_arity).map(|arity| (&idents[..arity], &decodes[..arity])) {
+        let (impl_generics, ty_generics) = gen_tuple_params(&param_bound, idents);
+
+        let expected_len = idents.len();
+
+        quote! {
+            #[automatically_derived]
+            impl <#input_lt_param, #(#impl_generics),*> syrup::de::DecodeFrom<#input_lt> for ( #(#ty_generics,)* ) {
+                fn decode_from(reader: &mut syrup::de::Reader<#input_lt>) -> ::std::result::Result<Self, syrup::de::ReadError> {
+                    reader.begin_list(syrup::de::SyrupKind::List { length: Some(#expected_len) })?;
+                    let res = ( #(#decodes,)* );
+                    reader.skip_rest()?;
+                    Ok(res)
+                }
+            }
+        }
+        .to_tokens(&mut res);
+    }
+    res.into()
+}
@@ -0,0 +1,443 @@
+use proc_macro2::{Delimiter, Group, Spacing, Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned};
+use syn::{Error, Lit, LitByteStr, LitStr, Result};
+
+/// A value written in syrup's text notation, as accepted by `syrup!` and `syrup_bytes!`
+pub(crate) enum Value {
+    Bool(bool),
+    F32(f32),
+    F64(f64),
+    Int {
+        positive: bool,
+        /// Without leading zeros
+        digits: String,
+        span: Span,
+    },
+    Bytes(Vec<u8>),
+    String(String),
+    Symbol(String),
+    List(Vec<Value>),
+    Record(Box<Value>, Vec<Value>),
+    Set(Vec<Value>),
+    Dictionary(Vec<(Value, Value)>),
+    /// `#(expr)`, encoded at runtime
+    Interpolated(Group),
+}
+
+/// Parse a single value from `input`
+pub(crate) fn parse(input: TokenStream) -> Result<Value> {
+    let mut parser = Parser::new(input, Span::call_site());
+    let value = parser.value()?;
+    match parser.peek() {
+        Some(token) => Err(Error::new(token.span(), "unexpected token after the value")),
+        None => Ok(value),
+    }
+}
+
+/// Whether a punctuation character separates values, rather than being part of a symbol
+const fn is_delimiter(c: char) -> bool {
+    matches!(c, '<' | '>' | '#' | ',' | ';' | '\'')
+}
+
+/// Whether `b` starts right where `a` ends
+fn adjacent(a: &TokenTree, b: &TokenTree) -> bool {
+    // unlike spans, spacing is kept when tokens are re-spanned
+    if let (TokenTree::Punct(p), TokenTree::Punct(_)) = (a, b) {
+        return p.spacing() == Spacing::Joint;
+    }
+    if let Some(adjacent) = joined_text(a.span(), b.span()) {
+        return adjacent;
+    }
+    let (a, b) = (a.span().unwrap().end(), b.span().unwrap().start());
+    (a.line(), a.column()) == (b.line(), b.column())
+}
+
+/// Whether the source text spanning `a` and `b` is just theirs, with nothing in between; only
+/// known where spans can be joined, which needs a nightly compiler
+fn joined_text(a: Span, b: Span) -> Option<bool> {
+    let joined = a.join(b)?.source_text()?;
+    let (a, b) = (a.source_text()?, b.source_text()?);
+    Some(joined.strip_prefix(&a) == Some(&b))
+}
+
+fn is_number(token: &TokenTree) -> bool {
+    matches!(token, TokenTree::Literal(lit) if lit.to_string().starts_with(|c: char| c.is_ascii_digit()))
+}
+
+struct Parser {
+    tokens: Vec<TokenTree>,
+    pos: usize,
+    /// Where to report running out of tokens
+    end: Span,
+}
+
+impl Parser {
+    fn new(input: TokenStream, end: Span) -> Self {
+        Self {
+            tokens: input.into_iter().collect(),
+            pos: 0,
+            end,
+        }
+    }
+
+    fn peek(&self) -> Option<&TokenTree> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<TokenTree> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| Error::new(self.end, "unexpected end of input"))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    /// Skip commas, which are treated as whitespace
+    fn skip_commas(&mut self) {
+        while matches!(self.peek(), Some(TokenTree::Punct(p)) if p.as_char() == ',') {
+            self.pos += 1;
+        }
+    }
+
+    /// Parse values up to the end of the tokens
+    fn values(&mut self) -> Result<Vec<Value>> {
+        let mut res = Vec::new();
+        loop {
+            self.skip_commas();
+            if self.peek().is_none() {
+                return Ok(res);
+            }
+            res.push(self.value()?);
+        }
+    }
+
+    fn entries(&mut self) -> Result<Vec<(Value, Value)>> {
+        let mut res = Vec::new();
+        loop {
+            self.skip_commas();
+            if self.peek().is_none() {
+                return Ok(res);
+            }
+            let key = self.value()?;
+            match self.next()? {
+                TokenTree::Punct(p) if p.as_char() == ':' => {}
+                token => return Err(Error::new(token.span(), "expected `:` after the key")),
+            }
+            res.push((key, self.value()?));
+        }
+    }
+
+    fn value(&mut self) -> Result<Value> {
+        let token = self.next()?;
+        match &token {
+            TokenTree::Group(group) => {
+                let mut inner = Parser::new(group.stream(), group.span_close());
+                match group.delimiter() {
+                    Delimiter::Bracket => inner.values().map(Value::List),
+                    Delimiter::Brace => inner.entries().map(Value::Dictionary),
+                    // from a `macro_rules!` fragment
+                    Delimiter::None => parse(group.stream()),
+                    Delimiter::Parenthesis => Err(Error::new(
+                        group.span(),
+                        "unexpected parentheses; use `#(...)` to interpolate an expression",
+                    )),
+                }
+            }
+            TokenTree::Punct(p) if p.as_char() == '#' => self.hash(&token),
+            TokenTree::Punct(p) if p.as_char() == '<' => {
+                if matches!(self.peek(), Some(TokenTree::Punct(p)) if p.as_char() == '>') {
+                    return Err(Error::new(p.span(), "a record needs a label"));
+                }
+                let label = self.value()?;
+                let mut elements = Vec::new();
+                loop {
+                    self.skip_commas();
+                    match self.peek() {
+                        Some(TokenTree::Punct(p)) if p.as_char() == '>' => {
+                            self.pos += 1;
+                            return Ok(Value::Record(Box::new(label), elements));
+                        }
+                        Some(_) => elements.push(self.value()?),
+                        None => return Err(Error::new(p.span(), "record isn't closed with `>`")),
+                    }
+                }
+            }
+            TokenTree::Punct(p)
+                if matches!(p.as_char(), '-' | '+')
+                    && self
+                        .peek()
+                        .is_some_and(|next| is_number(next) && adjacent(&token, next)) =>
+            {
+                let TokenTree::Literal(lit) = self.next()? else {
+                    unreachable!()
+                };
+                literal(&Lit::new(lit), p.as_char() == '+')
+            }
+            TokenTree::Punct(p) if is_delimiter(p.as_char()) => Err(Error::new(
+                p.span(),
+                format!("unexpected `{}`", p.as_char()),
+            )),
+            TokenTree::Literal(lit) => literal(&Lit::new(lit.clone()), true),
+            TokenTree::Ident(_) | TokenTree::Punct(_) => self.symbol(token),
+        }
+    }
+
+    /// Parse whatever follows a `#`
+    fn hash(&mut self, hash: &TokenTree) -> Result<Value> {
+        match self.next()? {
+            TokenTree::Ident(ident) if ident == "t" => Ok(Value::Bool(true)),
+            TokenTree::Ident(ident) if ident == "f" => Ok(Value::Bool(false)),
+            TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis => {
+                Ok(Value::Interpolated(group))
+            }
+            TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+                Parser::new(group.stream(), group.span_close())
+                    .values()
+                    .map(Value::Set)
+            }
+            _ => Err(Error::new(
+                hash.span(),
+                "expected `#t`, `#f`, a set `#{...}`, or an interpolation `#(...)`",
+            )),
+        }
+    }
+
+    /// A symbol made of `first` and any following tokens written without spaces between them,
+    /// such as `op:deliver`. A trailing `:` is left for a dictionary entry.
+    fn symbol(&mut self, first: TokenTree) -> Result<Value> {
+        let mut text = String::new();
+        let mut prev = first;
+        loop {
+            match &prev {
+                TokenTree::Ident(ident) => text.push_str(&ident.to_string()),
+                TokenTree::Punct(p) => text.push(p.as_char()),
+                token => text.push_str(&token.to_string()),
+            }
+            let Some(next) = self.peek() else {
+                break;
+            };
+            let continues = match next {
+                TokenTree::Ident(_) => true,
+                TokenTree::Punct(p) => !is_delimiter(p.as_char()),
+                token => is_number(token),
+            };
+            if !continues || !adjacent(&prev, next) {
+                break;
+            }
+            prev = self.next()?;
+        }
+        if text.len() > 1
+            && let TokenTree::Punct(p) = &prev
+            && p.as_char() == ':'
+        {
+            text.pop();
+            self.pos -= 1;
+        }
+        if text == ":" {
+            return Err(Error::new(prev.span(), "unexpected `:`"));
+        }
+        Ok(Value::Symbol(text))
+    }
+}
+
+fn literal(lit: &Lit, positive: bool) -> Result<Value> {
+    let sign = if positive { 1.0 } else { -1.0 };
+    match lit {
+        Lit::Str(s) if positive => Ok(Value::String(s.value())),
+        Lit::ByteStr(b) if positive => Ok(Value::Bytes(b.value())),
+        Lit::Int(int) if int.suffix().is_empty() => {
+            let digits = int.base10_digits().trim_start_matches('0');
+            Ok(Value::Int {
+                positive: positive || digits.is_empty(),
+                digits: if digits.is_empty() { "0" } else { digits }.to_owned(),
+                span: int.span(),
+            })
+        }
+        Lit::Float(float) => match float.suffix() {
+            "" | "f64" => Ok(Value::F64(sign * float.base10_parse::<f64>()?)),
+            "f" | "f32" => Ok(Value::F32(
+                sign as f32
+                    * float
+                        .base10_digits()
+                        .parse::<f32>()
+                        .map_err(|e| Error::new(float.span(), e))?,
+            )),
+            _ => Err(Error::new(
+                float.span(),
+                "expected a float suffix of `f`, `f32`, or `f64`",
+            )),
+        },
+        Lit::Int(int) => Err(Error::new(
+            int.span(),
+            "integers can't have a suffix; use `f` for an f32",
+        )),
+        lit => Err(Error::new(lit.span(), "unsupported literal")),
+    }
+}
+
+impl Value {
+    /// Code constructing the value as a `TokenTree<Vec<u8>>`
+    pub(crate) fn expand(&self) -> Result<TokenStream> {
+        let tree = quote!(::syrup::TokenTree::<::std::vec::Vec<u8>>);
+        let all = |values: &[Value]| values.iter().map(Value::expand).collect::<Result<Vec<_>>>();
+        Ok(match self {
+            Value::Bool(b) => quote!(#tree::Literal(::syrup::de::Literal::Bool(#b))),
+            Value::F32(f) => {
+                let bits = f.to_bits();
+                quote!(#tree::Literal(::syrup::de::Literal::F32(f32::from_bits(#bits))))
+            }
+            Value::F64(f) => {
+                let bits = f.to_bits();
+                quote!(#tree::Literal(::syrup::de::Literal::F64(f64::from_bits(#bits))))
+            }
+            Value::Int {
+                positive,
+                digits,
+                span,
+            } => {
+                let too_large = || {
+                    Error::new(
+                        *span,
+                        "integer doesn't fit in 128 bits; use `syrup_bytes!`, or interpolate it",
+                    )
+                };
+                let int = if *positive {
+                    let int: u128 = digits.parse().map_err(|_e| too_large())?;
+                    quote!(#int)
+                } else {
+                    let int: i128 = format!("-{digits}").parse().map_err(|_e| too_large())?;
+                    quote!(#int)
+                };
+                quote!(#tree::Literal(::syrup::de::Literal::Int(::syrup::de::Int::from(#int))))
+            }
+            Value::Bytes(bytes) => {
+                let bytes = LitByteStr::new(bytes, Span::call_site());
+                quote!(#tree::Literal(::syrup::de::Literal::Bytes(#bytes.to_vec())))
+            }
+            Value::String(s) => {
+                let s = LitStr::new(s, Span::call_site());
+                quote!(#tree::Literal(::syrup::de::Literal::String(#s.as_bytes().to_vec())))
+            }
+            Value::Symbol(s) => {
+                let s = LitStr::new(s, Span::call_site());
+                quote!(#tree::Literal(::syrup::de::Literal::Symbol(#s.as_bytes().to_vec())))
+            }
+            Value::List(elements) => {
+                let elements = all(elements)?;
+                quote!(#tree::List(::syrup::de::List::new(::std::vec![#(#elements),*])))
+            }
+            Value::Record(label, elements) => {
+                let label = label.expand()?;
+                let elements = all(elements)?;
+                quote!(#tree::Record(::std::boxed::Box::new(::syrup::de::Record::new(
+                    #label,
+                    ::std::vec![#(#elements),*],
+                ))))
+            }
+            Value::Set(entries) => {
+                let entries = all(entries)?;
+                quote!(#tree::Set(::syrup::de::Set::new(::std::vec![#(#entries),*])))
+            }
+            Value::Dictionary(entries) => {
+                let entries = entries
+                    .iter()
+                    .map(|(key, value)| {
+                        let (key, value) = (key.expand()?, value.expand()?);
+                        Ok(quote!((#key, #value)))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                quote!(#tree::Dictionary(::syrup::de::Dictionary::new(::std::vec![#(#entries),*])))
+            }
+            Value::Interpolated(expr) => quote_spanned! {expr.span()=>
+                ::syrup::Encode::<::std::vec::Vec<u8>>::encode(&#expr)
+            },
+        })
+    }
+
+    /// Append the value's canonical encoding to `out`
+    pub(crate) fn encode(&self, out: &mut Vec<u8>) -> Result<()> {
+        fn sized(out: &mut Vec<u8>, sep: u8, bytes: &[u8]) {
+            out.extend(bytes.len().to_string().bytes());
+            out.push(sep);
+            out.extend(bytes);
+        }
+        match self {
+            Value::Bool(b) => out.push(if *b { b't' } else { b'f' }),
+            Value::F32(f) => {
+                out.push(b'F');
+                out.extend(f.to_be_bytes());
+            }
+            Value::F64(f) => {
+                out.push(b'D');
+                out.extend(f.to_be_bytes());
+            }
+            Value::Int {
+                positive, digits, ..
+            } => {
+                out.extend(digits.bytes());
+                out.push(if *positive { b'+' } else { b'-' });
+            }
+            Value::Bytes(bytes) => sized(out, b':', bytes),
+            Value::String(s) => sized(out, b'"', s.as_bytes()),
+            Value::Symbol(s) => sized(out, b'\'', s.as_bytes()),
+            Value::List(elements) => {
+                out.push(b'[');
+                for element in elements {
+                    element.encode(out)?;
+                }
+                out.push(b']');
+            }
+            Value::Record(label, elements) => {
+                out.push(b'<');
+                label.encode(out)?;
+                for element in elements {
+                    element.encode(out)?;
+                }
+                out.push(b'>');
+            }
+            Value::Set(entries) => {
+                let mut encoded = entries
+                    .iter()
+                    .map(|entry| {
+                        let mut out = Vec::new();
+                        entry.encode(&mut out).map(|()| out)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                encoded.sort();
+                encoded.dedup();
+                out.push(b'#');
+                out.extend(encoded.concat());
+                out.push(b'$');
+            }
+            Value::Dictionary(entries) => {
+                let mut encoded = entries
+                    .iter()
+                    .map(|(key, value)| {
+                        let (mut k, mut v) = (Vec::new(), Vec::new());
+                        key.encode(&mut k)?;
+                        value.encode(&mut v)?;
+                        Ok((k, v))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                // stable, so the first of any duplicate keys is kept
+                encoded.sort_by(|(a, _), (b, _)| a.cmp(b));
+                encoded.dedup_by(|(a, _), (b, _)| a == b);
+                out.push(b'{');
+                for (key, value) in encoded {
+                    out.extend(key);
+                    out.extend(value);
+                }
+                out.push(b'}');
+            }
+            Value::Interpolated(expr) => {
+                return Err(Error::new(
+                    expr.span(),
+                    "`syrup_bytes!` can't interpolate values; use `syrup!` instead",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
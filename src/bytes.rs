@@ -28,7 +28,7 @@ pub fn decode_bytes<'i, Output>(input: &'i [u8]) -> Result<(&'i [u8], Output), D
 where
     &'i [u8]: Into<Output>,
 {
-    let (rem, tree) = TokenTree::<&'i [u8]>::parse::<nom::error::Error<&'i [u8]>>(input)?;
+    let (rem, tree) = TokenTree::<&'i [u8]>::parse_located(input)?;
     Ok((rem, decode::<&'i [u8], &'i [u8]>(&tree)?.into()))
 }
 
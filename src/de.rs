@@ -4,15 +4,101 @@ pub use lex::*;
 mod error;
 pub use error::*;
 
+mod reader;
+pub use reader::*;
+
+mod lazy;
+pub use lazy::*;
+
+mod stream;
+pub use stream::*;
+
 /// `[Decode]` implementations for standard library types
 mod impl_decode;
 
+/// `[DecodeFrom]` implementations for standard library types
+mod impl_decode_from;
+
 pub trait Decode<'tree, IData>: Sized {
     fn decode(input: &'tree TokenTree<IData>) -> Result<Self, DecodeError>;
 }
 
+/// Decodes a value straight from syrup input, as it's read by a [`Reader`], without building a
+/// [`TokenTree`] for it first.
+///
+/// Strings and byte strings may borrow from the input, and values that aren't needed (such as
+/// unknown dictionary keys, or surplus record elements) are skipped over without being parsed into
+/// trees. [`Decode`] remains the more flexible option, since it can look at a value more than once.
+///
+/// ```
+/// # use syrup::{DecodeFrom, de::Reader};
+/// #[derive(DecodeFrom, Debug, PartialEq)]
+/// struct Greeting<'a> {
+///     name: &'a str,
+///     times: u8,
+/// }
+///
+/// let input = b"<8'Greeting5\"world3+3:xyz>";
+/// let greeting: Greeting<'_> = Reader::new(input).read()?;
+/// assert_eq!(greeting, Greeting { name: "world", times: 3 });
+/// # Ok::<(), syrup::de::ReadError>(())
+/// ```
+pub trait DecodeFrom<'input>: Sized {
+    /// Decode the value at the reader's current position, leaving the reader just after it.
+    fn decode_from(reader: &mut Reader<'input>) -> Result<Self, ReadError>;
+}
+
+/// Decode a value from the start of `input`, returning it along with any input left after it.
+///
+/// The value may borrow strings and byte strings from `input`:
+///
+/// ```
+/// # use syrup::{DecodeFrom, bytes::Bytes, symbol::Symbol};
+/// #[derive(DecodeFrom)]
+/// struct Call<'a> {
+///     method: Symbol<&'a str>,
+///     arg: Bytes<&'a [u8]>,
+/// }
+///
+/// let input = b"<4'Call4'ping2:hi>t";
+/// let (rem, call) = syrup::from_bytes_partial::<Call<'_>>(input)?;
+/// assert_eq!(call.method.0, "ping");
+/// assert_eq!(call.arg.0, b"hi");
+/// assert_eq!(rem, b"t");
+/// # Ok::<(), syrup::de::DecodeBytesError<'_>>(())
+/// ```
+pub fn from_bytes_partial<'input, T>(
+    input: &'input [u8],
+) -> Result<(&'input [u8], T), DecodeBytesError<'input>>
+where
+    T: DecodeFrom<'input>,
+{
+    let mut reader = Reader::new(input);
+    let res = reader.read()?;
+    Ok((reader.remaining(), res))
+}
+
+/// Decode a value from `input`, which must hold exactly that value; anything after it is an error.
+///
+/// See [`from_bytes_partial`] to decode a value followed by other input.
+pub fn from_bytes<'input, T>(input: &'input [u8]) -> Result<T, DecodeBytesError<'input>>
+where
+    T: DecodeFrom<'input>,
+{
+    let (rem, res) = from_bytes_partial(input)?;
+    if !rem.is_empty() {
+        return Err(DecodeBytesError::TrailingInput {
+            offset: input.len() - rem.len(),
+        });
+    }
+    Ok(res)
+}
+
 /// Trait implemented for `T where for<'t> T: [Decode]<'t, &'input [u8]>`. In other words, for any type with a
 /// `[Decode]` implementation that doesn't require holding a reference to the input `[TokenTree]`.
+///
+/// Types borrowing from the input can implement [`DecodeFrom`] and be decoded with
+/// [`from_bytes_partial`] instead.
 pub trait DecodeFromBytes<'input>: Sized {
     /// Decode the input bytes into a tuple of `(unconsumed input, Self)`.
     fn decode_bytes(input: &'input [u8]) -> Result<(&'input [u8], Self), DecodeBytesError<'input>>;
@@ -25,7 +111,7 @@ where
     for<'tree> T: Decode<'tree, &'input [u8]>,
 {
     fn decode_bytes(input: &'input [u8]) -> Result<(&'input [u8], T), DecodeBytesError<'input>> {
-        let (rem, tree) = TokenTree::<&[u8]>::parse::<nom::error::Error<&'input [u8]>>(input)?;
+        let (rem, tree) = TokenTree::<&[u8]>::parse_located(input)?;
         Ok((rem, T::decode(&tree)?))
     }
 }
@@ -33,11 +119,11 @@ where
 /// Decode the input byte slice to the given type.
 ///
 /// Does the same thing as [`DecodeFromBytes::decode_bytes`], but that trait isn't ergonomic with
-/// current compiler limitations.
+/// current compiler limitations. See [`from_bytes_partial`] for types implementing [`DecodeFrom`].
 #[macro_export]
 macro_rules! decode_bytes {
     ($input:expr => $Output:ty) => {
-        $crate::de::TokenTree::<&[u8]>::parse::<$crate::nom::error::Error<&[u8]>>($input)
+        $crate::de::TokenTree::<&[u8]>::parse_located($input)
             .map_err($crate::de::DecodeBytesError::from)
             .and_then(|(rem, tree)| Ok((rem, tree.decode::<'_, $Output>()?)))
     };
@@ -1,4 +1,5 @@
 use std::{
+    borrow::Cow,
     num::{
         IntErrorKind, NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize,
         NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize, Saturating,
@@ -7,59 +8,11 @@ use std::{
     str::Utf8Error,
 };
 
-use borrow_or_share::{BorrowOrShare, Bos};
+use borrow_or_share::Bos;
 
-use crate::de::{DecodeIntError, Int, TokenTree};
-
-#[derive(Clone)]
-pub enum DecodeErrorKind<Str = String, Bytes = Vec<u8>> {
-    Unexpected {
-        expected: Str,
-        // TODO :: do we have to own this token tree
-        found: TokenTree<Vec<u8>>,
-    },
-    Missing {
-        expected: Str,
-    },
-    Utf8 {
-        input: Bytes,
-        error: Utf8Error,
-    },
-    Int {
-        input: Int<Bytes>,
-        error: DecodeIntError,
-    },
-}
-
-impl<Str, Bytes> std::fmt::Debug for DecodeErrorKind<Str, Bytes>
-where
-    Str: Bos<str>,
-    Bytes: Bos<[u8]>,
-{
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Unexpected { expected, found } => f
-                .debug_struct("Unexpected")
-                .field("expected", &expected.borrow_or_share())
-                .field("found", found)
-                .finish(),
-            Self::Missing { expected } => f
-                .debug_struct("Missing")
-                .field("expected", &expected.borrow_or_share())
-                .finish(),
-            Self::Utf8 { input, error } => f
-                .debug_struct("Utf8")
-                .field("input", &input.borrow_or_share())
-                .field("error", error)
-                .finish(),
-            Self::Int { input, error } => f
-                .debug_struct("Int")
-                .field("input", input)
-                .field("error", error)
-                .finish(),
-        }
-    }
-}
+use crate::de::{
+    Int, LimitExceeded, LimitedParseError, NotCanonical, ParseError, ReadError, TokenTree,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct IntDescription {
@@ -209,12 +162,22 @@ impl std::fmt::Display for SyrupKind {
     }
 }
 
+/// What went wrong while decoding a [`TokenTree`]; see [`DecodeError`] for where it went wrong.
 #[derive(Debug, Clone, thiserror::Error)]
-pub enum DecodeError {
-    #[error("encountered invalid string or symbol")]
-    InvalidUtf8,
-    #[error("failed conversion to {0}: {1:?}")]
-    ParseInt(IntDescription, IntErrorKind),
+pub enum DecodeErrorKind {
+    #[error("encountered invalid string or symbol{}: {error}", DisplayInput(.input.as_deref().map(String::from_utf8_lossy)))]
+    InvalidUtf8 {
+        /// The invalid bytes, if known
+        input: Option<Vec<u8>>,
+        error: Utf8Error,
+    },
+    #[error("failed conversion{} to {desc}: {kind:?}", DisplayInput(.input.as_ref()))]
+    ParseInt {
+        desc: IntDescription,
+        /// The integer that failed to convert, if known
+        input: Option<Int<Vec<u8>>>,
+        kind: IntErrorKind,
+    },
     #[error("expected: {expected}, found: {found}")]
     Unexpected {
         expected: SyrupKind,
@@ -222,38 +185,308 @@ pub enum DecodeError {
     },
     #[error("missing {0}")]
     Missing(SyrupKind),
+    #[error("expected a label in {expected:?}, found: '{found}'")]
+    UnexpectedLabel {
+        expected: &'static [&'static str],
+        found: String,
+    },
+    #[error("expected at most {expected} elements, found: {found}")]
+    ExtraElements { expected: usize, found: usize },
+    #[error("expected a key in {expected:?}, found: '{found}'")]
+    UnknownKey {
+        expected: &'static [&'static str],
+        found: String,
+    },
+    /// A message from a `serde::Deserialize` implementation
+    #[error("{0}")]
+    Custom(String),
+}
+
+/// Formats the optional input of a [`DecodeErrorKind`] as ` of <input>`
+struct DisplayInput<T>(Option<T>);
+
+impl<T: std::fmt::Debug> std::fmt::Display for DisplayInput<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(input) => write!(f, " of {input:?}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// One step along the path from the root of a [`TokenTree`] to where a [`DecodeError`] occurred
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// Inside a record with this label
+    Label(Cow<'static, str>),
+    /// A named or positional field of a record or dictionary struct
+    Field(Cow<'static, str>),
+    /// An element of a list, set, or tuple
+    Index(usize),
+    /// The entry of a dictionary with this key
+    Key(String),
+}
+
+impl PathSegment {
+    fn write(&self, f: &mut std::fmt::Formatter<'_>, first: bool) -> std::fmt::Result {
+        match (self, first) {
+            (PathSegment::Label(label), true) => f.write_str(label),
+            (PathSegment::Label(label), false) => write!(f, ".<{label}>"),
+            (PathSegment::Field(field), true) => f.write_str(field),
+            (PathSegment::Field(field), false) => write!(f, ".{field}"),
+            (PathSegment::Index(i), _) => write!(f, "[{i}]"),
+            (PathSegment::Key(key), _) => write!(f, "{{{key}}}"),
+        }
+    }
+}
+
+/// Formats a path like `op:deliver.args[2].<desc:export>.0`; see [`DecodeError::path`]
+pub struct DisplayPath<'p>(pub &'p [PathSegment]);
+
+impl std::fmt::Display for DisplayPath<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, segment) in self.0.iter().enumerate() {
+            segment.write(f, i == 0)?;
+        }
+        Ok(())
+    }
 }
 
+#[derive(Debug, Clone)]
+struct DecodeErrorInner {
+    kind: DecodeErrorKind,
+    /// Outermost first
+    path: Vec<PathSegment>,
+}
+
+/// An error from decoding a [`TokenTree`], along with the path to the value that failed to decode.
+///
+/// Implementations of [`Decode`](crate::de::Decode) for collections and derived types add to the
+/// path as errors propagate out of them:
+///
+/// ```
+/// # use syrup::de::{DecodeError, DecodeErrorKind};
+/// let tree = syrup::TokenTree::<&[u8]>::parse::<syrup::de::ParseError<'_>>(b"[[]1\"x]")?.1;
+/// let err = tree.decode::<Vec<Vec<u8>>>().unwrap_err();
+/// assert!(matches!(err.kind(), DecodeErrorKind::Unexpected { .. }));
+/// assert_eq!(err.to_string(), "[1]: expected: list, found: string");
+/// # Ok::<(), nom::Err<syrup::de::ParseError<'_>>>(())
+/// ```
+#[derive(Clone)]
+pub struct DecodeError(Box<DecodeErrorInner>);
+
 impl DecodeError {
     #[inline]
-    pub const fn int<Int: IsSigned>(error: IntErrorKind) -> Self {
-        Self::ParseInt(IntDescription::describe::<Int>(), error)
+    #[must_use]
+    pub fn kind(&self) -> &DecodeErrorKind {
+        &self.0.kind
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn into_kind(self) -> DecodeErrorKind {
+        self.0.kind
+    }
+
+    /// The path from the root of the decoded tree to the value that failed to decode, outermost
+    /// first.
+    #[inline]
+    #[must_use]
+    pub fn path(&self) -> &[PathSegment] {
+        &self.0.path
+    }
+
+    /// Note that this error occurred inside `segment`.
+    #[must_use]
+    pub fn within(mut self, segment: PathSegment) -> Self {
+        self.0.path.insert(0, segment);
+        self
+    }
+
+    #[must_use]
+    pub fn at_label(self, label: impl Into<Cow<'static, str>>) -> Self {
+        self.within(PathSegment::Label(label.into()))
+    }
+
+    #[must_use]
+    pub fn at_field(self, field: impl Into<Cow<'static, str>>) -> Self {
+        self.within(PathSegment::Field(field.into()))
+    }
+
+    #[must_use]
+    pub fn at_index(self, index: usize) -> Self {
+        self.within(PathSegment::Index(index))
+    }
+
+    /// Note that this error occurred in the dictionary entry with the given `key`.
+    #[must_use]
+    pub fn at_key<Data: Bos<[u8]>>(self, key: &TokenTree<Data>) -> Self {
+        let key = match key {
+            TokenTree::Literal(lit) => format!("{lit:?}"),
+            key => key.kind().to_string(),
+        };
+        self.within(PathSegment::Key(key))
+    }
+
+    #[inline]
+    pub fn int<Int: IsSigned>(kind: IntErrorKind) -> Self {
+        DecodeErrorKind::ParseInt {
+            desc: IntDescription::describe::<Int>(),
+            input: None,
+            kind,
+        }
+        .into()
+    }
+
+    /// Like [`DecodeError::int`], recording the `input` that failed to convert.
+    pub fn int_from<Int: IsSigned, Data: Bos<[u8]>>(
+        input: &crate::de::Int<Data>,
+        kind: IntErrorKind,
+    ) -> Self {
+        DecodeErrorKind::ParseInt {
+            desc: IntDescription::describe::<Int>(),
+            input: Some(input.into()),
+            kind,
+        }
+        .into()
+    }
+
+    pub fn utf8(input: &[u8], error: Utf8Error) -> Self {
+        DecodeErrorKind::InvalidUtf8 {
+            input: Some(input.to_vec()),
+            error,
+        }
+        .into()
     }
 
     pub fn unexpected<Data: Bos<[u8]>>(expected: SyrupKind, input: &TokenTree<Data>) -> Self {
-        Self::Unexpected {
+        Self::unexpected_kind(expected, input.kind())
+    }
+
+    /// Like [`DecodeError::unexpected`], for when only the kind of the input is known.
+    pub fn unexpected_kind(expected: SyrupKind, found: SyrupKind) -> Self {
+        DecodeErrorKind::Unexpected { expected, found }.into()
+    }
+
+    pub fn missing(expected: SyrupKind) -> Self {
+        DecodeErrorKind::Missing(expected).into()
+    }
+
+    pub fn unexpected_label(expected: &'static [&'static str], found: &[u8]) -> Self {
+        DecodeErrorKind::UnexpectedLabel {
+            expected,
+            found: String::from_utf8_lossy(found).into_owned(),
+        }
+        .into()
+    }
+
+    pub fn extra_elements(expected: usize, found: usize) -> Self {
+        DecodeErrorKind::ExtraElements { expected, found }.into()
+    }
+
+    pub fn unknown_key(expected: &'static [&'static str], found: &[u8]) -> Self {
+        DecodeErrorKind::UnknownKey {
             expected,
-            found: input.kind(),
+            found: String::from_utf8_lossy(found).into_owned(),
         }
+        .into()
+    }
+}
+
+/// [`std::str::from_utf8`], reporting the invalid input on failure
+pub(crate) fn str_from_utf8(input: &[u8]) -> Result<&str, DecodeError> {
+    std::str::from_utf8(input).map_err(|error| DecodeError::utf8(input, error))
+}
+
+impl From<DecodeErrorKind> for DecodeError {
+    fn from(kind: DecodeErrorKind) -> Self {
+        Self(Box::new(DecodeErrorInner {
+            kind,
+            path: Vec::new(),
+        }))
     }
 }
 
 impl From<Utf8Error> for DecodeError {
-    fn from(_: Utf8Error) -> Self {
-        Self::InvalidUtf8
+    fn from(error: Utf8Error) -> Self {
+        DecodeErrorKind::InvalidUtf8 { input: None, error }.into()
+    }
+}
+
+impl std::fmt::Debug for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DecodeError")
+            .field("kind", &self.0.kind)
+            .field("path", &self.0.path)
+            .finish()
+    }
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.0.path.is_empty() {
+            write!(f, "{}: ", DisplayPath(&self.0.path))?;
+        }
+        self.0.kind.fmt(f)
     }
 }
 
+impl std::error::Error for DecodeError {}
+
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum DecodeBytesError<'input> {
+    #[error("{}", lex_message(.0))]
+    Lex(nom::Err<ParseError<'input>>),
+    #[error(transparent)]
+    Decode(DecodeError),
+    /// Malformed input, found while reading it with a [`Reader`](crate::de::Reader)
+    #[error(transparent)]
+    Read(ReadError),
+    /// The input exceeded the [`ParseLimits`](crate::de::ParseLimits) it was parsed within
     #[error(transparent)]
-    Lex(nom::Err<nom::error::Error<&'input [u8]>>),
+    Limit(#[from] LimitExceeded),
+    /// Only from input that had to be canonical
     #[error(transparent)]
-    Decode(#[from] DecodeError),
+    NotCanonical(#[from] NotCanonical),
+    #[error("unexpected input after the end of the value, at byte {offset}")]
+    TrailingInput { offset: usize },
 }
 
-impl<'i> From<nom::Err<nom::error::Error<&'i [u8]>>> for DecodeBytesError<'i> {
-    fn from(value: nom::Err<nom::error::Error<&'i [u8]>>) -> Self {
+impl<'i> From<nom::Err<ParseError<'i>>> for DecodeBytesError<'i> {
+    fn from(value: nom::Err<ParseError<'i>>) -> Self {
         Self::Lex(value)
     }
 }
+
+impl<'i> From<LimitedParseError<ParseError<'i>>> for DecodeBytesError<'i> {
+    fn from(value: LimitedParseError<ParseError<'i>>) -> Self {
+        match value {
+            LimitedParseError::Limit(e) => Self::Limit(e),
+            LimitedParseError::NotCanonical(e) => Self::NotCanonical(e),
+            LimitedParseError::Parse(e) => Self::Lex(e),
+        }
+    }
+}
+
+impl From<DecodeError> for DecodeBytesError<'_> {
+    fn from(value: DecodeError) -> Self {
+        Self::Decode(value)
+    }
+}
+
+impl From<ReadError> for DecodeBytesError<'_> {
+    fn from(value: ReadError) -> Self {
+        match value {
+            ReadError::Decode(e) => Self::Decode(e),
+            ReadError::Limit(e) => Self::Limit(e),
+            e => Self::Read(e),
+        }
+    }
+}
+
+fn lex_message(error: &nom::Err<ParseError<'_>>) -> String {
+    match error {
+        nom::Err::Incomplete(_) => "unexpected end of input".to_owned(),
+        nom::Err::Error(e) | nom::Err::Failure(e) => e.to_string(),
+    }
+}
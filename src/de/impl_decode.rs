@@ -1,7 +1,7 @@
 use std::{
     borrow::Cow,
     cell::{Cell, RefCell},
-    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque},
     num::{
         NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize, NonZeroU8,
         NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize, Saturating, Wrapping,
@@ -14,6 +14,7 @@ use borrow_or_share::{BorrowOrShare, Bos};
 
 use crate::de::{
     Decode, DecodeError, Dictionary, List, Literal, Record, Set, SyrupKind, TokenTree,
+    str_from_utf8,
 };
 
 #[cfg(test)]
@@ -109,6 +110,16 @@ impl_decode_to_tokens! {Set, SyrupKind::Set => OData, Set<OData>, val => val.int
 impl_decode_to_tokens! {Record, SyrupKind::Record { label: None } => OData, Record<OData>, val => (&**val).into()}
 impl_decode_to_tokens! {Literal, SyrupKind::Unknown("Literal") => OData, Literal<OData>, val => val.into()}
 
+impl<'i, 'o, IData, OData> Decode<'i, IData> for TokenTree<OData>
+where
+    IData: BorrowOrShare<'i, 'o, [u8]>,
+    &'o [u8]: Into<OData>,
+{
+    fn decode(input: &'i TokenTree<IData>) -> Result<Self, DecodeError> {
+        Ok(input.into())
+    }
+}
+
 macro_rules! impl_parse_simple {
     ($Ty:ty, $Lit:ident) => {
         impl<'i, IData> Decode<'i, IData> for $Ty
@@ -138,7 +149,7 @@ macro_rules! impl_parse_stringlike {
             fn decode(input: &'i TokenTree<IData>) -> Result<Self, DecodeError> {
                 match input {
                     TokenTree::Literal(Literal::$Lit(val)) => {
-                        Ok(std::str::from_utf8(val.borrow_or_share())?.into())
+                        Ok(str_from_utf8(val.borrow_or_share())?.into())
                     }
                     _ => Err(DecodeError::unexpected(SyrupKind::String, input)),
                 }
@@ -162,9 +173,7 @@ where
 {
     fn decode(input: &'i TokenTree<IData>) -> Result<Self, DecodeError> {
         match input {
-            TokenTree::Literal(Literal::String(val)) => {
-                Ok(std::str::from_utf8(val.borrow_or_share())?)
-            }
+            TokenTree::Literal(Literal::String(val)) => str_from_utf8(val.borrow_or_share()),
             _ => Err(DecodeError::unexpected(SyrupKind::String, input)),
         }
     }
@@ -179,7 +188,7 @@ macro_rules! impl_decode_for_int {
         {
             fn decode(input: &'i TokenTree<IData>) -> Result<Self, DecodeError> {
                 match input {
-                    TokenTree::Literal(Literal::Int(int)) => <&'i $crate::de::Int<IData> as TryInto<$Int>>::try_into(int).map_err(|source| DecodeError::int::<$Int>(source.kind)),
+                    TokenTree::Literal(Literal::Int(int)) => <&'i $crate::de::Int<IData> as TryInto<$Int>>::try_into(int).map_err(|source| DecodeError::int_from::<$Int, _>(int, source.kind)),
                     _ => Err(DecodeError::unexpected(
                         SyrupKind::int::<$Int>(),
                         input
@@ -218,15 +227,15 @@ impl_decode_for_int!(
     NonZeroI128
 );
 
-impl<'i, IData, T: Decode<'i, IData> + Into<Wrapping<T>>> Decode<'i, IData> for Wrapping<T> {
+impl<'i, IData, T: Decode<'i, IData>> Decode<'i, IData> for Wrapping<T> {
     fn decode(input: &'i TokenTree<IData>) -> Result<Self, DecodeError> {
-        T::decode(input).map(T::into)
+        T::decode(input).map(Wrapping)
     }
 }
 
-impl<'i, IData, T: Decode<'i, IData> + Into<Saturating<T>>> Decode<'i, IData> for Saturating<T> {
+impl<'i, IData, T: Decode<'i, IData>> Decode<'i, IData> for Saturating<T> {
     fn decode(input: &'i TokenTree<IData>) -> Result<Self, DecodeError> {
-        T::decode(input).map(T::into)
+        T::decode(input).map(Saturating)
     }
 }
 
@@ -287,8 +296,8 @@ where
         match input {
             TokenTree::List(List { elements }) => {
                 let mut res = Vec::with_capacity(elements.len());
-                for token in elements {
-                    res.push(T::decode(token)?);
+                for (i, token) in elements.iter().enumerate() {
+                    res.push(T::decode(token).map_err(|e| e.at_index(i))?);
                 }
                 Ok(res)
             }
@@ -300,6 +309,44 @@ where
     }
 }
 
+macro_rules! impl_decode_from_list {
+    ($($List:ident),+) => {
+        $(
+        impl<'i, IData, T: Decode<'i, IData>> Decode<'i, IData> for $List<T>
+        where
+            IData: Bos<[u8]>,
+        {
+            fn decode(input: &'i TokenTree<IData>) -> Result<Self, DecodeError> {
+                match input {
+                    TokenTree::List(List { elements }) => elements
+                        .iter()
+                        .enumerate()
+                        .map(|(i, token)| T::decode(token).map_err(|e| e.at_index(i)))
+                        .collect(),
+                    _ => Err(DecodeError::unexpected(
+                        SyrupKind::List { length: None },
+                        input,
+                    )),
+                }
+            }
+        }
+        )+
+    };
+}
+
+impl_decode_from_list!(VecDeque, LinkedList);
+
+/// Always decodes to [`Cow::Owned`].
+impl<'i, IData, T> Decode<'i, IData> for Cow<'_, [T]>
+where
+    T: Clone,
+    Vec<T>: Decode<'i, IData>,
+{
+    fn decode(input: &'i TokenTree<IData>) -> Result<Self, DecodeError> {
+        Vec::<T>::decode(input).map(Cow::Owned)
+    }
+}
+
 impl<'i, IData, T, S> Decode<'i, IData> for HashSet<T, S>
 where
     T: Decode<'i, IData> + std::hash::Hash + Eq,
@@ -310,8 +357,8 @@ where
         match input {
             TokenTree::Set(set) => {
                 let mut res = HashSet::<T, S>::default();
-                for element in set {
-                    res.insert(element.decode()?);
+                for (i, element) in set.into_iter().enumerate() {
+                    res.insert(element.decode().map_err(|e| e.at_index(i))?);
                 }
                 Ok(res)
             }
@@ -329,8 +376,8 @@ where
         match input {
             TokenTree::Set(set) => {
                 let mut res = Self::default();
-                for element in set {
-                    res.insert(element.decode()?);
+                for (i, element) in set.into_iter().enumerate() {
+                    res.insert(element.decode().map_err(|e| e.at_index(i))?);
                 }
                 Ok(res)
             }
@@ -351,7 +398,8 @@ where
             TokenTree::Dictionary(dict) => {
                 let mut res = Self::default();
                 for (k, v) in dict {
-                    res.insert(K::decode(k)?, V::decode(v)?);
+                    let at_key = |e: DecodeError| e.at_key(k);
+                    res.insert(K::decode(k).map_err(at_key)?, V::decode(v).map_err(at_key)?);
                 }
                 Ok(res)
             }
@@ -371,7 +419,8 @@ where
             TokenTree::Dictionary(dict) => {
                 let mut res = Self::default();
                 for (k, v) in dict {
-                    res.insert(K::decode(k)?, V::decode(v)?);
+                    let at_key = |e: DecodeError| e.at_key(k);
+                    res.insert(K::decode(k).map_err(at_key)?, V::decode(v).map_err(at_key)?);
                 }
                 Ok(res)
             }
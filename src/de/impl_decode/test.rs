@@ -175,3 +175,31 @@ mod int {
         NonZeroI128 => decodes_non_zero_i128
     }
 }
+
+#[test]
+fn reports_collection_paths() {
+    use std::collections::BTreeMap;
+
+    use crate::de::{DecodeErrorKind, PathSegment};
+
+    let Err(DecodeBytesError::Decode(err)) = crate::decode_bytes!(b"[[]1\"x]" => Vec<Vec<u8>>)
+    else {
+        panic!("expected a decode error");
+    };
+    assert_eq!(err.path(), [PathSegment::Index(1)]);
+
+    let Err(DecodeBytesError::Decode(err)) =
+        crate::decode_bytes!(b"{1\"a1+1\"b300+}" => BTreeMap<String, u8>)
+    else {
+        panic!("expected a decode error");
+    };
+    assert_eq!(err.path(), [PathSegment::Key("\"b\"".to_owned())]);
+    assert_eq!(err.to_string(), format!("{{\"b\"}}: {}", err.kind()));
+    let DecodeErrorKind::ParseInt {
+        input: Some(input), ..
+    } = err.kind()
+    else {
+        panic!("expected an integer error, got {err:?}");
+    };
+    assert_eq!(&*input.encode(), b"300+");
+}
@@ -0,0 +1,292 @@
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque},
+    num::{
+        NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize, NonZeroU8,
+        NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize, Saturating, Wrapping,
+    },
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    bytes::Bytes,
+    de::{Decode, DecodeError, DecodeFrom, Literal, ReadError, Reader, SyrupKind, TokenTree},
+    symbol::Symbol,
+};
+
+#[cfg(test)]
+mod test;
+
+mod _impl_tuple {
+    use crate as syrup;
+
+    syrup_proc::impl_decode_from_for_tuple!(32);
+}
+
+impl<'i> DecodeFrom<'i> for () {
+    fn decode_from(reader: &mut Reader<'i>) -> Result<Self, ReadError> {
+        reader.begin_list(SyrupKind::List { length: Some(0) })?;
+        match reader.skip_rest()? {
+            0 => Ok(()),
+            found => Err(DecodeError::extra_elements(0, found).into()),
+        }
+    }
+}
+
+/// See the [`Decode`] impl for [`Option`].
+impl<'i, T: DecodeFrom<'i>> DecodeFrom<'i> for Option<T> {
+    fn decode_from(reader: &mut Reader<'i>) -> Result<Self, ReadError> {
+        if let Some(crate::de::Event::Literal(Literal::Bool(false))) = reader.peek_event()? {
+            reader.next_event()?;
+            return Ok(None);
+        }
+        T::decode_from(reader).map(Some)
+    }
+}
+
+macro_rules! impl_decode_from_for_wrapper {
+    ($($Wrapper:ident),+) => {
+        $(
+        impl<'i, T: DecodeFrom<'i>> DecodeFrom<'i> for $Wrapper<T> {
+            fn decode_from(reader: &mut Reader<'i>) -> Result<Self, ReadError> {
+                T::decode_from(reader).map($Wrapper::new)
+            }
+        }
+        )+
+    };
+}
+
+impl_decode_from_for_wrapper!(Box, Rc, Arc, Cell, RefCell, Mutex);
+
+impl<'i, T: DecodeFrom<'i>> DecodeFrom<'i> for Wrapping<T> {
+    fn decode_from(reader: &mut Reader<'i>) -> Result<Self, ReadError> {
+        T::decode_from(reader).map(Wrapping)
+    }
+}
+
+impl<'i, T: DecodeFrom<'i>> DecodeFrom<'i> for Saturating<T> {
+    fn decode_from(reader: &mut Reader<'i>) -> Result<Self, ReadError> {
+        T::decode_from(reader).map(Saturating)
+    }
+}
+
+impl<'i, T: DecodeFrom<'i>> DecodeFrom<'i> for Box<[T]> {
+    fn decode_from(reader: &mut Reader<'i>) -> Result<Self, ReadError> {
+        Vec::<T>::decode_from(reader).map(From::from)
+    }
+}
+
+/// Always decodes to [`Cow::Owned`].
+impl<'i, T: DecodeFrom<'i> + Clone> DecodeFrom<'i> for Cow<'_, [T]> {
+    fn decode_from(reader: &mut Reader<'i>) -> Result<Self, ReadError> {
+        Vec::<T>::decode_from(reader).map(Cow::Owned)
+    }
+}
+
+/// Reads the value at the current position as a tree; only literals avoid allocating.
+impl<'i, OData> DecodeFrom<'i> for TokenTree<OData>
+where
+    &'i [u8]: Into<OData>,
+{
+    fn decode_from(reader: &mut Reader<'i>) -> Result<Self, ReadError> {
+        Ok((&reader.read_tree()?).into())
+    }
+}
+
+/// Decodes literals through their [`Decode`] impls, which are cheap for a borrowed literal
+macro_rules! impl_decode_from_literal {
+    ($($Ty:ty => $expected:expr),+$(,)?) => {
+        $(
+        impl<'i> DecodeFrom<'i> for $Ty {
+            fn decode_from(reader: &mut Reader<'i>) -> Result<Self, ReadError> {
+                let literal = TokenTree::Literal(reader.read_literal($expected)?);
+                Ok(<$Ty>::decode(&literal)?)
+            }
+        }
+        )+
+    };
+}
+
+impl_decode_from_literal! {
+    bool => SyrupKind::Bool,
+    f32 => SyrupKind::F32,
+    f64 => SyrupKind::F64,
+    String => SyrupKind::String,
+    Box<str> => SyrupKind::String,
+    Rc<str> => SyrupKind::String,
+    Arc<str> => SyrupKind::String,
+}
+
+macro_rules! impl_decode_from_for_int {
+    ($($Int:ty),+$(,)?) => {
+        impl_decode_from_literal! {
+            $($Int => SyrupKind::int::<$Int>()),+
+        }
+    };
+}
+
+impl_decode_from_for_int!(
+    u8,
+    u16,
+    u32,
+    u64,
+    usize,
+    u128,
+    i8,
+    i16,
+    i32,
+    i64,
+    isize,
+    i128,
+    NonZeroU8,
+    NonZeroU16,
+    NonZeroU32,
+    NonZeroU64,
+    NonZeroUsize,
+    NonZeroU128,
+    NonZeroI8,
+    NonZeroI16,
+    NonZeroI32,
+    NonZeroI64,
+    NonZeroIsize,
+    NonZeroI128
+);
+
+impl<'i: 'o, 'o> DecodeFrom<'i> for &'o str {
+    fn decode_from(reader: &mut Reader<'i>) -> Result<Self, ReadError> {
+        let literal = TokenTree::Literal(reader.read_literal(SyrupKind::String)?);
+        Ok(literal.decode()?)
+    }
+}
+
+/// Borrows from the input.
+impl<'i: 'o, 'o> DecodeFrom<'i> for Cow<'o, str> {
+    fn decode_from(reader: &mut Reader<'i>) -> Result<Self, ReadError> {
+        <&'o str>::decode_from(reader).map(Cow::Borrowed)
+    }
+}
+
+impl<'i, Str> DecodeFrom<'i> for Symbol<Str>
+where
+    &'i str: Into<Str>,
+{
+    fn decode_from(reader: &mut Reader<'i>) -> Result<Self, ReadError> {
+        let literal = TokenTree::Literal(reader.read_literal(SyrupKind::Symbol(None))?);
+        Ok(literal.decode()?)
+    }
+}
+
+impl<'i, B> DecodeFrom<'i> for Bytes<B>
+where
+    &'i [u8]: Into<B>,
+{
+    fn decode_from(reader: &mut Reader<'i>) -> Result<Self, ReadError> {
+        let literal = TokenTree::Literal(reader.read_literal(SyrupKind::Bytes { length: None })?);
+        Ok(literal.decode()?)
+    }
+}
+
+/// Read the elements of the current container until it ends, then leave it.
+fn read_elements<'i, T, C>(reader: &mut Reader<'i>) -> Result<C, ReadError>
+where
+    T: DecodeFrom<'i>,
+    C: FromIterator<T>,
+{
+    let mut index = 0;
+    let res = std::iter::from_fn(|| match reader.at_end() {
+        Ok(true) => None,
+        Ok(false) => {
+            let element = T::decode_from(reader).map_err(|e| e.at_index(index));
+            index += 1;
+            Some(element)
+        }
+        Err(e) => Some(Err(e)),
+    })
+    .collect::<Result<C, ReadError>>()?;
+    reader.skip_rest()?;
+    Ok(res)
+}
+
+macro_rules! impl_decode_from_list {
+    ($($List:ident),+) => {
+        $(
+        impl<'i, T: DecodeFrom<'i>> DecodeFrom<'i> for $List<T> {
+            fn decode_from(reader: &mut Reader<'i>) -> Result<Self, ReadError> {
+                reader.begin_list(SyrupKind::List { length: None })?;
+                read_elements(reader)
+            }
+        }
+        )+
+    };
+}
+
+impl_decode_from_list!(Vec, VecDeque, LinkedList);
+
+impl<'i, T, S> DecodeFrom<'i> for HashSet<T, S>
+where
+    T: DecodeFrom<'i> + std::hash::Hash + Eq,
+    S: Default + std::hash::BuildHasher,
+{
+    fn decode_from(reader: &mut Reader<'i>) -> Result<Self, ReadError> {
+        reader.begin_set(SyrupKind::Set)?;
+        read_elements(reader)
+    }
+}
+
+impl<'i, T> DecodeFrom<'i> for BTreeSet<T>
+where
+    T: DecodeFrom<'i> + std::cmp::Ord,
+{
+    fn decode_from(reader: &mut Reader<'i>) -> Result<Self, ReadError> {
+        reader.begin_set(SyrupKind::Set)?;
+        read_elements(reader)
+    }
+}
+
+/// Read the entries of the current dictionary until it ends, then leave it.
+fn read_entries<'i, K, V, C>(reader: &mut Reader<'i>) -> Result<C, ReadError>
+where
+    K: DecodeFrom<'i>,
+    V: DecodeFrom<'i>,
+    C: Default + Extend<(K, V)>,
+{
+    let mut res = C::default();
+    while !reader.at_end()? {
+        // the key is only parsed again to locate an error
+        let key_input = reader.remaining();
+        let at_key = |e: ReadError| match TokenTree::<&[u8]>::parse_located(key_input) {
+            Ok((_, key)) => e.at_key(&key),
+            Err(_) => e,
+        };
+        let key = K::decode_from(reader).map_err(at_key)?;
+        let value = V::decode_from(reader).map_err(at_key)?;
+        res.extend([(key, value)]);
+    }
+    reader.skip_rest()?;
+    Ok(res)
+}
+
+impl<'i, K, V, S> DecodeFrom<'i> for HashMap<K, V, S>
+where
+    K: DecodeFrom<'i> + std::hash::Hash + Eq,
+    V: DecodeFrom<'i>,
+    S: Default + std::hash::BuildHasher,
+{
+    fn decode_from(reader: &mut Reader<'i>) -> Result<Self, ReadError> {
+        reader.begin_dictionary(SyrupKind::Dictionary)?;
+        read_entries(reader)
+    }
+}
+
+impl<'i, K, V> DecodeFrom<'i> for BTreeMap<K, V>
+where
+    K: DecodeFrom<'i> + std::cmp::Ord,
+    V: DecodeFrom<'i>,
+{
+    fn decode_from(reader: &mut Reader<'i>) -> Result<Self, ReadError> {
+        reader.begin_dictionary(SyrupKind::Dictionary)?;
+        read_entries(reader)
+    }
+}
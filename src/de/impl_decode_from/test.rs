@@ -0,0 +1,113 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+
+use proptest::prelude::*;
+
+use crate::{
+    Encode,
+    de::{DecodeErrorKind, DecodeFrom, PathSegment, ReadError, Reader},
+};
+
+/// Decode `input`, checking that the reader is left after the value
+fn read_all<'i, T: DecodeFrom<'i>>(input: &'i [u8]) -> T {
+    let mut reader = Reader::new(input);
+    let res = reader.read::<T>().unwrap();
+    assert_eq!(reader.position(), input.len());
+    res
+}
+
+fn read_err<'i, T: DecodeFrom<'i> + std::fmt::Debug>(input: &'i [u8]) -> ReadError {
+    Reader::new(input).read::<T>().unwrap_err()
+}
+
+#[test]
+fn reads_literals() {
+    assert!(read_all::<bool>(b"t"));
+    assert_eq!(read_all::<u16>(b"300+"), 300);
+    assert_eq!(read_all::<i8>(b"5-"), -5);
+    assert_eq!(read_all::<String>(b"2\"hi"), "hi");
+    assert_eq!(read_all::<Option<u8>>(b"f"), None);
+    assert_eq!(read_all::<Option<u8>>(b"3+"), Some(3));
+    assert_eq!(read_all::<()>(b"[]"), ());
+}
+
+#[test]
+fn borrows_strings_from_input() {
+    let input = b"[5\"hello3\"you]";
+    let words: Vec<&str> = Reader::new(input).read().unwrap();
+    assert_eq!(words, ["hello", "you"]);
+    assert!(
+        words
+            .iter()
+            .all(|word| input.as_ptr_range().contains(&word.as_ptr()))
+    );
+}
+
+#[test]
+fn reads_collections() {
+    assert_eq!(read_all::<Vec<u8>>(b"[1+2+3+]"), [1, 2, 3]);
+    assert_eq!(
+        read_all::<VecDeque<bool>>(b"[tf]"),
+        VecDeque::from([true, false])
+    );
+    assert_eq!(
+        read_all::<BTreeSet<String>>(b"#1\"a1\"b$"),
+        BTreeSet::from(["a".to_owned(), "b".to_owned()])
+    );
+    assert_eq!(
+        read_all::<HashMap<String, u8>>(b"{1\"a1+1\"b2+}"),
+        HashMap::from([("a".to_owned(), 1), ("b".to_owned(), 2)])
+    );
+    assert_eq!(read_all::<(u8, String)>(b"[1+1\"x]"), (1, "x".to_owned()));
+}
+
+#[test]
+fn skips_surplus_tuple_elements() {
+    let mut reader = Reader::new(b"[1+[2+#$]3:xyz]t");
+    assert_eq!(reader.read::<(u8,)>().unwrap(), (1,));
+    assert!(reader.read::<bool>().unwrap());
+}
+
+#[test]
+fn reports_paths() {
+    let err = read_err::<Vec<(u8, u8)>>(b"[[1+2+][3+t]]");
+    let ReadError::Decode(err) = err else {
+        panic!("{err:?}")
+    };
+    assert_eq!(err.path(), [PathSegment::Index(1), PathSegment::Index(1)]);
+
+    let err = read_err::<BTreeMap<String, u8>>(b"{1\"a1+1\"b1\"x}");
+    let ReadError::Decode(err) = err else {
+        panic!("{err:?}")
+    };
+    assert!(err.to_string().starts_with("{\"b\"}: expected: "), "{err}");
+}
+
+#[test]
+fn reports_missing_elements() {
+    let err = read_err::<(u8, u8)>(b"[1+]");
+    assert!(
+        matches!(&err, ReadError::Decode(e) if matches!(e.kind(), DecodeErrorKind::Missing(_))),
+        "{err:?}"
+    );
+    assert!(matches!(
+        read_err::<()>(b"[1+]"),
+        ReadError::Decode(e) if matches!(e.kind(), DecodeErrorKind::ExtraElements { expected: 0, found: 1 })
+    ));
+}
+
+#[test]
+fn keeps_syntax_errors() {
+    assert!(matches!(
+        read_err::<Vec<u8>>(b"[1+2+"),
+        ReadError::Incomplete { offset: 5 }
+    ));
+}
+
+proptest! {
+    #[test]
+    fn round_trips_encode(map: HashMap<String, (Vec<i64>, Option<i16>)>) {
+        let bytes = Encode::<Vec<u8>>::encode(&map).to_bytes().into_owned();
+        let read: HashMap<String, (Vec<i64>, Option<i16>)> = Reader::new(&bytes).read().unwrap();
+        prop_assert_eq!(read, map);
+    }
+}
@@ -0,0 +1,338 @@
+use std::cell::OnceCell;
+
+use borrow_or_share::Bos;
+
+use crate::{
+    de::{
+        Decode, DecodeFrom, Event, LimitExceeded, ParseLimits, ReadError, Reader, SyrupKind,
+        TokenTree,
+    },
+    symbol::Symbol,
+};
+
+#[cfg(test)]
+mod test;
+
+/// A value in syrup input, whose collections are only scanned once they're looked into.
+///
+/// Creating a `LazyTokenTree` reads nothing. The first time a collection's contents are accessed,
+/// its input is scanned once to find where each child starts and ends; the children themselves
+/// are scanned the same way when they're accessed in turn. This makes it cheap to look at part of
+/// a large message, such as the label of a record and its first element, then [`decode`] just
+/// the parts that are needed.
+///
+/// ```
+/// # use syrup::{de::LazyTokenTree, symbol::Symbol};
+/// let input = b"<10'op:deliver3+[4'ping]{2'to5'carol}>";
+/// let message = LazyTokenTree::new(input);
+/// assert_eq!(message.label()?.decode::<Symbol<&str>>()?, Symbol("op:deliver"));
+/// assert_eq!(message.len()?, 3);
+/// let Some(target) = message.get(0)? else { panic!() };
+/// assert_eq!(target.decode::<u64>()?, 3);
+/// let Some(to) = message.get(2)?.unwrap().get(Symbol("to"))? else { panic!() };
+/// assert_eq!(to.as_bytes()?, b"5'carol");
+/// # Ok::<(), syrup::de::ReadError>(())
+/// ```
+///
+/// Since input is only checked as it's scanned, malformed input is reported by whichever access
+/// first reaches it, with offsets into the whole input.
+///
+/// [`decode`]: LazyTokenTree::decode
+#[derive(Clone)]
+pub struct LazyTokenTree<'a> {
+    /// The input from the start of this value; only the root's may extend past its end
+    input: &'a [u8],
+    /// Where `input` starts in the whole input
+    offset: usize,
+    /// The length of this value, once it's known
+    len: OnceCell<usize>,
+    children: OnceCell<Result<Children<'a>, ReadError>>,
+}
+
+#[derive(Clone)]
+struct Children<'a> {
+    kind: Kind,
+    /// For records, the label and then the elements; for dictionaries, keys alternating with
+    /// their values
+    nodes: Vec<LazyTokenTree<'a>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Literal,
+    List,
+    Record,
+    Set,
+    Dictionary,
+}
+
+impl<'a> LazyTokenTree<'a> {
+    /// A lazy tree for the value at the start of `input`. Any input after the value is ignored.
+    #[must_use]
+    pub const fn new(input: &'a [u8]) -> Self {
+        Self {
+            input,
+            offset: 0,
+            len: OnceCell::new(),
+            children: OnceCell::new(),
+        }
+    }
+
+    fn child(&self, start: usize, end: usize) -> Self {
+        Self {
+            input: &self.input[start..end],
+            offset: self.offset + start,
+            len: OnceCell::from(end - start),
+            children: OnceCell::new(),
+        }
+    }
+
+    /// Where this value starts in the input the root was created from
+    #[inline]
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Scan this value's children, if it hasn't been done yet.
+    fn children(&self) -> Result<&Children<'a>, ReadError> {
+        self.children
+            .get_or_init(|| self.scan().map_err(|e| self.shift(e)))
+            .as_ref()
+            .map_err(Clone::clone)
+    }
+
+    fn scan(&self) -> Result<Children<'a>, ReadError> {
+        let mut reader = Reader::new(self.input);
+        let kind = match reader.next_event()? {
+            Some(Event::Literal(_)) => Kind::Literal,
+            Some(Event::ListStart) => Kind::List,
+            Some(Event::RecordStart) => Kind::Record,
+            Some(Event::SetStart) => Kind::Set,
+            Some(Event::DictStart) => Kind::Dictionary,
+            // an unmatched close is an error, so only the end of the input is left
+            Some(Event::End) | None => return Err(ReadError::Incomplete { offset: 0 }),
+        };
+        let mut nodes = Vec::new();
+        if kind != Kind::Literal {
+            while !reader.at_end()? {
+                let start = reader.position();
+                reader.skip_value()?;
+                nodes.push(self.child(start, reader.position()));
+            }
+            reader.next_event()?;
+        }
+        // children always know their length already
+        let _ = self.len.set(reader.position());
+        Ok(Children { kind, nodes })
+    }
+
+    /// Make the offsets in an error from a reader over `self.input` relative to the whole input.
+    fn shift(&self, error: ReadError) -> ReadError {
+        let by = self.offset;
+        match error {
+            ReadError::Syntax { offset, kind } => ReadError::Syntax {
+                offset: offset + by,
+                kind,
+            },
+            ReadError::Incomplete { offset } => ReadError::Incomplete {
+                offset: offset + by,
+            },
+            ReadError::UnexpectedClose { offset, found } => ReadError::UnexpectedClose {
+                offset: offset + by,
+                found,
+            },
+            ReadError::MissingLabel { offset } => ReadError::MissingLabel {
+                offset: offset + by,
+            },
+            ReadError::MissingValue { offset } => ReadError::MissingValue {
+                offset: offset + by,
+            },
+            ReadError::Limit(e) => ReadError::Limit(LimitExceeded {
+                offset: e.offset + by,
+                ..e
+            }),
+            e @ ReadError::Decode(_) => e,
+        }
+    }
+
+    /// Fail with `expected`, reporting the kind of this value instead.
+    fn unexpected<T>(&self, expected: SyrupKind) -> Result<T, ReadError> {
+        Reader::new(self.input)
+            .unexpected(expected)
+            .map_err(|e| self.shift(e))
+    }
+
+    /// The encoded value, exactly as it appears in the input.
+    pub fn as_bytes(&self) -> Result<&'a [u8], ReadError> {
+        let len = match self.len.get() {
+            Some(&len) => len,
+            None => {
+                self.children()?;
+                self.len.get().copied().unwrap_or_default()
+            }
+        };
+        Ok(&self.input[..len])
+    }
+
+    /// The label of this record.
+    pub fn label(&self) -> Result<&Self, ReadError> {
+        let children = self.children()?;
+        match children.kind {
+            Kind::Record => Ok(&children.nodes[0]),
+            _ => self.unexpected(SyrupKind::Record { label: None }),
+        }
+    }
+
+    /// The elements of this list or record (not counting a record's label), the entries of this
+    /// set, or the key-value pairs of this dictionary, in the order they appear in the input.
+    fn elements(&self) -> Result<&[Self], ReadError> {
+        let children = self.children()?;
+        match children.kind {
+            Kind::List | Kind::Set | Kind::Dictionary => Ok(&children.nodes),
+            Kind::Record => Ok(&children.nodes[1..]),
+            Kind::Literal => self.unexpected(SyrupKind::List { length: None }),
+        }
+    }
+
+    /// The number of elements in this list or record (not counting a record's label), entries in
+    /// this set, or entries in this dictionary.
+    #[expect(
+        clippy::len_without_is_empty,
+        reason = "`is_empty` would also need a `Result`"
+    )]
+    pub fn len(&self) -> Result<usize, ReadError> {
+        let len = self.elements()?.len();
+        match self.children()?.kind {
+            Kind::Dictionary => Ok(len / 2),
+            _ => Ok(len),
+        }
+    }
+
+    /// Look up an element of this list, record or set by position, or a value in this dictionary
+    /// by key; see [`LazyIndex`].
+    ///
+    /// Returns `None` if the index is out of range, or the key isn't present.
+    pub fn get(&self, index: impl LazyIndex) -> Result<Option<&Self>, ReadError> {
+        index.index_into(self)
+    }
+
+    /// The key-value pairs of this dictionary, in the order they appear in the input.
+    pub fn entries(&self) -> Result<impl Iterator<Item = (&Self, &Self)>, ReadError> {
+        match self.children()?.kind {
+            Kind::Dictionary => Ok(self
+                .elements()?
+                .chunks_exact(2)
+                .map(|entry| (&entry[0], &entry[1]))),
+            _ => self.unexpected(SyrupKind::Dictionary),
+        }
+    }
+
+    /// Parse the whole value into a [`TokenTree`], within the default
+    /// [`ParseLimits`](crate::de::ParseLimits).
+    pub fn to_tree(&self) -> Result<TokenTree<&'a [u8]>, ReadError> {
+        let input = match self.len.get() {
+            Some(&len) => &self.input[..len],
+            None => self.input,
+        };
+        Reader::new(input).read_tree().map_err(|e| self.shift(e))
+    }
+
+    /// Decode the value with [`Decode`], by way of a [`TokenTree`] of just this value.
+    ///
+    /// The tree doesn't outlive the call, so types that borrow from it can't be decoded this way;
+    /// [`read`](Self::read) them instead.
+    pub fn decode<T>(&self) -> Result<T, ReadError>
+    where
+        T: for<'t> Decode<'t, &'a [u8]>,
+    {
+        Ok(T::decode(&self.to_tree()?)?)
+    }
+
+    /// Decode the value with [`DecodeFrom`], reading it straight from the input.
+    pub fn read<T: DecodeFrom<'a>>(&self) -> Result<T, ReadError> {
+        Reader::new(self.as_bytes()?)
+            .read()
+            .map_err(|e| self.shift(e))
+    }
+}
+
+impl std::fmt::Debug for LazyTokenTree<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.len.get() {
+            Some(&len) => write!(
+                f,
+                "LazyTokenTree(b\"{}\")",
+                self.input[..len].escape_ascii()
+            ),
+            None => write!(f, "LazyTokenTree(b\"{}\"..)", self.input.escape_ascii()),
+        }
+    }
+}
+
+/// A way of looking up part of a [`LazyTokenTree`], with [`LazyTokenTree::get`].
+///
+/// Positions (`usize`) index into lists, records and sets; [`TokenTree`]s and [`Symbol`]s look up
+/// dictionary keys, comparing them in canonical form.
+pub trait LazyIndex {
+    fn index_into<'t, 'a>(
+        self,
+        tree: &'t LazyTokenTree<'a>,
+    ) -> Result<Option<&'t LazyTokenTree<'a>>, ReadError>;
+}
+
+impl LazyIndex for usize {
+    fn index_into<'t, 'a>(
+        self,
+        tree: &'t LazyTokenTree<'a>,
+    ) -> Result<Option<&'t LazyTokenTree<'a>>, ReadError> {
+        match tree.children()?.kind {
+            Kind::Dictionary => tree.unexpected(SyrupKind::List { length: None }),
+            _ => Ok(tree.elements()?.get(self)),
+        }
+    }
+}
+
+/// Find the value for the key encoded as `key`.
+fn lookup<'t, 'a>(
+    tree: &'t LazyTokenTree<'a>,
+    key: &[u8],
+) -> Result<Option<&'t LazyTokenTree<'a>>, ReadError> {
+    // keys are usually in canonical form already, so their bytes can be compared as they are
+    for (k, v) in tree.entries()? {
+        if k.as_bytes()? == key {
+            return Ok(Some(v));
+        }
+    }
+    // only a key that isn't canonical can encode the same value differently
+    for (k, v) in tree.entries()? {
+        let canonical = TokenTree::<&[u8]>::parse_canonical::<nom::error::Error<&[u8]>>(
+            k.as_bytes()?,
+            &ParseLimits::default(),
+        )
+        .is_ok();
+        if !canonical && *k.to_tree()?.to_bytes() == *key {
+            return Ok(Some(v));
+        }
+    }
+    Ok(None)
+}
+
+impl<Data: Bos<[u8]>> LazyIndex for &TokenTree<Data> {
+    fn index_into<'t, 'a>(
+        self,
+        tree: &'t LazyTokenTree<'a>,
+    ) -> Result<Option<&'t LazyTokenTree<'a>>, ReadError> {
+        lookup(tree, &self.to_bytes())
+    }
+}
+
+impl<S: Bos<str>> LazyIndex for Symbol<S> {
+    fn index_into<'t, 'a>(
+        self,
+        tree: &'t LazyTokenTree<'a>,
+    ) -> Result<Option<&'t LazyTokenTree<'a>>, ReadError> {
+        let key = crate::symbol::encode::<&[u8], _>(self.as_str().as_bytes());
+        lookup(tree, &key.to_bytes())
+    }
+}
@@ -0,0 +1,184 @@
+use crate::{
+    Decode, DecodeFrom, TokenTree,
+    de::{DecodeErrorKind, LazyTokenTree, Limit, LimitExceeded, Literal, ReadError, SyrupKind},
+    symbol::Symbol,
+};
+
+fn bytes<'a>(tree: Option<&LazyTokenTree<'a>>) -> &'a [u8] {
+    tree.unwrap().as_bytes().unwrap()
+}
+
+#[test]
+fn indexes_collections() {
+    let tree = LazyTokenTree::new(b"<3'foo[1+2+]#3+4+${1'a5+1'b[]}>trailing");
+    assert_eq!(bytes(Some(tree.label().unwrap())), b"3'foo");
+    assert_eq!(tree.len().unwrap(), 3);
+    assert_eq!(tree.as_bytes().unwrap(), b"<3'foo[1+2+]#3+4+${1'a5+1'b[]}>");
+
+    let list = tree.get(0).unwrap().unwrap();
+    assert_eq!(list.len().unwrap(), 2);
+    assert_eq!(bytes(list.get(1).unwrap()), b"2+");
+    assert!(list.get(2).unwrap().is_none());
+
+    let set = tree.get(1).unwrap().unwrap();
+    assert_eq!(bytes(set.get(0).unwrap()), b"3+");
+
+    let dict = tree.get(2).unwrap().unwrap();
+    assert_eq!(dict.len().unwrap(), 2);
+    assert_eq!(bytes(dict.get(Symbol("b")).unwrap()), b"[]");
+    let key = TokenTree::Literal(Literal::Symbol(&b"a"[..]));
+    assert_eq!(bytes(dict.get(&key).unwrap()), b"5+");
+    assert!(dict.get(Symbol("c")).unwrap().is_none());
+    let entries: Vec<_> = dict
+        .entries()
+        .unwrap()
+        .map(|(k, v)| (k.as_bytes().unwrap(), v.as_bytes().unwrap()))
+        .collect();
+    assert_eq!(entries, [(&b"1'a"[..], &b"5+"[..]), (b"1'b", b"[]")]);
+}
+
+#[test]
+fn finds_non_canonical_keys() {
+    let tree = LazyTokenTree::new(b"{01+1'a}");
+    let key = TokenTree::Literal(Literal::Int(1u8.into()));
+    assert_eq!(bytes(tree.get(&key).unwrap()), b"1'a");
+    let tree = LazyTokenTree::new(b"{1'b2+001+1'a1'c3+}");
+    assert_eq!(bytes(tree.get(&key).unwrap()), b"1'a");
+    assert_eq!(bytes(tree.get(Symbol("b")).unwrap()), b"2+");
+    let missing = TokenTree::Literal(Literal::Int(2u8.into()));
+    assert!(tree.get(&missing).unwrap().is_none());
+}
+
+#[test]
+fn only_scans_what_is_accessed() {
+    let tree = LazyTokenTree::new(b"[[1+2+][3+4+]]");
+    assert!(tree.children.get().is_none());
+    assert_eq!(tree.len().unwrap(), 2);
+    let [first, second] = &tree.children.get().unwrap().as_ref().unwrap().nodes[..] else {
+        panic!()
+    };
+    assert_eq!(first.len().unwrap(), 2);
+    assert!(first.children.get().is_some());
+    assert!(second.children.get().is_none());
+}
+
+#[test]
+fn reports_errors_at_their_offset() {
+    let tree = LazyTokenTree::new(b"[1+[2+ 3+]]");
+    assert!(matches!(
+        tree.get(1),
+        Err(ReadError::Syntax { offset: 6, .. })
+    ));
+    // the error is kept
+    assert!(matches!(
+        tree.len(),
+        Err(ReadError::Syntax { offset: 6, .. })
+    ));
+
+    let tree = LazyTokenTree::new(b"[1+<3'pos1+2+>]");
+    let record = tree.get(1).unwrap().unwrap();
+    assert_eq!(record.offset(), 3);
+    assert!(matches!(
+        record.read::<Vec<u8>>(),
+        Err(ReadError::Decode(_))
+    ));
+    assert!(matches!(
+        LazyTokenTree::new(b"[1+").len(),
+        Err(ReadError::Incomplete { offset: 3 })
+    ));
+    assert!(matches!(
+        LazyTokenTree::new(b"").len(),
+        Err(ReadError::Incomplete { offset: 0 })
+    ));
+    assert!(matches!(
+        LazyTokenTree::new(b"}").len(),
+        Err(ReadError::UnexpectedClose { offset: 0, .. })
+    ));
+}
+
+#[test]
+fn rejects_the_wrong_kind() {
+    let tree = LazyTokenTree::new(b"[1+]");
+    let Err(ReadError::Decode(e)) = tree.label() else {
+        panic!()
+    };
+    assert!(e.to_string().contains("record"), "{e}");
+    assert!(tree.get(Symbol("a")).is_err());
+    assert!(LazyTokenTree::new(b"{}").get(0).is_err());
+    assert!(LazyTokenTree::new(b"1+").len().is_err());
+
+    let mut deep = b"[".to_vec();
+    deep.extend(std::iter::repeat_n(b'[', 200_000));
+    deep.extend(std::iter::repeat_n(b']', 200_001));
+    let tree = LazyTokenTree::new(&deep);
+    // only the start of the value is read to say what it is
+    assert!(matches!(
+        tree.get(0).unwrap().unwrap().label(),
+        Err(ReadError::Decode(e)) if matches!(
+            e.kind(),
+            DecodeErrorKind::Unexpected { found: SyrupKind::List { length: None }, .. }
+        )
+    ));
+    assert!(matches!(
+        tree.get(0).unwrap().unwrap().to_tree(),
+        Err(ReadError::Limit(LimitExceeded {
+            limit: Limit::Depth,
+            offset: 129
+        }))
+    ));
+}
+
+#[test]
+fn decodes_parts() {
+    #[derive(Decode, Debug, PartialEq)]
+    #[syrup(label = "pos")]
+    struct Pos {
+        x: u8,
+        name: String,
+    }
+
+    #[derive(DecodeFrom, Debug, PartialEq)]
+    #[syrup(label = "pos")]
+    struct BorrowedPos<'a> {
+        x: u8,
+        name: &'a str,
+    }
+
+    let input = b"[<3'pos1+3\"one>5\"other]";
+    let tree = LazyTokenTree::new(input);
+    let pos = tree.get(0).unwrap().unwrap();
+    assert_eq!(
+        pos.decode::<Pos>().unwrap(),
+        Pos {
+            x: 1,
+            name: "one".into()
+        }
+    );
+    assert_eq!(
+        pos.read::<BorrowedPos<'_>>().unwrap(),
+        BorrowedPos { x: 1, name: "one" }
+    );
+    assert_eq!(
+        tree.get(1).unwrap().unwrap().read::<&str>().unwrap(),
+        "other"
+    );
+    let Err(ReadError::Decode(e)) = tree.get(1).unwrap().unwrap().decode::<u8>() else {
+        panic!()
+    };
+    assert!(matches!(
+        e.kind(),
+        DecodeErrorKind::Unexpected {
+            expected: SyrupKind::Int { .. },
+            ..
+        }
+    ));
+    assert_eq!(tree.to_tree().unwrap().to_bytes(), &input[..]);
+}
+
+#[test]
+fn shares_the_scan_between_accesses() {
+    let tree = LazyTokenTree::new(b"[1+2+3+]");
+    let first = tree.get(0).unwrap().unwrap();
+    assert_eq!(tree.len().unwrap(), 3);
+    assert!(std::ptr::eq(first, tree.get(0).unwrap().unwrap()));
+}
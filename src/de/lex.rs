@@ -9,6 +9,29 @@ pub use literal::*;
 mod collection;
 pub use collection::*;
 
+mod limits;
+pub use limits::*;
+
+mod canonical;
+pub use canonical::*;
+
+mod span;
+pub use span::*;
+
+mod text;
+pub use text::*;
+
+mod access;
+
+mod path;
+pub use path::*;
+
+mod visit;
+pub use visit::*;
+
+mod error;
+pub use error::*;
+
 use crate::de::{Decode, DecodeError, SyrupKind};
 
 #[cfg(test)]
@@ -47,7 +70,14 @@ where
     Data: Bos<[u8]>,
 {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.to_bytes().hash(state);
+        std::mem::discriminant(self).hash(state);
+        match self {
+            TokenTree::Dictionary(d) => d.hash(state),
+            TokenTree::List(l) => l.hash(state),
+            TokenTree::Record(r) => r.hash(state),
+            TokenTree::Set(s) => s.hash(state),
+            TokenTree::Literal(l) => l.hash(state),
+        }
     }
 }
 
@@ -96,8 +126,13 @@ impl<Data> TokenTree<Data> {
         }
     }
 
+    /// Parse a single value from the front of `i`.
+    ///
+    /// Nesting depth and literal lengths aren't bounded; use [`TokenTree::parse_with_limits`]
+    /// for untrusted input.
     pub fn parse<'i, E: ParseLiteralError<'i>>(i: &'i [u8]) -> IResult<&'i [u8], Self, E>
     where
+        Data: Bos<[u8]>,
         &'i [u8]: Into<Data>,
     {
         Literal::parse
@@ -109,6 +144,15 @@ impl<Data> TokenTree<Data> {
             .parse(i)
     }
 
+    /// Like [`TokenTree::parse`], but errors know their offset into `i`.
+    pub fn parse_located<'i>(i: &'i [u8]) -> IResult<&'i [u8], Self, ParseError<'i>>
+    where
+        Data: Bos<[u8]>,
+        &'i [u8]: Into<Data>,
+    {
+        Self::parse::<ParseError<'i>>(i).map_err(|e| e.map(|e| e.with_origin(i)))
+    }
+
     #[inline]
     pub fn decode<'input, Output: Decode<'input, Data>>(
         &'input self,
@@ -0,0 +1,157 @@
+use borrow_or_share::BorrowOrShare;
+
+use crate::{
+    de::lex::{Dictionary, Int, List, Literal, Record, Set, TokenTree},
+    symbol::Symbol,
+};
+
+#[cfg(test)]
+mod test;
+
+/// Accessors for looking into a tree without matching on it, each returning `None` if the tree
+/// isn't the kind of value asked for.
+///
+/// ```
+/// # use syrup::{TokenTree, symbol::Symbol};
+/// let tree: TokenTree<Vec<u8>> = r#"<op:deliver 3 ["ping" 7]>"#.parse().unwrap();
+/// let message = tree.as_record().unwrap();
+/// assert_eq!(message.label_symbol(), Some(Symbol("op:deliver")));
+/// assert_eq!(message.elements[0].as_int::<i64>(), Some(3));
+/// let args = &message.elements[1].as_list().unwrap().elements;
+/// assert_eq!(args[0].as_str(), Some("ping"));
+/// assert_eq!(args[1].as_str(), None);
+/// ```
+impl<Data> TokenTree<Data> {
+    #[inline]
+    #[must_use]
+    pub const fn as_literal(&self) -> Option<&Literal<Data>> {
+        match self {
+            TokenTree::Literal(literal) => Some(literal),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn as_list(&self) -> Option<&List<Data>> {
+        match self {
+            TokenTree::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn as_record(&self) -> Option<&Record<Data>> {
+        match self {
+            TokenTree::Record(record) => Some(record),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn as_set(&self) -> Option<&Set<Data>> {
+        match self {
+            TokenTree::Set(set) => Some(set),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn as_dictionary(&self) -> Option<&Dictionary<Data>> {
+        match self {
+            TokenTree::Dictionary(dict) => Some(dict),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn as_bool(&self) -> Option<bool> {
+        match self {
+            TokenTree::Literal(Literal::Bool(b)) => Some(*b),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn as_f32(&self) -> Option<f32> {
+        match self {
+            TokenTree::Literal(Literal::F32(f)) => Some(*f),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn as_f64(&self) -> Option<f64> {
+        match self {
+            TokenTree::Literal(Literal::F64(f)) => Some(*f),
+            _ => None,
+        }
+    }
+
+    /// This integer, if it fits in an `I`.
+    #[must_use]
+    pub fn as_int<I>(&self) -> Option<I>
+    where
+        I: for<'t> TryFrom<&'t Int<Data>>,
+    {
+        match self {
+            TokenTree::Literal(Literal::Int(int)) => I::try_from(int).ok(),
+            _ => None,
+        }
+    }
+
+    /// The contents of this byte string
+    #[must_use]
+    pub fn as_bytes<'i, 'o>(&'i self) -> Option<&'o [u8]>
+    where
+        Data: BorrowOrShare<'i, 'o, [u8]>,
+    {
+        match self {
+            TokenTree::Literal(Literal::Bytes(bytes)) => Some(bytes.borrow_or_share()),
+            _ => None,
+        }
+    }
+
+    /// The contents of this string, if they're valid UTF-8
+    #[must_use]
+    pub fn as_str<'i, 'o>(&'i self) -> Option<&'o str>
+    where
+        Data: BorrowOrShare<'i, 'o, [u8]>,
+    {
+        match self {
+            TokenTree::Literal(Literal::String(s)) => std::str::from_utf8(s.borrow_or_share()).ok(),
+            _ => None,
+        }
+    }
+
+    /// This symbol, if it's valid UTF-8
+    #[must_use]
+    pub fn as_symbol<'i, 'o>(&'i self) -> Option<Symbol<&'o str>>
+    where
+        Data: BorrowOrShare<'i, 'o, [u8]>,
+    {
+        match self {
+            TokenTree::Literal(Literal::Symbol(s)) => {
+                std::str::from_utf8(s.borrow_or_share()).ok().map(Symbol)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<Data> Record<Data> {
+    /// The label of this record, if it's a symbol, as labels usually are
+    #[must_use]
+    pub fn label_symbol<'i, 'o>(&'i self) -> Option<Symbol<&'o str>>
+    where
+        Data: BorrowOrShare<'i, 'o, [u8]>,
+    {
+        self.label.as_symbol()
+    }
+}
@@ -0,0 +1,94 @@
+use proptest::prelude::*;
+
+use crate::{
+    de::lex::{TokenTree, test::MaxDepth},
+    symbol::Symbol,
+};
+
+fn parse(text: &str) -> TokenTree<Vec<u8>> {
+    text.parse()
+        .unwrap_or_else(|e| panic!("failed to parse {text:?}: {e}"))
+}
+
+#[test]
+fn reads_literals() {
+    assert_eq!(parse("#t").as_bool(), Some(true));
+    assert_eq!(parse("1.5f").as_f32(), Some(1.5));
+    assert_eq!(parse("1.5").as_f64(), Some(1.5));
+    assert_eq!(parse("1.5").as_f32(), None);
+    assert_eq!(parse("-300").as_int::<i64>(), Some(-300));
+    assert_eq!(parse("-300").as_int::<u64>(), None);
+    assert_eq!(parse("300").as_int::<u8>(), None);
+    assert_eq!(parse("\"300\"").as_int::<u64>(), None);
+    assert_eq!(parse("#\"ab\"").as_bytes(), Some(b"ab".as_slice()));
+    assert_eq!(parse("\"ab\"").as_bytes(), None);
+    assert_eq!(parse("\"ab\"").as_str(), Some("ab"));
+    assert_eq!(parse("ab").as_str(), None);
+    assert_eq!(parse("ab").as_symbol(), Some(Symbol("ab")));
+    assert_eq!(parse("\"ab\"").as_symbol(), None);
+    // strings and symbols that aren't UTF-8 can't be read as `str`s
+    let (_, invalid) = TokenTree::<&[u8]>::parse::<nom::error::Error<_>>(b"1\"\xff").unwrap();
+    assert_eq!(invalid.as_str(), None);
+}
+
+#[test]
+fn borrows_from_the_input() {
+    let input = b"<4'open[5\"hello]>";
+    let text = {
+        let (_, tree) = TokenTree::<&[u8]>::parse::<nom::error::Error<_>>(input).unwrap();
+        let record = tree.as_record().unwrap();
+        assert_eq!(record.label_symbol(), Some(Symbol("open")));
+        record.elements[0].as_list().unwrap().elements[0]
+            .as_str()
+            .unwrap()
+    };
+    assert_eq!(text, "hello");
+}
+
+#[test]
+fn looks_up_entries() {
+    let tree = parse(r#"{to: "carol", "to": 1, 2: [], [1 2]: #f}"#);
+    let dict = tree.as_dictionary().unwrap();
+    assert_eq!(dict.get(&Symbol("to")), Some(&parse("\"carol\"")));
+    assert_eq!(dict.get("to"), Some(&parse("1")));
+    assert_eq!(dict.get(&2u32), Some(&parse("[]")));
+    assert_eq!(dict.get(&vec![1u8, 2]), Some(&parse("#f")));
+    assert_eq!(dict.get(&3u32), None);
+
+    // keys are compared in canonical form
+    let (_, tree) = TokenTree::<&[u8]>::parse::<nom::error::Error<_>>(b"{02+1'a}").unwrap();
+    let found = tree.as_dictionary().unwrap().get(&2u8).unwrap();
+    assert_eq!(found.as_symbol(), Some(Symbol("a")));
+
+    let tree = parse("#{1 two \"three\"}");
+    let set = tree.as_set().unwrap();
+    assert!(set.contains(&1u8));
+    assert!(set.contains(&Symbol("two")));
+    assert!(set.contains("three"));
+    assert!(!set.contains("two"));
+    assert_eq!(parse("[]").as_set(), None);
+}
+
+proptest! {
+    #[test]
+    fn matches_only_its_kind(tree in TokenTree::arbitrary_with(MaxDepth(1))) {
+        let kinds = [
+            tree.as_literal().is_some(),
+            tree.as_list().is_some(),
+            tree.as_record().is_some(),
+            tree.as_set().is_some(),
+            tree.as_dictionary().is_some(),
+        ];
+        prop_assert_eq!(kinds.iter().filter(|&&kind| kind).count(), 1);
+        if let TokenTree::Dictionary(dict) = &tree {
+            for (key, _) in dict {
+                prop_assert!(dict.get(key).is_some());
+            }
+        }
+        if let TokenTree::Set(set) = &tree {
+            for entry in set {
+                prop_assert!(set.contains(entry));
+            }
+        }
+    }
+}
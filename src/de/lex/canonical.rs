@@ -0,0 +1,105 @@
+use borrow_or_share::Bos;
+
+use crate::de::lex::{
+    LimitedParseError, LimitedParser, ParseError, ParseLimits, ParseLiteralError, TokenTree,
+};
+
+#[cfg(test)]
+mod test;
+
+/// A rule of syrup's canonical form, as checked by [`TokenTree::parse_canonical`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CanonicalRule {
+    /// Integers and length prefixes have no leading zeros
+    LeadingZero,
+    /// Zero is written `0+`, never `0-`
+    NegativeZero,
+    /// Set members and dictionary keys are sorted by their encodings
+    Unsorted,
+    /// Set members and dictionary keys appear only once
+    Duplicate,
+}
+
+impl std::fmt::Display for CanonicalRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            CanonicalRule::LeadingZero => "number with a leading zero",
+            CanonicalRule::NegativeZero => "negative zero",
+            CanonicalRule::Unsorted => "entry out of order",
+            CanonicalRule::Duplicate => "duplicate entry",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+#[error("non-canonical input at byte {offset}: {rule}")]
+pub struct NotCanonical {
+    pub rule: CanonicalRule,
+    /// Offset from the start of the input of the value breaking the rule
+    pub offset: usize,
+}
+
+impl<Data> TokenTree<Data> {
+    /// Like [`TokenTree::parse_with_limits`], but also fails with
+    /// [`LimitedParseError::NotCanonical`] if the input isn't in canonical form.
+    ///
+    /// Syrup values have exactly one canonical encoding, which is what makes it meaningful to sign
+    /// or hash them; see [`CanonicalRule`]. [`canonicalize`] converts input to that encoding.
+    ///
+    /// ```
+    /// # use syrup::de::{CanonicalRule, LimitedParseError, ParseLimits, TokenTree};
+    /// let limits = ParseLimits::default();
+    /// assert!(TokenTree::<&[u8]>::parse_canonical::<nom::error::Error<_>>(b"{1'a1+1'b2+}", &limits).is_ok());
+    /// let Err(LimitedParseError::NotCanonical(e)) =
+    ///     TokenTree::<&[u8]>::parse_canonical::<nom::error::Error<_>>(b"{1'b2+1'a1+}", &limits)
+    /// else {
+    ///     panic!()
+    /// };
+    /// assert_eq!((e.rule, e.offset), (CanonicalRule::Unsorted, 6));
+    /// ```
+    pub fn parse_canonical<'i, E: ParseLiteralError<'i>>(
+        i: &'i [u8],
+        limits: &ParseLimits,
+    ) -> Result<(&'i [u8], Self), LimitedParseError<E>>
+    where
+        Data: Bos<[u8]>,
+        &'i [u8]: Into<Data>,
+    {
+        LimitedParser::new(i, limits, true).tree(i, 0)
+    }
+}
+
+/// An error from [`canonicalize`]
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum CanonicalizeError<'i> {
+    #[error(transparent)]
+    Parse(LimitedParseError<ParseError<'i>>),
+    #[error("unexpected input after the end of the value, at byte {offset}")]
+    TrailingInput { offset: usize },
+}
+
+/// Re-encode a single syrup value in canonical form, sorting sets and dictionaries, dropping
+/// duplicate members and keys (keeping the first), and normalizing integers.
+///
+/// The input is parsed within the default [`ParseLimits`], so this is suitable for untrusted input.
+///
+/// ```
+/// assert_eq!(syrup::canonicalize(b"#007+1'b1'a1'b0-$").unwrap(), b"#0+1'a1'b7+$");
+/// ```
+pub fn canonicalize(input: &[u8]) -> Result<Vec<u8>, CanonicalizeError<'_>> {
+    let (rem, tree) =
+        TokenTree::<&[u8]>::parse_with_limits::<ParseError<'_>>(input, &ParseLimits::default())
+            .map_err(|e| match e {
+                LimitedParseError::Parse(e) => {
+                    LimitedParseError::Parse(e.map(|e| e.with_origin(input)))
+                }
+                e => e,
+            })
+            .map_err(CanonicalizeError::Parse)?;
+    if !rem.is_empty() {
+        return Err(CanonicalizeError::TrailingInput {
+            offset: input.len() - rem.len(),
+        });
+    }
+    Ok(tree.to_bytes().into_owned())
+}
@@ -0,0 +1,124 @@
+use std::collections::BTreeSet;
+
+use proptest::prelude::*;
+
+use crate::{
+    Encode,
+    de::lex::{
+        CanonicalRule, CanonicalizeError, LimitedParseError, NotCanonical, ParseLimits, TokenTree,
+        canonicalize,
+    },
+};
+
+type E<'i> = nom::error::Error<&'i [u8]>;
+
+fn not_canonical(input: &[u8]) -> Option<NotCanonical> {
+    match TokenTree::<&[u8]>::parse_canonical::<E<'_>>(input, &ParseLimits::default()) {
+        Err(LimitedParseError::NotCanonical(e)) => Some(e),
+        Ok(_) => None,
+        Err(e) => panic!("{e:?}"),
+    }
+}
+
+fn rejects(input: &[u8], rule: CanonicalRule, offset: usize) {
+    assert_eq!(
+        not_canonical(input),
+        Some(NotCanonical { rule, offset }),
+        "{}",
+        input.escape_ascii()
+    );
+}
+
+#[test]
+fn accepts_canonical_input() {
+    for input in [
+        b"0+".as_slice(),
+        b"10-",
+        b"0:",
+        b"[1+0+]",
+        b"#0+1+2\"ab$",
+        b"{1'a[]1'b{}}",
+        b"<1'a#$>",
+    ] {
+        assert_eq!(not_canonical(input), None, "{}", input.escape_ascii());
+    }
+}
+
+#[test]
+fn rejects_leading_zeros() {
+    rejects(b"007+", CanonicalRule::LeadingZero, 0);
+    rejects(b"[1+00-]", CanonicalRule::LeadingZero, 3);
+    rejects(b"<1'a02:ab>", CanonicalRule::LeadingZero, 4);
+}
+
+#[test]
+fn rejects_negative_zero() {
+    rejects(b"0-", CanonicalRule::NegativeZero, 0);
+    rejects(b"{1'a0-}", CanonicalRule::NegativeZero, 4);
+}
+
+#[test]
+fn rejects_unsorted_entries() {
+    rejects(b"#2+1+$", CanonicalRule::Unsorted, 3);
+    rejects(b"{1'b1+1'a2+}", CanonicalRule::Unsorted, 6);
+    // only keys are ordered, not values
+    assert_eq!(not_canonical(b"{1'a2+1'b1+}"), None);
+}
+
+#[test]
+fn rejects_duplicates() {
+    rejects(b"#1+1+$", CanonicalRule::Duplicate, 3);
+    rejects(b"{1'a1+1'a2+}", CanonicalRule::Duplicate, 6);
+}
+
+#[test]
+fn canonicalizes() {
+    assert_eq!(canonicalize(b"0-").unwrap(), b"0+");
+    assert_eq!(canonicalize(b"[00012-]").unwrap(), b"[12-]");
+    assert_eq!(
+        canonicalize(b"{1'b#2+1+2+$1'a0-1'b[]}").unwrap(),
+        b"{1'a0+1'b#1+2+$}"
+    );
+    assert!(matches!(
+        canonicalize(b"1+2+"),
+        Err(CanonicalizeError::TrailingInput { offset: 2 })
+    ));
+    assert!(matches!(
+        canonicalize(b"[1+"),
+        Err(CanonicalizeError::Parse(LimitedParseError::Parse(
+            nom::Err::Incomplete(_)
+        )))
+    ));
+}
+
+#[test]
+fn encodes_zero_canonically() {
+    let zero: TokenTree<Vec<u8>> = 0i32.encode();
+    assert_eq!(&*zero.to_bytes(), b"0+");
+}
+
+proptest! {
+    #[test]
+    fn encodings_are_canonical(
+        dict in prop::collection::btree_map(any::<i64>(), prop::collection::btree_set(".{0,8}", 0..8), 0..16),
+        set: BTreeSet<i16>,
+    ) {
+        let encoded: TokenTree<Vec<u8>> = (dict, set).encode();
+        let bytes = encoded.to_bytes();
+        prop_assert_eq!(not_canonical(&bytes), None);
+        prop_assert_eq!(canonicalize(&bytes).unwrap(), &*bytes);
+    }
+
+    #[test]
+    fn canonicalizing_is_idempotent(elements: Vec<(u8, i8)>) {
+        // a set with arbitrary (possibly duplicate) members, in arbitrary order
+        let mut input = b"#".to_vec();
+        for (a, b) in &elements {
+            input.extend(format!("[{a}+{}{}]", b.unsigned_abs(), if *b < 0 { '-' } else { '+' }).bytes());
+        }
+        input.push(b'$');
+        let canonical = canonicalize(&input).unwrap();
+        prop_assert_eq!(not_canonical(&canonical), None);
+        prop_assert_eq!(canonicalize(&canonical).unwrap(), canonical);
+    }
+}
@@ -1,17 +1,65 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, cmp::Ordering};
 
 use borrow_or_share::{BorrowOrShare, Bos};
-use nom::{
-    IResult, Parser,
-    multi::{many, many0},
-    sequence::delimited,
-};
+use nom::{IResult, Needed, Parser};
 
-use crate::de::lex::{ParseLiteralError, TokenTree, byte};
+use crate::{
+    de::lex::{ParseLiteralError, TokenTree, byte},
+    ser::Encode,
+};
 
 #[cfg(test)]
 mod test;
 
+/// Parse a collection that starts with `open`, followed by `contents`.
+///
+/// Once the collection is open, any error in its contents is a failure, attributed to the
+/// collection `name` through [`ContextError`](nom::error::ContextError).
+fn collection<'i, O, E: ParseLiteralError<'i>>(
+    name: &'static str,
+    open: u8,
+    mut contents: impl Parser<&'i [u8], Output = O, Error = E>,
+) -> impl Parser<&'i [u8], Output = O, Error = E> {
+    move |i: &'i [u8]| {
+        let (rest, ()) = byte(open).parse(i)?;
+        contents.parse(rest).map_err(|e| match e {
+            nom::Err::Error(e) | nom::Err::Failure(e) => {
+                nom::Err::Failure(E::add_context(i, name, e))
+            }
+            incomplete @ nom::Err::Incomplete(_) => incomplete,
+        })
+    }
+}
+
+/// Parse `element`s up to and including `close`.
+///
+/// If an element fails to parse at its start, the error also expects `close` there.
+fn elements<'i, O, E: ParseLiteralError<'i>>(
+    mut element: impl Parser<&'i [u8], Output = O, Error = E>,
+    close: u8,
+) -> impl Parser<&'i [u8], Output = Vec<O>, Error = E> {
+    move |mut i: &'i [u8]| {
+        let mut res = Vec::new();
+        loop {
+            match i.first() {
+                Some(&b) if b == close => return Ok((&i[1..], res)),
+                Some(_) => {}
+                None => return Err(nom::Err::Incomplete(Needed::new(1))),
+            }
+            match element.parse(i) {
+                Ok((rest, el)) => {
+                    res.push(el);
+                    i = rest;
+                }
+                Err(nom::Err::Error(e)) => {
+                    return Err(nom::Err::Error(e.or(E::from_char(i, char::from(close)))));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
 // #[cfg_attr(test, derive(proptest_derive::Arbitrary))]
 // #[cfg_attr(test, proptest(params = "crate::de::lex::test::MaxDepth"))]
@@ -60,6 +108,15 @@ where
 
 impl<Data> Eq for List<Data> where Data: Eq + Bos<[u8]> {}
 
+impl<Data> std::hash::Hash for List<Data>
+where
+    Data: Bos<[u8]>,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.elements.hash(state);
+    }
+}
+
 impl<Data> List<Data> {
     #[inline]
     pub const fn new(elements: Vec<TokenTree<Data>>) -> Self {
@@ -94,9 +151,10 @@ impl<Data> List<Data> {
 
     pub fn parse<'i, E: ParseLiteralError<'i>>(i: &'i [u8]) -> IResult<&'i [u8], Self, E>
     where
+        Data: Bos<[u8]>,
         &'i [u8]: Into<Data>,
     {
-        delimited(byte(b'['), many0(TokenTree::<Data>::parse), byte(b']'))
+        collection("list", b'[', elements(TokenTree::parse, b']'))
             .map(List::new)
             .parse(i)
     }
@@ -154,6 +212,16 @@ where
 
 impl<Data> Eq for Record<Data> where Data: Eq + Bos<[u8]> {}
 
+impl<Data> std::hash::Hash for Record<Data>
+where
+    Data: Bos<[u8]>,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.label.hash(state);
+        self.elements.hash(state);
+    }
+}
+
 impl<Data> Record<Data> {
     #[inline]
     pub const fn new(label: TokenTree<Data>, elements: Vec<TokenTree<Data>>) -> Self {
@@ -190,18 +258,107 @@ impl<Data> Record<Data> {
 
     pub fn parse<'i, E: ParseLiteralError<'i>>(i: &'i [u8]) -> IResult<&'i [u8], Self, E>
     where
+        Data: Bos<[u8]>,
         &'i [u8]: Into<Data>,
     {
-        delimited(
-            byte(b'<'),
-            TokenTree::parse.and(many0(TokenTree::parse)),
-            byte(b'>'),
+        collection(
+            "record",
+            b'<',
+            TokenTree::parse.and(elements(TokenTree::parse, b'>')),
         )
         .map(|(label, elements)| Record::new(label, elements))
         .parse(i)
     }
 }
 
+/// An error from [`Set::try_new`] or [`Dictionary::try_new`]: two entries, or two keys, encode
+/// the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+#[error("entry {index} is a duplicate of an earlier one")]
+pub struct DuplicateEntry {
+    /// Index of the later of the two entries, in the order they were given
+    pub index: usize,
+}
+
+/// `entries` sorted by the canonical encoding of the tree `key` picks out of each, along with
+/// that encoding and the index each entry had. Entries that encode the same keep their order.
+fn sort_by_encoding<T, Data: Bos<[u8]>>(
+    entries: Vec<T>,
+    key: impl Fn(&T) -> &TokenTree<Data>,
+) -> Vec<(Vec<u8>, usize, T)> {
+    let mut sorted: Vec<_> = entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, entry)| (key(&entry).to_bytes().into_owned(), index, entry))
+        .collect();
+    sorted.sort_by(|(l, ..), (r, ..)| l.cmp(r));
+    sorted
+}
+
+/// Compares what's written to it against `expected`, failing at the first byte that differs.
+struct CompareWriter<'e> {
+    /// What's left to compare against
+    expected: &'e [u8],
+    /// How what was written compares, once it's known to differ
+    ord: Ordering,
+}
+
+impl std::io::Write for CompareWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let len = buf.len().min(self.expected.len());
+        let (head, rest) = self.expected.split_at(len);
+        self.ord = buf[..len].cmp(head).then(buf.len().cmp(&len));
+        match self.ord {
+            Ordering::Equal => {
+                self.expected = rest;
+                Ok(buf.len())
+            }
+            _ => Err(std::io::ErrorKind::Other.into()),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Compare the canonical encoding of `tree` with `encoded`, without encoding the whole tree.
+pub(super) fn cmp_encoding<Data: Bos<[u8]>>(tree: &TokenTree<Data>, encoded: &[u8]) -> Ordering {
+    let mut w = CompareWriter {
+        expected: encoded,
+        ord: Ordering::Equal,
+    };
+    match tree.write_bytes(&mut w) {
+        // the tree's encoding is a prefix of `encoded`
+        Ok(_) => 0.cmp(&w.expected.len()),
+        Err(_e) => w.ord,
+    }
+}
+
+/// Sort `entries` as for [`Set::new`] or [`Dictionary::new`], keeping only the first of any
+/// that encode the same.
+pub(crate) fn sort_merging<T, Data: Bos<[u8]>>(
+    entries: Vec<T>,
+    key: impl Fn(&T) -> &TokenTree<Data>,
+) -> Vec<T> {
+    let mut sorted = sort_by_encoding(entries, key);
+    sorted.dedup_by(|(encoded, ..), (prev, ..)| encoded == prev);
+    sorted.into_iter().map(|(.., entry)| entry).collect()
+}
+
+/// Sort `entries` as for [`Set::try_new`] or [`Dictionary::try_new`], failing on any that
+/// encode the same.
+pub(crate) fn sort_rejecting<T, Data: Bos<[u8]>>(
+    entries: Vec<T>,
+    key: impl Fn(&T) -> &TokenTree<Data>,
+) -> Result<Vec<T>, DuplicateEntry> {
+    let sorted = sort_by_encoding(entries, key);
+    if let Some(pair) = sorted.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+        return Err(DuplicateEntry { index: pair[1].1 });
+    }
+    Ok(sorted.into_iter().map(|(.., entry)| entry).collect())
+}
+
 #[derive(Clone)]
 // #[cfg_attr(test, derive(proptest_derive::Arbitrary))]
 // #[cfg_attr(test, proptest(params = "crate::de::lex::test::MaxDepth"))]
@@ -244,30 +401,31 @@ where
     RData: Bos<[u8]>,
 {
     fn eq(&self, other: &Set<RData>) -> bool {
-        // TODO :: performance
-        self.encode().eq(&other.encode())
+        // both are sorted and deduplicated, so equal sets have equal entries in the same order
+        self.entries.eq(&other.entries)
     }
 }
 
 impl<Data> Eq for Set<Data> where Data: Eq + Bos<[u8]> {}
 
+impl<Data> std::hash::Hash for Set<Data>
+where
+    Data: Bos<[u8]>,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.entries.hash(state);
+    }
+}
+
 pub(crate) fn set_encoded_entries<'i, S>(set: S) -> (usize, Vec<Cow<'i, [u8]>>)
 where
     S: IntoIterator<Item = Cow<'i, [u8]>>,
     <S as IntoIterator>::IntoIter: ExactSizeIterator,
 {
-    let entries = set.into_iter();
-    let mut sorted = Vec::with_capacity(entries.len());
-    let mut total_bytes = 0;
-    for entry in entries {
-        total_bytes += entry.len();
-        let ppoint = sorted.partition_point(|oentry| oentry < &entry);
-        if sorted.get(ppoint).is_some() && sorted[ppoint] == entry {
-            // duplicate entry; skip
-            continue;
-        }
-        sorted.insert(ppoint, entry);
-    }
+    let mut sorted: Vec<_> = set.into_iter().collect();
+    sorted.sort_unstable();
+    sorted.dedup();
+    let total_bytes = sorted.iter().map(|entry| entry.len()).sum();
     (total_bytes, sorted)
 }
 
@@ -286,9 +444,9 @@ pub(crate) fn encode_into_as_set<'i>(
 }
 
 impl<Data> Set<Data> {
-    /// `entries` should not contain any duplicates; they won't be encoded.
+    /// `entries` must already be sorted by their canonical encodings, without duplicates.
     #[inline]
-    pub const fn new(entries: Vec<TokenTree<Data>>) -> Self {
+    pub(crate) const fn from_sorted(entries: Vec<TokenTree<Data>>) -> Self {
         Self { entries }
     }
 
@@ -296,12 +454,10 @@ impl<Data> Set<Data> {
     where
         Data: Bos<[u8]>,
     {
-        let (total_bytes, sorted) = set_encoded_entries(self.into_iter().map(|e| e.to_bytes()));
-
-        let mut res = Vec::with_capacity(total_bytes + 2);
+        let mut res = Vec::new();
         res.push(b'#');
-        for entry in &sorted {
-            res.extend_from_slice(entry);
+        for entry in &self.entries {
+            drop(entry.write_bytes(&mut res));
         }
         res.push(b'$');
         res
@@ -311,15 +467,21 @@ impl<Data> Set<Data> {
     where
         Data: Bos<[u8]>,
     {
-        let (_, sorted) = set_encoded_entries(self.into_iter().map(|e| e.to_bytes()));
-        encode_into_as_set(&sorted, w)
+        let mut amt = 2; // starting at 2 for the #$
+        w.write_all(b"#")?;
+        for entry in &self.entries {
+            amt += entry.write_bytes(w)?;
+        }
+        w.write_all(b"$")?;
+        Ok(amt)
     }
 
     pub fn parse<'i, E: ParseLiteralError<'i>>(i: &'i [u8]) -> IResult<&'i [u8], Self, E>
     where
+        Data: Bos<[u8]>,
         &'i [u8]: Into<Data>,
     {
-        delimited(byte(b'#'), many(0.., TokenTree::parse), byte(b'$'))
+        collection("set", b'#', elements(TokenTree::parse, b'$'))
             .map(Self::new)
             .parse(i)
     }
@@ -333,6 +495,96 @@ impl<Data> Set<Data> {
     pub fn is_empty(&self) -> bool {
         self.entries.is_empty()
     }
+
+    /// The entries can be changed through this, as long as [`Set::sort`] is called afterwards.
+    pub(crate) fn entries_mut(&mut self) -> &mut Vec<TokenTree<Data>> {
+        &mut self.entries
+    }
+
+    /// Keep only the entries for which `f` returns `true`.
+    pub fn retain(&mut self, f: impl FnMut(&TokenTree<Data>) -> bool) {
+        self.entries.retain(f);
+    }
+}
+
+impl<Data: Bos<[u8]>> Set<Data> {
+    /// A set of `entries`, sorted by their canonical encodings. Of any entries that encode the
+    /// same, only the first is kept; use [`Set::try_new`] to reject them instead.
+    #[must_use]
+    pub fn new(entries: Vec<TokenTree<Data>>) -> Self {
+        Self {
+            entries: sort_merging(entries, |entry| entry),
+        }
+    }
+
+    /// Like [`Set::new`], but fails if any two entries encode the same.
+    pub fn try_new(entries: Vec<TokenTree<Data>>) -> Result<Self, DuplicateEntry> {
+        Ok(Self {
+            entries: sort_rejecting(entries, |entry| entry)?,
+        })
+    }
+
+    /// Restore the order of the entries after changing them through [`Set::entries_mut`],
+    /// keeping the first of any that now encode the same.
+    pub(crate) fn sort(&mut self) {
+        self.entries = sort_merging(std::mem::take(&mut self.entries), |entry| entry);
+    }
+
+    /// Where `entry` is, or else where it would be inserted.
+    fn search(&self, entry: &[u8]) -> Result<usize, usize> {
+        self.entries.binary_search_by(|e| cmp_encoding(e, entry))
+    }
+
+    fn position<'e, E>(&self, entry: &'e E) -> Option<usize>
+    where
+        E: Encode<'e, Vec<u8>> + ?Sized,
+    {
+        self.search(&entry.encode().to_bytes()).ok()
+    }
+
+    /// Whether this set contains `entry`, which can be anything that encodes to it, as with
+    /// [`Dictionary::get`]. Entries are compared in canonical form.
+    pub fn contains<'e, E>(&self, entry: &'e E) -> bool
+    where
+        E: Encode<'e, Vec<u8>> + ?Sized,
+    {
+        self.position(entry).is_some()
+    }
+
+    /// Add `entry`, unless the set already contains it, returning whether it was added.
+    pub fn insert(&mut self, entry: TokenTree<Data>) -> bool {
+        let Err(index) = self.search(&entry.to_bytes()) else {
+            return false;
+        };
+        self.entries.insert(index, entry);
+        true
+    }
+
+    /// Remove `entry`, which can be anything that encodes to it, returning the entry that was
+    /// removed.
+    pub fn remove<'e, E>(&mut self, entry: &'e E) -> Option<TokenTree<Data>>
+    where
+        E: Encode<'e, Vec<u8>> + ?Sized,
+    {
+        let index = self.position(entry)?;
+        Some(self.entries.remove(index))
+    }
+}
+
+impl<Data: Bos<[u8]>> FromIterator<TokenTree<Data>> for Set<Data> {
+    fn from_iter<T: IntoIterator<Item = TokenTree<Data>>>(iter: T) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+impl<Data> IntoIterator for Set<Data> {
+    type Item = TokenTree<Data>;
+
+    type IntoIter = std::vec::IntoIter<TokenTree<Data>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
 }
 
 impl<'s, Data> IntoIterator for &'s Set<Data> {
@@ -391,13 +643,28 @@ where
     RData: Bos<[u8]>,
 {
     fn eq(&self, other: &Dictionary<RData>) -> bool {
-        // TODO :: performance
-        self.encode().eq(&other.encode())
+        // both are sorted and deduplicated, so equal dictionaries have equal entries in the same
+        // order
+        self.len() == other.len()
+            && self
+                .entries
+                .iter()
+                .zip(&other.entries)
+                .all(|((lk, lv), (rk, rv))| lk == rk && lv == rv)
     }
 }
 
 impl<Data> Eq for Dictionary<Data> where Data: Eq + Bos<[u8]> {}
 
+impl<Data> std::hash::Hash for Dictionary<Data>
+where
+    Data: Bos<[u8]>,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.entries.hash(state);
+    }
+}
+
 pub(crate) type SortedDictEntries<'i> = Vec<(Cow<'i, [u8]>, Cow<'i, [u8]>)>;
 
 pub(crate) fn dict_encoded_entries<'i, Dict>(dict: Dict) -> (usize, SortedDictEntries<'i>)
@@ -405,18 +672,14 @@ where
     Dict: IntoIterator<Item = (Cow<'i, [u8]>, Cow<'i, [u8]>)>,
     <Dict as IntoIterator>::IntoIter: ExactSizeIterator,
 {
-    let entries = dict.into_iter();
-    let mut pairs = Vec::with_capacity(entries.len());
-    let mut total_bytes = 0;
-    for (key, value) in entries {
-        total_bytes += key.len() + value.len();
-        let ppoint = pairs.partition_point(|(okey, _)| okey < &key);
-        if pairs.get(ppoint).is_some() && pairs[ppoint].0 == key {
-            // key already present; skip
-            continue;
-        }
-        pairs.insert(ppoint, (key, value));
-    }
+    let mut pairs: Vec<_> = dict.into_iter().collect();
+    // stable, so that the first of any duplicate keys is the one kept
+    pairs.sort_by(|(l, _), (r, _)| l.cmp(r));
+    pairs.dedup_by(|(key, _), (prev, _)| key == prev);
+    let total_bytes = pairs
+        .iter()
+        .map(|(key, value)| key.len() + value.len())
+        .sum();
     (total_bytes, pairs)
 }
 
@@ -436,9 +699,10 @@ pub(crate) fn encode_into_as_dict<'i>(
 }
 
 impl<Data> Dictionary<Data> {
-    /// `entries` should not contain any duplicate keys; they won't be encoded.
+    /// `entries` must already be sorted by the canonical encodings of their keys, without
+    /// duplicate keys.
     #[inline]
-    pub const fn new(entries: Vec<(TokenTree<Data>, TokenTree<Data>)>) -> Self {
+    pub(crate) const fn from_sorted(entries: Vec<(TokenTree<Data>, TokenTree<Data>)>) -> Self {
         Self { entries }
     }
 
@@ -446,13 +710,11 @@ impl<Data> Dictionary<Data> {
     where
         Data: Bos<[u8]>,
     {
-        let (total_bytes, pairs) =
-            dict_encoded_entries(self.into_iter().map(|(k, v)| (k.to_bytes(), v.to_bytes())));
-        let mut res = Vec::with_capacity(total_bytes + 2);
+        let mut res = Vec::new();
         res.push(b'{');
-        for (key, value) in &pairs {
-            res.extend_from_slice(key);
-            res.extend_from_slice(value);
+        for (key, value) in &self.entries {
+            drop(key.write_bytes(&mut res));
+            drop(value.write_bytes(&mut res));
         }
         res.push(b'}');
         res
@@ -462,19 +724,25 @@ impl<Data> Dictionary<Data> {
     where
         Data: Bos<[u8]>,
     {
-        let (_, pairs) =
-            dict_encoded_entries(self.into_iter().map(|(k, v)| (k.to_bytes(), v.to_bytes())));
-        encode_into_as_dict(&pairs, w)
+        let mut amt = 2; // starting at 2 for the {}
+        w.write_all(b"{")?;
+        for (key, value) in &self.entries {
+            amt += key.write_bytes(w)?;
+            amt += value.write_bytes(w)?;
+        }
+        w.write_all(b"}")?;
+        Ok(amt)
     }
 
     pub fn parse<'i, E: ParseLiteralError<'i>>(i: &'i [u8]) -> IResult<&'i [u8], Self, E>
     where
+        Data: Bos<[u8]>,
         &'i [u8]: Into<Data>,
     {
-        delimited(
-            byte(b'{'),
-            many(0.., TokenTree::parse.and(TokenTree::parse)),
-            byte(b'}'),
+        collection(
+            "dictionary",
+            b'{',
+            elements(TokenTree::parse.and(TokenTree::parse), b'}'),
         )
         .map(Self::new)
         .parse(i)
@@ -489,6 +757,155 @@ impl<Data> Dictionary<Data> {
     pub fn is_empty(&self) -> bool {
         self.entries.is_empty()
     }
+
+    /// The entries can be changed through this, as long as [`Dictionary::sort`] is called
+    /// afterwards.
+    pub(crate) fn entries_mut(&mut self) -> &mut Vec<(TokenTree<Data>, TokenTree<Data>)> {
+        &mut self.entries
+    }
+
+    /// The values of this dictionary, which can be changed in place, unlike the keys.
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut TokenTree<Data>> {
+        self.entries.iter_mut().map(|(_, value)| value)
+    }
+
+    /// Keep only the entries for which `f` returns `true`.
+    pub fn retain(&mut self, mut f: impl FnMut(&TokenTree<Data>, &mut TokenTree<Data>) -> bool) {
+        self.entries.retain_mut(|(key, value)| f(key, value));
+    }
+}
+
+impl<Data: Bos<[u8]>> Dictionary<Data> {
+    /// A dictionary of `entries`, sorted by the canonical encodings of their keys. Of any
+    /// entries whose keys encode the same, only the first is kept; use [`Dictionary::try_new`]
+    /// to reject them instead.
+    #[must_use]
+    pub fn new(entries: Vec<(TokenTree<Data>, TokenTree<Data>)>) -> Self {
+        Self {
+            entries: sort_merging(entries, |(key, _)| key),
+        }
+    }
+
+    /// Like [`Dictionary::new`], but fails if any two keys encode the same.
+    ///
+    /// ```
+    /// # use syrup::{Encode, de::{Dictionary, DuplicateEntry}};
+    /// let entries = vec![
+    ///     ("b".encode(), 1u8.encode()),
+    ///     ("a".encode(), 2u8.encode()),
+    ///     ("b".encode(), 3u8.encode()),
+    /// ];
+    /// assert_eq!(
+    ///     Dictionary::<Vec<u8>>::try_new(entries.clone()),
+    ///     Err(DuplicateEntry { index: 2 })
+    /// );
+    /// let dict = Dictionary::new(entries);
+    /// assert_eq!(dict.encode(), b"{1\"a2+1\"b1+}");
+    /// ```
+    pub fn try_new(
+        entries: Vec<(TokenTree<Data>, TokenTree<Data>)>,
+    ) -> Result<Self, DuplicateEntry> {
+        Ok(Self {
+            entries: sort_rejecting(entries, |(key, _)| key)?,
+        })
+    }
+
+    /// Restore the order of the entries after changing their keys through
+    /// [`Dictionary::entries_mut`], keeping the first of any that now encode the same.
+    pub(crate) fn sort(&mut self) {
+        self.entries = sort_merging(std::mem::take(&mut self.entries), |(key, _)| key);
+    }
+
+    /// Where the entry for `key` is, or else where it would be inserted.
+    fn search(&self, key: &[u8]) -> Result<usize, usize> {
+        self.entries.binary_search_by(|(k, _)| cmp_encoding(k, key))
+    }
+
+    fn position<'k, K>(&self, key: &'k K) -> Option<usize>
+    where
+        K: Encode<'k, Vec<u8>> + ?Sized,
+    {
+        self.search(&key.encode().to_bytes()).ok()
+    }
+
+    /// The value for `key`, which can be anything that encodes to the key, such as a
+    /// [`TokenTree`], a [`Symbol`](crate::symbol::Symbol) or a `str`. Keys are compared in
+    /// canonical form.
+    ///
+    /// ```
+    /// # use syrup::{TokenTree, symbol::Symbol};
+    /// let tree: TokenTree<Vec<u8>> = r#"{to: "carol", "id": 5, 1: #t}"#.parse().unwrap();
+    /// let dict = tree.as_dictionary().unwrap();
+    /// assert_eq!(dict.get(&Symbol("to")).and_then(TokenTree::as_str), Some("carol"));
+    /// assert_eq!(dict.get("id").and_then(TokenTree::as_int::<u8>), Some(5));
+    /// assert_eq!(dict.get(&1u8).and_then(TokenTree::as_bool), Some(true));
+    /// assert!(dict.get("to").is_none());
+    /// ```
+    pub fn get<'k, K>(&self, key: &'k K) -> Option<&TokenTree<Data>>
+    where
+        K: Encode<'k, Vec<u8>> + ?Sized,
+    {
+        self.position(key).map(|index| &self.entries[index].1)
+    }
+
+    /// Like [`Dictionary::get`], but the value can be changed in place.
+    pub fn get_mut<'k, K>(&mut self, key: &'k K) -> Option<&mut TokenTree<Data>>
+    where
+        K: Encode<'k, Vec<u8>> + ?Sized,
+    {
+        self.position(key).map(|index| &mut self.entries[index].1)
+    }
+
+    /// Set the value for `key`, returning the value it replaces, if any.
+    ///
+    /// ```
+    /// # use syrup::{Encode, TokenTree, symbol::Symbol};
+    /// let mut tree: TokenTree<Vec<u8>> = "{to: carol}".parse().unwrap();
+    /// let TokenTree::Dictionary(dict) = &mut tree else { unreachable!() };
+    /// let old = dict.insert(Symbol("to").encode(), Symbol("bob").encode());
+    /// assert_eq!(old.as_ref().and_then(TokenTree::as_symbol), Some(Symbol("carol")));
+    /// dict.insert("id".encode(), 5u8.encode());
+    /// assert!(dict.remove(&Symbol("to")).is_some());
+    /// assert_eq!(tree.to_string(), r#"{"id": 5}"#);
+    /// ```
+    pub fn insert(
+        &mut self,
+        key: TokenTree<Data>,
+        value: TokenTree<Data>,
+    ) -> Option<TokenTree<Data>> {
+        match self.search(&key.to_bytes()) {
+            Ok(index) => Some(std::mem::replace(&mut self.entries[index].1, value)),
+            Err(index) => {
+                self.entries.insert(index, (key, value));
+                None
+            }
+        }
+    }
+
+    /// Remove the entry for `key`, returning its value.
+    pub fn remove<'k, K>(&mut self, key: &'k K) -> Option<TokenTree<Data>>
+    where
+        K: Encode<'k, Vec<u8>> + ?Sized,
+    {
+        let index = self.position(key)?;
+        Some(self.entries.remove(index).1)
+    }
+}
+
+impl<Data: Bos<[u8]>> FromIterator<(TokenTree<Data>, TokenTree<Data>)> for Dictionary<Data> {
+    fn from_iter<T: IntoIterator<Item = (TokenTree<Data>, TokenTree<Data>)>>(iter: T) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+impl<Data> IntoIterator for Dictionary<Data> {
+    type Item = (TokenTree<Data>, TokenTree<Data>);
+
+    type IntoIter = std::vec::IntoIter<(TokenTree<Data>, TokenTree<Data>)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
 }
 
 impl<'s, Data> IntoIterator for &'s Dictionary<Data> {
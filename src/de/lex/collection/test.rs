@@ -3,8 +3,12 @@ use proptest::prelude::*;
 type E<'i> = nom::error::Error<&'i [u8]>;
 
 use crate::{
-    TokenTree,
-    de::lex::{Dictionary, List, Record, Set, test::MaxDepth},
+    Encode, TokenTree,
+    de::lex::{
+        Dictionary, DuplicateEntry, List, Record, Set, collection::cmp_encoding, test::MaxDepth,
+    },
+    list,
+    symbol::Symbol,
 };
 
 impl Arbitrary for List<Vec<u8>> {
@@ -38,7 +42,7 @@ impl Arbitrary for Set<Vec<u8>> {
 
     fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
         Vec::<TokenTree<Vec<u8>>>::arbitrary_with((Default::default(), args.next()))
-            .prop_map(|entries| Set { entries })
+            .prop_map(Set::new)
             .boxed()
     }
 }
@@ -52,7 +56,7 @@ impl Arbitrary for Dictionary<Vec<u8>> {
             Default::default(),
             (args.next(), args.next()),
         ))
-        .prop_map(|entries| Dictionary { entries })
+        .prop_map(Dictionary::new)
         .boxed()
     }
 }
@@ -64,7 +68,7 @@ proptest! {
         let res = List::<&[u8]>::parse::<E<'_>>(&bytes);
         prop_assert!(res.is_ok(), "encoded input: `{}`, error: {}", String::from_utf8_lossy(&bytes), res.unwrap_err());
         let (rem, res) = res.unwrap();
-        prop_assert_eq!(rem, [].as_slice());
+        prop_assert_eq!(rem, b"".as_slice());
         prop_assert_eq!(res, list);
     }
 
@@ -74,7 +78,7 @@ proptest! {
         let res = Record::<&[u8]>::parse::<E<'_>>(&bytes);
         prop_assert!(res.is_ok(), "encoded input: `{}`, error: {}", String::from_utf8_lossy(&bytes), res.unwrap_err());
         let (rem, res) = res.unwrap();
-        prop_assert_eq!(rem, [].as_slice());
+        prop_assert_eq!(rem, b"".as_slice());
         prop_assert_eq!(res, rec);
     }
 
@@ -84,7 +88,7 @@ proptest! {
         let res = Set::<&[u8]>::parse::<E<'_>>(&bytes);
         prop_assert!(res.is_ok(), "encoded input: `{}`, error: {}", String::from_utf8_lossy(&bytes), res.unwrap_err());
         let (rem, res) = res.unwrap();
-        prop_assert_eq!(rem, [].as_slice());
+        prop_assert_eq!(rem, b"".as_slice());
         prop_assert_eq!(res, set);
     }
 
@@ -94,7 +98,131 @@ proptest! {
         let res = Dictionary::<&[u8]>::parse::<E<'_>>(&bytes);
         prop_assert!(res.is_ok(), "encoded input: `{}`, error: {}", String::from_utf8_lossy(&bytes), res.unwrap_err());
         let (rem, res) = res.unwrap();
-        prop_assert_eq!(rem, [].as_slice());
+        prop_assert_eq!(rem, b"".as_slice());
         prop_assert_eq!(res, dict);
     }
+
+    #[test]
+    fn keeps_entries_sorted(
+        mut set: Set<Vec<u8>>,
+        entries in proptest::collection::vec(any::<TokenTree<Vec<u8>>>(), 0..8),
+    ) {
+        for entry in entries {
+            let inserted = set.insert(entry.clone());
+            prop_assert!(set.contains(&entry));
+            if inserted {
+                prop_assert_eq!(set.remove(&entry), Some(entry.clone()));
+                prop_assert!(set.insert(entry));
+            }
+        }
+        let encoded: Vec<_> = set.into_iter().map(|entry| entry.to_bytes().into_owned()).collect();
+        prop_assert!(encoded.is_sorted_by(|l, r| l < r));
+    }
+
+    #[test]
+    fn compares_encodings(l: TokenTree<Vec<u8>>, r: TokenTree<Vec<u8>>) {
+        let (l_bytes, r_bytes) = (l.to_bytes(), r.to_bytes());
+        prop_assert_eq!(cmp_encoding(&l, &r_bytes), l_bytes.cmp(&r_bytes));
+        prop_assert_eq!(cmp_encoding(&l, &l_bytes), std::cmp::Ordering::Equal);
+        prop_assert_eq!(cmp_encoding(&l, &l_bytes[..l_bytes.len() - 1]), std::cmp::Ordering::Greater);
+        prop_assert_eq!(l == r, l_bytes == r_bytes);
+        if l == r {
+            prop_assert_eq!(hash(&l), hash(&r));
+        }
+    }
+}
+
+fn hash(tree: &TokenTree<Vec<u8>>) -> u64 {
+    use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher};
+    BuildHasherDefault::<DefaultHasher>::default().hash_one(tree)
+}
+
+#[test]
+fn compares_non_canonical_ints() {
+    let parse = |input| TokenTree::<&[u8]>::parse::<E<'_>>(input).unwrap().1;
+    let (padded, canonical) = (parse(b"007+"), parse(b"7+"));
+    assert_eq!(padded, canonical);
+    assert_eq!(parse(b"0-"), parse(b"0+"));
+    assert_ne!(padded, parse(b"7-"));
+
+    let set = |entry: &TokenTree<&[u8]>| Set::<Vec<u8>>::new(vec![entry.into()]);
+    assert_eq!(set(&padded), set(&canonical));
+    assert_eq!(
+        hash(&TokenTree::Set(set(&padded))),
+        hash(&TokenTree::Set(set(&canonical)))
+    );
+
+    let dict = |key: &TokenTree<&[u8]>| {
+        Dictionary::<Vec<u8>>::new(vec![(key.into(), Symbol("v").encode())])
+    };
+    assert_eq!(dict(&padded), dict(&canonical));
+    assert_eq!(
+        hash(&TokenTree::Dictionary(dict(&padded))),
+        hash(&TokenTree::Dictionary(dict(&canonical)))
+    );
+    assert!(dict(&padded).get(&7u8).is_some());
+}
+
+#[test]
+fn sorts_and_merges_entries() {
+    let set = Set::<Vec<u8>>::new(vec!["b".encode(), 2u8.encode(), "a".encode(), 2u8.encode()]);
+    assert_eq!(set.encode(), b"#1\"a1\"b2+$");
+    assert_eq!(
+        Set::<Vec<u8>>::try_new(vec!["b".encode(), 2u8.encode(), "b".encode()]),
+        Err(DuplicateEntry { index: 2 })
+    );
+
+    // the first of any duplicate keys is kept
+    let dict: Dictionary<Vec<u8>> = [
+        (Symbol("b").encode(), 1u8.encode()),
+        (Symbol("a").encode(), 2u8.encode()),
+        (Symbol("b").encode(), 3u8.encode()),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(dict.encode(), b"{1'a2+1'b1+}");
+    let keys: Vec<_> = (&dict)
+        .into_iter()
+        .map(|(key, _)| key.as_symbol())
+        .collect();
+    assert_eq!(keys, [Some(Symbol("a")), Some(Symbol("b"))]);
+    assert_eq!(
+        dict,
+        Dictionary::new(dict.clone().into_iter().rev().collect())
+    );
+}
+
+#[test]
+fn edits_sets() {
+    let mut set = Set::<Vec<u8>>::new(Vec::new());
+    assert!(set.insert(1u8.encode()));
+    assert!(set.insert("one".encode()));
+    assert!(!set.insert(1u8.encode()));
+    assert_eq!(set.len(), 2);
+    assert!(set.contains(&1u8));
+    assert_eq!(set.remove("one"), Some("one".encode()));
+    assert_eq!(set.remove("one"), None);
+    set.retain(|entry| entry.as_int::<u8>() != Some(1));
+    assert!(set.is_empty());
+}
+
+#[test]
+fn edits_dictionaries() {
+    let mut dict = Dictionary::<Vec<u8>>::new(Vec::new());
+    assert_eq!(dict.insert(Symbol("a").encode(), 1u8.encode()), None);
+    assert_eq!(dict.insert("a".encode(), 2u8.encode()), None);
+    assert_eq!(
+        dict.insert(Symbol("a").encode(), 3u8.encode()),
+        Some(1u8.encode())
+    );
+    assert_eq!(dict.len(), 2);
+    *dict.get_mut("a").unwrap() = 4u8.encode();
+    for value in dict.values_mut() {
+        *value = TokenTree::List(List::new(vec![value.clone()]));
+    }
+    assert_eq!(dict.encode(), b"{1\"a[4+]1'a[3+]}");
+    assert_eq!(dict.remove(&Symbol("a")), Some(list![&3u8]));
+    assert_eq!(dict.remove(&Symbol("a")), None);
+    dict.retain(|_, value| value.as_list().is_none());
+    assert!(dict.is_empty());
 }
@@ -0,0 +1,291 @@
+use std::{fmt::Write, num::ParseIntError};
+
+use nom::error::{ContextError, ErrorKind, FromExternalError};
+
+#[cfg(test)]
+mod test;
+
+/// A class of token that would have been accepted where a [`ParseError`] occurred
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Expected {
+    /// An ascii digit, as in an integer or the length prefix of a bytes/string/symbol literal
+    Digit,
+    /// A specific byte, such as a separator (`:`, `"`, `'`), a sign (`+`, `-`), or a delimiter
+    Byte(u8),
+    /// A length prefix small enough to fit in a `u64`
+    Length,
+    /// Anything else, as reported by a nom combinator
+    Other(ErrorKind),
+}
+
+impl Expected {
+    /// The bytes any value may start with, other than a digit
+    const VALUE_STARTS: [u8; 8] = *b"tfFD[<#{";
+}
+
+impl std::fmt::Display for Expected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expected::Digit => f.write_str("a digit"),
+            Expected::Byte(b) => write!(f, "'{}'", b.escape_ascii()),
+            Expected::Length => f.write_str("a length that fits in 64 bits"),
+            Expected::Other(kind) => write!(f, "{}", kind.description()),
+        }
+    }
+}
+
+/// An error from parsing syrup, recording where parsing failed and what would have been accepted
+/// there.
+///
+/// The offset of the failure is only known once the error has been given the complete input with
+/// [`with_origin`](Self::with_origin); [`decode_bytes!`](crate::decode_bytes) does this
+/// automatically.
+///
+/// ```
+/// # use syrup::de::{Expected, ParseError, TokenTree};
+/// let input = b"[1+<3'foo2x>]";
+/// let Err(nom::Err::Failure(e)) = TokenTree::<&[u8]>::parse::<ParseError<'_>>(input) else {
+///     panic!()
+/// };
+/// let e = e.with_origin(input);
+/// assert_eq!(e.offset(), Some(10));
+/// assert_eq!(e.found(), Some(b'x'));
+/// assert!(e.expected().contains(&Expected::Byte(b':')));
+/// assert_eq!(
+///     e.containers().collect::<Vec<_>>(),
+///     [("list", Some(0)), ("record", Some(3))]
+/// );
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct ParseError<'i> {
+    /// The input remaining where parsing failed
+    input: &'i [u8],
+    /// The complete input, if known
+    origin: Option<&'i [u8]>,
+    expected: Vec<Expected>,
+    /// Containers that were open at the failure, innermost first, with the input at their start
+    containers: Vec<(&'static str, &'i [u8])>,
+}
+
+impl<'i> ParseError<'i> {
+    fn new(input: &'i [u8], expected: Expected) -> Self {
+        Self {
+            input,
+            origin: None,
+            expected: vec![expected],
+            containers: Vec::new(),
+        }
+    }
+
+    /// Record the complete input that was being parsed, so that offsets can be reported.
+    ///
+    /// `origin` must be the input that was originally given to the parser.
+    #[must_use]
+    pub fn with_origin(mut self, origin: &'i [u8]) -> Self {
+        debug_assert!(origin.len() >= self.input.len());
+        self.origin = Some(origin);
+        self
+    }
+
+    /// The input remaining where parsing failed.
+    #[inline]
+    #[must_use]
+    pub const fn input(&self) -> &'i [u8] {
+        self.input
+    }
+
+    fn offset_of(&self, input: &[u8]) -> Option<usize> {
+        self.origin.map(|origin| origin.len() - input.len())
+    }
+
+    /// The offset of the failure from the start of the input, if the input is known.
+    #[must_use]
+    pub fn offset(&self) -> Option<usize> {
+        self.offset_of(self.input)
+    }
+
+    /// The byte found where parsing failed, or `None` at the end of the input.
+    #[inline]
+    #[must_use]
+    pub fn found(&self) -> Option<u8> {
+        self.input.first().copied()
+    }
+
+    /// The tokens that would have been accepted where parsing failed.
+    #[inline]
+    #[must_use]
+    pub fn expected(&self) -> &[Expected] {
+        &self.expected
+    }
+
+    /// The lists, records, sets, and dictionaries that were open where parsing failed, outermost
+    /// first, along with their offsets if the input is known.
+    pub fn containers(&self) -> impl Iterator<Item = (&'static str, Option<usize>)> + '_ {
+        self.containers
+            .iter()
+            .rev()
+            .map(|&(kind, input)| (kind, self.offset_of(input)))
+    }
+
+    /// Render a hex dump of up to `radius` bytes either side of the failure, with the failing
+    /// byte marked.
+    ///
+    /// Without a known origin, the dump starts at the failure.
+    ///
+    /// ```
+    /// # use syrup::de::{ParseError, TokenTree};
+    /// let input = b"[1+2x]";
+    /// let Err(nom::Err::Failure(e)) = TokenTree::<&[u8]>::parse::<ParseError<'_>>(input) else {
+    ///     panic!()
+    /// };
+    /// assert_eq!(
+    ///     e.with_origin(input).hex_context(2),
+    ///     "00000002: 2b 32 78 5d  |+2x]|\n                ^^\n"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn hex_context(&self, radius: usize) -> String {
+        let origin = self.origin.unwrap_or(self.input);
+        let offset = origin.len() - self.input.len();
+        let start = offset.saturating_sub(radius);
+        let end = origin
+            .len()
+            .min(offset.saturating_add(radius).saturating_add(1));
+        let window = &origin[start..end];
+
+        let mut res = format!("{start:08x}:");
+        for b in window {
+            write!(res, " {b:02x}").unwrap();
+        }
+        res.push_str("  |");
+        for &b in window {
+            res.push(match b {
+                0x20..=0x7e => char::from(b),
+                _ => '.',
+            });
+        }
+        res.push_str("|\n");
+        // "00000000:" and a space per byte before the failing one
+        let column = 9 + 3 * (offset - start) + 1;
+        res.extend(std::iter::repeat_n(' ', column));
+        res.push_str("^^\n");
+        res
+    }
+
+    fn write_expected(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let any_value = self.expected.contains(&Expected::Digit)
+            && Expected::VALUE_STARTS
+                .iter()
+                .all(|&b| self.expected.contains(&Expected::Byte(b)));
+        let mut items = Vec::new();
+        if any_value {
+            items.push("a value".to_owned());
+        }
+        for expected in &self.expected {
+            let covered = matches!(expected, Expected::Byte(b) if Expected::VALUE_STARTS.contains(b))
+                || *expected == Expected::Digit;
+            if !(any_value && covered) {
+                items.push(expected.to_string());
+            }
+        }
+        match items.as_slice() {
+            [] => Ok(()),
+            [only] => write!(f, "expected {only}"),
+            [init @ .., last] => write!(f, "expected {} or {last}", init.join(", ")),
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.found() {
+            Some(b) => write!(f, "unexpected '{}'", b.escape_ascii())?,
+            None => f.write_str("unexpected end of input")?,
+        }
+        if let Some(offset) = self.offset() {
+            write!(f, " at byte {offset}")?;
+        }
+        if !self.expected.is_empty() {
+            f.write_str(", ")?;
+            self.write_expected(f)?;
+        }
+        for (i, (kind, offset)) in self.containers().enumerate() {
+            f.write_str(if i == 0 { ", in " } else { " > " })?;
+            f.write_str(kind)?;
+            if let Some(offset) = offset {
+                write!(f, "@{offset}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for ParseError<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParseError")
+            .field("offset", &self.offset())
+            .field("found", &self.found())
+            .field("expected", &self.expected)
+            .field("containers", &self.containers().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl std::error::Error for ParseError<'_> {}
+
+impl<'i> nom::error::ParseError<&'i [u8]> for ParseError<'i> {
+    fn from_error_kind(input: &'i [u8], kind: ErrorKind) -> Self {
+        Self::new(
+            input,
+            match kind {
+                ErrorKind::Digit => Expected::Digit,
+                kind => Expected::Other(kind),
+            },
+        )
+    }
+
+    fn append(_: &'i [u8], _: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn from_char(input: &'i [u8], c: char) -> Self {
+        match u8::try_from(c) {
+            Ok(b) => Self::new(input, Expected::Byte(b)),
+            Err(_) => Self::new(input, Expected::Other(ErrorKind::Char)),
+        }
+    }
+
+    /// Keep whichever error got further into the input, or merge their expectations if both
+    /// failed at the same place.
+    fn or(mut self, other: Self) -> Self {
+        match self.input.len().cmp(&other.input.len()) {
+            std::cmp::Ordering::Less => self,
+            std::cmp::Ordering::Greater => other,
+            std::cmp::Ordering::Equal => {
+                for expected in other.expected {
+                    if !self.expected.contains(&expected) {
+                        self.expected.push(expected);
+                    }
+                }
+                if self.containers.len() < other.containers.len() {
+                    self.containers = other.containers;
+                }
+                self.origin = self.origin.or(other.origin);
+                self
+            }
+        }
+    }
+}
+
+impl<'i> ContextError<&'i [u8]> for ParseError<'i> {
+    fn add_context(input: &'i [u8], ctx: &'static str, mut other: Self) -> Self {
+        other.containers.push((ctx, input));
+        other
+    }
+}
+
+impl<'i> FromExternalError<&'i [u8], ParseIntError> for ParseError<'i> {
+    fn from_external_error(input: &'i [u8], _: ErrorKind, _: ParseIntError) -> Self {
+        Self::new(input, Expected::Length)
+    }
+}
@@ -0,0 +1,106 @@
+use proptest::prelude::*;
+
+use crate::de::{DecodeBytesError, Expected, ParseError, TokenTree};
+
+fn parse_err(input: &[u8]) -> ParseError<'_> {
+    match TokenTree::<&[u8]>::parse_located(input) {
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => e,
+        res => panic!(
+            "expected {:?} to fail, got {res:?}",
+            input.escape_ascii().to_string()
+        ),
+    }
+}
+
+#[test]
+fn reports_expected_separators() {
+    let e = parse_err(b"12x");
+    assert_eq!(e.offset(), Some(2));
+    assert_eq!(e.found(), Some(b'x'));
+    for b in *b"+-:\"'" {
+        assert!(e.expected().contains(&Expected::Byte(b)), "{e:?}");
+    }
+    assert_eq!(e.containers().count(), 0);
+}
+
+#[test]
+fn reports_expected_value_or_close() {
+    let e = parse_err(b"[1+2]");
+    assert_eq!(e.offset(), Some(4));
+    assert_eq!(e.found(), Some(b']'));
+    assert!(e.expected().contains(&Expected::Byte(b'+')));
+
+    let e = parse_err(b"[1+2+)");
+    assert_eq!(e.offset(), Some(5));
+    assert!(e.expected().contains(&Expected::Digit));
+    assert!(e.expected().contains(&Expected::Byte(b'[')));
+    assert!(e.expected().contains(&Expected::Byte(b']')));
+    assert_eq!(e.containers().collect::<Vec<_>>(), [("list", Some(0))]);
+    assert_eq!(
+        e.to_string(),
+        "unexpected ')' at byte 5, expected a value or ']', in list@0"
+    );
+}
+
+#[test]
+fn reports_open_containers() {
+    let e = parse_err(b"{3'key<5'label#t$1x>}");
+    assert_eq!(e.offset(), Some(18));
+    assert_eq!(
+        e.containers().collect::<Vec<_>>(),
+        [("dictionary", Some(0)), ("record", Some(6))]
+    );
+
+    // a dictionary key without a value
+    let e = parse_err(b"{1+}");
+    assert_eq!(e.offset(), Some(3));
+    assert!(!e.expected().contains(&Expected::Byte(b'}')));
+}
+
+#[test]
+fn reports_unknown_offsets_without_origin() {
+    let Err(nom::Err::Failure(e)) = TokenTree::<&[u8]>::parse::<ParseError<'_>>(b"[x]") else {
+        panic!()
+    };
+    assert_eq!(e.offset(), None);
+    assert_eq!(e.input(), b"x]");
+    assert_eq!(e.containers().collect::<Vec<_>>(), [("list", None)]);
+    assert_eq!(e.hex_context(4), "00000000: 78 5d  |x]|\n          ^^\n");
+}
+
+#[test]
+fn renders_hex_context() {
+    let input = b"<3'foo\x001x>";
+    let e = parse_err(input);
+    assert_eq!(e.offset(), Some(6));
+    assert_eq!(
+        e.hex_context(3),
+        "00000003: 66 6f 6f 00 31 78 3e  |foo.1x>|\n                   ^^\n"
+    );
+}
+
+#[test]
+fn decode_bytes_reports_offsets() {
+    let Err(DecodeBytesError::Lex(nom::Err::Failure(e))) =
+        crate::decode_bytes!(b"[1+2]" => Vec<i32>)
+    else {
+        panic!()
+    };
+    assert_eq!(e.offset(), Some(4));
+}
+
+proptest! {
+    #[test]
+    fn reports_offset_of_garbage(tree in TokenTree::<Vec<u8>>::arbitrary(), garbage in b'a'..=b'c') {
+        // wrap the tree in a list and put a byte that can't start any value after it
+        let mut input = b"[".to_vec();
+        input.extend(tree.to_bytes().iter());
+        let offset = input.len();
+        input.push(garbage);
+        input.push(b']');
+        let e = parse_err(&input);
+        prop_assert_eq!(e.offset(), Some(offset));
+        prop_assert_eq!(e.found(), Some(garbage));
+        prop_assert!(e.expected().contains(&Expected::Byte(b']')));
+    }
+}
@@ -0,0 +1,380 @@
+use std::cmp::Ordering;
+
+use borrow_or_share::Bos;
+use nom::Needed;
+
+use crate::de::lex::{
+    CanonicalRule, Dictionary, List, Literal, NotCanonical, ParseLiteralError, Record, Set,
+    SpanTree, TokenTree, collection::sort_merging,
+};
+
+#[cfg(test)]
+mod test;
+
+/// Bounds on the size and shape of input accepted by [`TokenTree::parse_with_limits`].
+///
+/// The defaults are generous enough for ordinary messages while keeping a malicious peer from
+/// exhausting the stack or memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParseLimits {
+    /// Maximum nesting depth of lists, records, sets, and dictionaries; a bare literal has a
+    /// depth of 0
+    pub max_depth: usize,
+    /// Maximum length of a bytes, string, or symbol literal, and maximum number of digits in an
+    /// integer
+    pub max_literal_len: u64,
+    /// Maximum number of elements in a single list, record (not counting the label), set, or
+    /// dictionary (counting each entry once)
+    pub max_elements: usize,
+    /// Maximum total number of values in the tree, including nested ones
+    pub max_nodes: usize,
+}
+
+impl ParseLimits {
+    /// No limits at all; equivalent to [`TokenTree::parse`].
+    pub const UNLIMITED: Self = Self {
+        max_depth: usize::MAX,
+        max_literal_len: u64::MAX,
+        max_elements: usize::MAX,
+        max_nodes: usize::MAX,
+    };
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 128,
+            max_literal_len: 16 * 1024 * 1024,
+            max_elements: 64 * 1024,
+            max_nodes: 1024 * 1024,
+        }
+    }
+}
+
+/// Which of the [`ParseLimits`] was exceeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Limit {
+    Depth,
+    LiteralLength,
+    Elements,
+    Nodes,
+}
+
+impl std::fmt::Display for Limit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Limit::Depth => "nesting depth",
+            Limit::LiteralLength => "literal length",
+            Limit::Elements => "collection element count",
+            Limit::Nodes => "total value count",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+#[error("exceeded {limit} limit at byte {offset}")]
+pub struct LimitExceeded {
+    pub limit: Limit,
+    /// Offset from the start of the input of the value that exceeded the limit
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum LimitedParseError<E> {
+    #[error(transparent)]
+    Limit(#[from] LimitExceeded),
+    /// Only from [`TokenTree::parse_canonical`]
+    #[error(transparent)]
+    NotCanonical(#[from] NotCanonical),
+    #[error("{0}")]
+    Parse(nom::Err<E>),
+}
+
+impl<E> From<nom::Err<E>> for LimitedParseError<E> {
+    fn from(value: nom::Err<E>) -> Self {
+        Self::Parse(value)
+    }
+}
+
+/// Whether the literal at the start of `i`, which may be incomplete, is longer than `limits`
+/// allow; only its length prefix or digits need to be there.
+pub(crate) fn literal_too_long(i: &[u8], limits: &ParseLimits) -> bool {
+    let digits = i.iter().take_while(|b| b.is_ascii_digit()).count();
+    match i.get(digits) {
+        // the length prefix of a bytes/string/symbol literal; anything too large to parse is
+        // certainly too long
+        Some(b':' | b'"' | b'\'') if digits > 0 => std::str::from_utf8(&i[..digits])
+            .ok()
+            .and_then(|len| len.parse::<u64>().ok())
+            .is_none_or(|len| len > limits.max_literal_len),
+        // the digits of an integer (or of an incomplete literal)
+        _ => digits as u64 > limits.max_literal_len,
+    }
+}
+
+pub(super) type LimitedResult<'i, T, E> = Result<(&'i [u8], T), LimitedParseError<E>>;
+
+pub(super) struct LimitedParser<'i, 'l> {
+    start: &'i [u8],
+    limits: &'l ParseLimits,
+    nodes: usize,
+    /// Whether to reject input that isn't in canonical form
+    canonical: bool,
+    /// Whether to build a [`SpanTree`] alongside the tree
+    spans: bool,
+}
+
+/// A parsed node, along with its spans if they're being recorded
+type Node<Data> = (TokenTree<Data>, Option<SpanTree>);
+
+impl<'i, 'l> LimitedParser<'i, 'l> {
+    pub(super) const fn new(start: &'i [u8], limits: &'l ParseLimits, canonical: bool) -> Self {
+        Self {
+            start,
+            limits,
+            nodes: 0,
+            canonical,
+            spans: false,
+        }
+    }
+
+    const fn offset(&self, i: &'i [u8]) -> usize {
+        self.start.len() - i.len()
+    }
+
+    fn exceeded<E>(&self, limit: Limit, i: &'i [u8]) -> LimitedParseError<E> {
+        LimitedParseError::Limit(LimitExceeded {
+            limit,
+            offset: self.offset(i),
+        })
+    }
+
+    fn not_canonical<E>(&self, rule: CanonicalRule, i: &'i [u8]) -> LimitedParseError<E> {
+        LimitedParseError::NotCanonical(NotCanonical {
+            rule,
+            offset: self.offset(i),
+        })
+    }
+
+    pub(super) fn tree<Data, E>(
+        &mut self,
+        i: &'i [u8],
+        depth: usize,
+    ) -> LimitedResult<'i, TokenTree<Data>, E>
+    where
+        E: ParseLiteralError<'i>,
+        Data: Bos<[u8]>,
+        &'i [u8]: Into<Data>,
+    {
+        let (rem, (tree, _)) = self.node(i, depth)?;
+        Ok((rem, tree))
+    }
+
+    /// Like [`Self::tree`], but also returns the spans of its nodes.
+    pub(super) fn spanned_tree<Data, E>(
+        &mut self,
+        i: &'i [u8],
+    ) -> LimitedResult<'i, (TokenTree<Data>, SpanTree), E>
+    where
+        E: ParseLiteralError<'i>,
+        Data: Bos<[u8]>,
+        &'i [u8]: Into<Data>,
+    {
+        self.spans = true;
+        let (rem, (tree, spans)) = self.node(i, 0)?;
+        let spans = spans.expect("the parser records the spans of every node");
+        Ok((rem, (tree, spans)))
+    }
+
+    /// Sort set members or dictionary entries by the encoding of the tree `key` picks out of
+    /// each, unless they're already known to be sorted.
+    fn sort<T, Data: Bos<[u8]>>(
+        &self,
+        entries: Vec<T>,
+        key: impl Fn(&T) -> &TokenTree<Data>,
+    ) -> Vec<T> {
+        if self.canonical {
+            // checked while parsing
+            entries
+        } else {
+            sort_merging(entries, key)
+        }
+    }
+
+    fn node<Data, E>(&mut self, i: &'i [u8], depth: usize) -> LimitedResult<'i, Node<Data>, E>
+    where
+        E: ParseLiteralError<'i>,
+        Data: Bos<[u8]>,
+        &'i [u8]: Into<Data>,
+    {
+        self.nodes += 1;
+        if self.nodes > self.limits.max_nodes {
+            return Err(self.exceeded(Limit::Nodes, i));
+        }
+        let Some(&first) = i.first() else {
+            return Err(nom::Err::Incomplete(Needed::new(1)).into());
+        };
+        let offset = self.offset(i);
+        let close = match first {
+            b'[' => b']',
+            b'<' => b'>',
+            b'#' => b'$',
+            b'{' => b'}',
+            _ => {
+                let (rem, literal) = self.literal(i)?;
+                let spans = self
+                    .spans
+                    .then(|| SpanTree::Literal(offset..self.offset(rem)));
+                return Ok((rem, (TokenTree::Literal(literal), spans)));
+            }
+        };
+        if depth >= self.limits.max_depth {
+            return Err(self.exceeded(Limit::Depth, i));
+        }
+        let mut i = &i[1..];
+        let mut elements: Vec<Node<Data>> = Vec::new();
+        let mut prev_sorted: Option<&[u8]> = None;
+        loop {
+            match i.first() {
+                Some(&b) if b == close => break,
+                Some(_) => {}
+                None => return Err(nom::Err::Incomplete(Needed::new(1)).into()),
+            }
+            let count = match first {
+                // the label isn't an element
+                b'<' => elements.len().saturating_sub(1),
+                // keys and values are parsed as separate elements here
+                b'{' => elements.len() / 2,
+                _ => elements.len(),
+            };
+            if count >= self.limits.max_elements {
+                return Err(self.exceeded(Limit::Elements, i));
+            }
+            let (rem, element) = self.node(i, depth + 1)?;
+            // set members and dictionary keys must be in order of their encodings
+            let sorted = match first {
+                b'#' => true,
+                b'{' => elements.len().is_multiple_of(2),
+                _ => false,
+            };
+            if self.canonical && sorted {
+                let encoded = &i[..i.len() - rem.len()];
+                if let Some(prev) = prev_sorted {
+                    match encoded.cmp(prev) {
+                        Ordering::Greater => {}
+                        Ordering::Equal => {
+                            return Err(self.not_canonical(CanonicalRule::Duplicate, i));
+                        }
+                        Ordering::Less => {
+                            return Err(self.not_canonical(CanonicalRule::Unsorted, i));
+                        }
+                    }
+                }
+                prev_sorted = Some(encoded);
+            }
+            elements.push(element);
+            i = rem;
+        }
+        let malformed = |kind| Err(nom::Err::Error(E::from_error_kind(i, kind)).into());
+        let span = offset..self.offset(i) + 1;
+        let (tree, spans) = match first {
+            b'<' => {
+                let mut elements = elements.into_iter();
+                let Some((label, label_spans)) = elements.next() else {
+                    return malformed(nom::error::ErrorKind::Verify);
+                };
+                let (elements, spans): (Vec<_>, Vec<_>) = elements.unzip();
+                let spans =
+                    label_spans
+                        .zip(spans.into_iter().collect())
+                        .map(|(label, elements)| SpanTree::Record {
+                            span,
+                            label: Box::new(label),
+                            elements,
+                        });
+                let record = Record::new(label, elements);
+                (TokenTree::Record(Box::new(record)), spans)
+            }
+            b'#' => {
+                let (entries, spans): (Vec<_>, Vec<_>) =
+                    self.sort(elements, |(entry, _)| entry).into_iter().unzip();
+                let spans = spans
+                    .into_iter()
+                    .collect::<Option<_>>()
+                    .map(|entries| SpanTree::Set { span, entries });
+                (TokenTree::Set(Set::from_sorted(entries)), spans)
+            }
+            b'{' => {
+                if !elements.len().is_multiple_of(2) {
+                    // a key without a value
+                    return malformed(nom::error::ErrorKind::Count);
+                }
+                let mut entries = Vec::with_capacity(elements.len() / 2);
+                let mut elements = elements.into_iter();
+                while let (Some(key), Some(value)) = (elements.next(), elements.next()) {
+                    entries.push((key, value));
+                }
+                let (entries, spans): (Vec<_>, Vec<_>) = self
+                    .sort(entries, |((key, _), _)| key)
+                    .into_iter()
+                    .map(|((key, key_spans), (value, value_spans))| {
+                        ((key, value), key_spans.zip(value_spans))
+                    })
+                    .unzip();
+                let spans = spans
+                    .into_iter()
+                    .collect::<Option<_>>()
+                    .map(|entries| SpanTree::Dictionary { span, entries });
+                (
+                    TokenTree::Dictionary(Dictionary::from_sorted(entries)),
+                    spans,
+                )
+            }
+            _ => {
+                let (elements, spans): (Vec<_>, Vec<_>) = elements.into_iter().unzip();
+                let spans = spans
+                    .into_iter()
+                    .collect::<Option<_>>()
+                    .map(|elements| SpanTree::List { span, elements });
+                (TokenTree::List(List::new(elements)), spans)
+            }
+        };
+        let i = &i[1..];
+        Ok((i, (tree, spans.filter(|_| self.spans))))
+    }
+
+    fn literal<Data, E>(&self, i: &'i [u8]) -> LimitedResult<'i, Literal<Data>, E>
+    where
+        E: ParseLiteralError<'i>,
+        &'i [u8]: Into<Data>,
+    {
+        if literal_too_long(i, self.limits) {
+            return Err(self.exceeded(Limit::LiteralLength, i));
+        }
+        let digits = i.iter().take_while(|b| b.is_ascii_digit()).count();
+        let res = Literal::parse(i)?;
+        // the digits of an integer or of a length prefix
+        if self.canonical && digits > 1 && i[0] == b'0' {
+            return Err(self.not_canonical(CanonicalRule::LeadingZero, i));
+        }
+        if self.canonical && i.starts_with(b"0-") {
+            return Err(self.not_canonical(CanonicalRule::NegativeZero, i));
+        }
+        Ok(res)
+    }
+}
+
+impl<Data> TokenTree<Data> {
+    /// Like [`TokenTree::parse`], but fails with [`LimitedParseError::Limit`] as soon as the input
+    /// exceeds any of the given `limits`.
+    pub fn parse_with_limits<'i, E: ParseLiteralError<'i>>(
+        i: &'i [u8],
+        limits: &ParseLimits,
+    ) -> Result<(&'i [u8], Self), LimitedParseError<E>>
+    where
+        Data: Bos<[u8]>,
+        &'i [u8]: Into<Data>,
+    {
+        LimitedParser::new(i, limits, false).tree(i, 0)
+    }
+}
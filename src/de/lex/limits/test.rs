@@ -0,0 +1,144 @@
+use proptest::prelude::*;
+
+use super::LimitedResult;
+use crate::de::lex::{Limit, LimitExceeded, LimitedParseError, ParseLimits, TokenTree};
+
+type E<'i> = nom::error::Error<&'i [u8]>;
+
+fn parse<'i>(
+    input: &'i [u8],
+    limits: &ParseLimits,
+) -> LimitedResult<'i, TokenTree<&'i [u8]>, E<'i>> {
+    TokenTree::parse_with_limits(input, limits)
+}
+
+fn exceeded(input: &[u8], limits: &ParseLimits) -> Option<LimitExceeded> {
+    match parse(input, limits) {
+        Err(LimitedParseError::Limit(exceeded)) => Some(exceeded),
+        _ => None,
+    }
+}
+
+#[test]
+fn limits_depth() {
+    let limits = ParseLimits {
+        max_depth: 2,
+        ..ParseLimits::default()
+    };
+    assert!(parse(b"[[1+]]", &limits).is_ok());
+    assert_eq!(
+        exceeded(b"[<1'a[]>]", &limits),
+        Some(LimitExceeded {
+            limit: Limit::Depth,
+            offset: 5
+        })
+    );
+    // deep enough to overflow the stack without a limit
+    let deep = vec![b'['; 1 << 20];
+    assert_eq!(
+        exceeded(&deep, &ParseLimits::default()).map(|e| e.limit),
+        Some(Limit::Depth)
+    );
+}
+
+#[test]
+fn limits_literal_length() {
+    let limits = ParseLimits {
+        max_literal_len: 3,
+        ..ParseLimits::default()
+    };
+    assert!(parse(b"[3:abc3\"abc3'abc123+]", &limits).is_ok());
+    assert_eq!(
+        exceeded(b"[3:abc4:abcd]", &limits),
+        Some(LimitExceeded {
+            limit: Limit::LiteralLength,
+            offset: 6
+        })
+    );
+    assert_eq!(
+        exceeded(b"4\"abcd", &limits).map(|e| e.limit),
+        Some(Limit::LiteralLength)
+    );
+    assert_eq!(
+        exceeded(b"1234-", &limits).map(|e| e.limit),
+        Some(Limit::LiteralLength)
+    );
+    // a length prefix too large for any buffer, without the data that should follow it
+    assert_eq!(
+        exceeded(b"99999999999999999999999:", &ParseLimits::default()).map(|e| e.limit),
+        Some(Limit::LiteralLength)
+    );
+}
+
+#[test]
+fn limits_elements() {
+    let limits = ParseLimits {
+        max_elements: 2,
+        ..ParseLimits::default()
+    };
+    assert!(parse(b"[1+2+]<1'a1+2+>#1+2+${1'a1+1'b2+}", &limits).is_ok());
+    assert_eq!(
+        exceeded(b"[1+2+3+]", &limits),
+        Some(LimitExceeded {
+            limit: Limit::Elements,
+            offset: 5
+        })
+    );
+    assert_eq!(
+        exceeded(b"<1'a1+2+3+>", &limits).map(|e| e.limit),
+        Some(Limit::Elements)
+    );
+    assert_eq!(
+        exceeded(b"{1'a1+1'b2+1'c3+}", &limits).map(|e| e.limit),
+        Some(Limit::Elements)
+    );
+}
+
+#[test]
+fn limits_nodes() {
+    let limits = ParseLimits {
+        max_nodes: 4,
+        ..ParseLimits::default()
+    };
+    assert!(parse(b"[[1+]2+]", &limits).is_ok());
+    assert_eq!(
+        exceeded(b"[[1+]2+3+]", &limits),
+        Some(LimitExceeded {
+            limit: Limit::Nodes,
+            offset: 7
+        })
+    );
+}
+
+#[test]
+fn reports_syntax_errors() {
+    let limits = ParseLimits::default();
+    assert!(matches!(
+        parse(b"[1+", &limits),
+        Err(LimitedParseError::Parse(nom::Err::Incomplete(_)))
+    ));
+    assert!(matches!(
+        parse(b"<>", &limits),
+        Err(LimitedParseError::Parse(nom::Err::Error(_)))
+    ));
+    assert!(matches!(
+        parse(b"{1+}", &limits),
+        Err(LimitedParseError::Parse(nom::Err::Error(_)))
+    ));
+    assert!(matches!(
+        parse(b"[x]", &limits),
+        Err(LimitedParseError::Parse(nom::Err::Error(_)))
+    ));
+}
+
+proptest! {
+    #[test]
+    fn parses_like_unlimited(tree in TokenTree::arbitrary()) {
+        let bytes = tree.to_bytes();
+        let res = parse(&bytes, &ParseLimits::default());
+        prop_assert!(res.is_ok(), "encoded input: `{}`, error: {}", String::from_utf8_lossy(&bytes), res.unwrap_err());
+        let (rem, res) = res.unwrap();
+        prop_assert_eq!(rem, b"".as_slice());
+        prop_assert_eq!(&res, &tree);
+    }
+}
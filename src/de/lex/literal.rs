@@ -4,10 +4,7 @@ use borrow_or_share::{BorrowOrShare, Bos};
 use nom::{
     IResult, Mode, Needed, OutputMode, Parser,
     bytes::streaming as bytes,
-    character::streaming::one_of,
-    combinator::recognize,
-    error::{FromExternalError, ParseError},
-    multi::many1,
+    error::{ContextError, ErrorKind, FromExternalError, ParseError},
     sequence::preceded,
 };
 
@@ -86,10 +83,9 @@ pub fn bool_literal<'i, E: ParseError<&'i [u8]>>(i: &'i [u8]) -> IResult<&'i [u8
     match c {
         b't' => Ok((&i[1..], true)),
         b'f' => Ok((&i[1..], false)),
-        _ => Err(nom::Err::Error(E::from_error_kind(
-            i,
-            nom::error::ErrorKind::Char,
-        ))),
+        _ => Err(nom::Err::Error(
+            E::from_char(i, 't').or(E::from_char(i, 'f')),
+        )),
     }
 }
 
@@ -102,7 +98,15 @@ pub fn f64_literal<'i, E: ParseError<&'i [u8]>>(i: &'i [u8]) -> IResult<&'i [u8]
 }
 
 fn digits_dec<'i, E: ParseError<&'i [u8]>>(i: &'i [u8]) -> IResult<&'i [u8], &'i [u8], E> {
-    recognize(many1(one_of("0123456789"))).parse(i)
+    let len = i.iter().take_while(|b| b.is_ascii_digit()).count();
+    if len == i.len() {
+        // more digits may follow
+        Err(nom::Err::Incomplete(Needed::new(1)))
+    } else if len == 0 {
+        Err(nom::Err::Error(E::from_error_kind(i, ErrorKind::Digit)))
+    } else {
+        Ok((&i[len..], &i[..len]))
+    }
 }
 
 pub fn int_literal<'i, Digits, E: ParseError<&'i [u8]>>(
@@ -113,18 +117,18 @@ where
 {
     #[expect(unsafe_code)]
     digits_dec
-        .and(one_of("+-").map(|v| v == '+'))
+        .and(byte(b'+').map(|()| true).or(byte(b'-').map(|()| false)))
         .map(|(digits, positive)| unsafe { Int::new(positive, digits.into()) })
         .parse(i)
 }
 
 pub trait ParseLiteralError<'i>:
-    ParseError<&'i [u8]> + FromExternalError<&'i [u8], ParseIntError>
+    ParseError<&'i [u8]> + ContextError<&'i [u8]> + FromExternalError<&'i [u8], ParseIntError>
 {
 }
 
-impl<'i, E: ParseError<&'i [u8]> + FromExternalError<&'i [u8], ParseIntError>> ParseLiteralError<'i>
-    for E
+impl<'i, E> ParseLiteralError<'i> for E where
+    E: ParseError<&'i [u8]> + ContextError<&'i [u8]> + FromExternalError<&'i [u8], ParseIntError>
 {
 }
 
@@ -216,7 +220,8 @@ where
 
 impl<LData, RData> PartialEq<Literal<RData>> for Literal<LData>
 where
-    LData: PartialEq<RData>,
+    LData: PartialEq<RData> + Bos<[u8]>,
+    RData: Bos<[u8]>,
 {
     fn eq(&self, other: &Literal<RData>) -> bool {
         match (self, other) {
@@ -232,7 +237,25 @@ where
     }
 }
 
-impl<Data> Eq for Literal<Data> where Data: PartialEq + Eq {}
+impl<Data> Eq for Literal<Data> where Data: Eq + Bos<[u8]> {}
+
+impl<Data> std::hash::Hash for Literal<Data>
+where
+    Data: Bos<[u8]>,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Literal::Bool(b) => b.hash(state),
+            Literal::F32(fl) => fl.to_be_bytes().hash(state),
+            Literal::F64(fl) => fl.to_be_bytes().hash(state),
+            Literal::Int(int) => int.hash(state),
+            Literal::Bytes(data) | Literal::String(data) | Literal::Symbol(data) => {
+                data.borrow_or_share().hash(state);
+            }
+        }
+    }
+}
 
 impl<Data> Literal<Data> {
     pub fn encode<'i, 'o>(&'i self) -> Cow<'o, [u8]>
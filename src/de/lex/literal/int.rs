@@ -10,19 +10,32 @@ use std::{
 use borrow_or_share::{BorrowOrShare, Bos};
 
 /// An integer literal.
-#[derive(Clone, Copy, Hash)]
+#[derive(Clone, Copy)]
 pub struct Int<Digits> {
     pub positive: bool,
     /// SAFETY: must only contain ASCII digit characters 0-9
     digits: Digits,
 }
 
+/// Compares the integers' canonical forms, so that equal integers are those that encode the same.
 impl<LDigits, RDigits> PartialEq<Int<RDigits>> for Int<LDigits>
 where
-    LDigits: PartialEq<RDigits>,
+    LDigits: Bos<[u8]>,
+    RDigits: Bos<[u8]>,
 {
     fn eq(&self, other: &Int<RDigits>) -> bool {
-        self.positive == other.positive && self.digits.eq(&other.digits)
+        self.canonical() == other.canonical()
+    }
+}
+
+impl<Digits> Eq for Int<Digits> where Digits: Bos<[u8]> {}
+
+impl<Digits> std::hash::Hash for Int<Digits>
+where
+    Digits: Bos<[u8]>,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.canonical().hash(state);
     }
 }
 
@@ -39,15 +52,44 @@ where
     }
 }
 
+/// Writes the integer in decimal, as it is canonically encoded, without a `+` sign.
+impl<Digits> std::fmt::Display for Int<Digits>
+where
+    Digits: Bos<[u8]>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (digits, sign) = self.canonical();
+        if sign == b'-' {
+            f.write_str("-")?;
+        }
+        #[expect(unsafe_code, reason = "digits are always ascii")]
+        f.write_str(unsafe { std::str::from_utf8_unchecked(digits) })
+    }
+}
+
 impl<Digits> Int<Digits> {
-    pub fn encode<'i, 'o>(&'i self) -> Cow<'o, [u8]>
+    /// The digits and sign this integer is canonically encoded with: no leading zeros, and zero
+    /// is always positive.
+    fn canonical<'i, 'o>(&'i self) -> (&'o [u8], u8)
     where
         Digits: BorrowOrShare<'i, 'o, [u8]>,
     {
         let digits = self.digits.borrow_or_share();
+        let zeros = digits.iter().take_while(|&&d| d == b'0').count();
+        match &digits[zeros..] {
+            [] => (b"0", b'+'),
+            digits => (digits, if self.positive { b'+' } else { b'-' }),
+        }
+    }
+
+    pub fn encode<'i, 'o>(&'i self) -> Cow<'o, [u8]>
+    where
+        Digits: BorrowOrShare<'i, 'o, [u8]>,
+    {
+        let (digits, sign) = self.canonical();
         let mut res = Vec::with_capacity(digits.len() + 1);
         res.extend_from_slice(digits);
-        res.push(if self.positive { b'+' } else { b'-' });
+        res.push(sign);
         Cow::Owned(res)
     }
 
@@ -55,9 +97,9 @@ impl<Digits> Int<Digits> {
     where
         Digits: Bos<[u8]>,
     {
-        let digits = self.digits.borrow_or_share();
+        let (digits, sign) = self.canonical();
         w.write_all(digits)?;
-        w.write_all(if self.positive { b"+" } else { b"-" })?;
+        w.write_all(&[sign])?;
         Ok(digits.len() + 1)
     }
 
@@ -111,14 +153,21 @@ pub struct DecodeIntError {
 
 impl std::fmt::Display for DecodeIntError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+        f.write_str(match self.kind {
+            IntErrorKind::Empty => "cannot decode integer from empty string",
+            IntErrorKind::InvalidDigit => "invalid digit found in string",
+            IntErrorKind::PosOverflow => "number too large to fit in target type",
+            IntErrorKind::NegOverflow => "number too small to fit in target type",
+            IntErrorKind::Zero => "number would be zero for non-zero type",
+            _ => "unknown integer decoding error",
+        })
     }
 }
 
 impl From<ParseIntError> for DecodeIntError {
     fn from(value: ParseIntError) -> Self {
         Self {
-            kind: value.kind().clone(),
+            kind: *value.kind(),
         }
     }
 }
@@ -173,7 +222,7 @@ macro_rules! impl_int {
             impl From<$Int> for Int<Vec<u8>> {
                 fn from(val: $Int) -> Self {
                     #[expect(unsafe_code)]
-                    unsafe { Self::new(val.is_positive(), val.unsigned_abs().to_string().into_bytes()) }
+                    unsafe { Self::new(!val.is_negative(), val.unsigned_abs().to_string().into_bytes()) }
                 }
             }
         )+
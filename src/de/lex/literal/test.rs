@@ -77,7 +77,7 @@ proptest! {
         let res = super::f32_literal::<E<'_>>(&bytes);
         prop_assert!(res.is_ok());
         let (rem, res) = res.unwrap();
-        prop_assert_eq!(rem, [].as_slice());
+        prop_assert_eq!(rem, b"".as_slice());
         prop_assert_eq!(res.to_be_bytes(), f.to_be_bytes());
     }
 
@@ -89,7 +89,7 @@ proptest! {
         let res = super::f64_literal::<E<'_>>(&bytes);
         prop_assert!(res.is_ok());
         let (rem, res) = res.unwrap();
-        prop_assert_eq!(rem, [].as_slice());
+        prop_assert_eq!(rem, b"".as_slice());
         prop_assert_eq!(res.to_be_bytes(), f.to_be_bytes());
     }
 
@@ -99,7 +99,7 @@ proptest! {
         let res = super::int_literal::<&[u8], E<'_>>(&input);
         prop_assert!(res.is_ok());
         let (rem, res) = res.unwrap();
-        prop_assert_eq!(rem, [].as_slice());
+        prop_assert_eq!(rem, b"".as_slice());
         prop_assert_eq!(&res, &num);
     }
 
@@ -126,7 +126,7 @@ proptest! {
         let res = super::Literal::<&[u8]>::parse::<E<'_>>(&input);
         prop_assert!(res.is_ok(), "encoded input: `{}`, error: {}", String::from_utf8_lossy(&input), res.unwrap_err());
         let (rem, res) = res.unwrap();
-        prop_assert_eq!(rem, [].as_slice());
+        prop_assert_eq!(rem, b"".as_slice());
         prop_assert_eq!(&res, &literal);
     }
 }
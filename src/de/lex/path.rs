@@ -0,0 +1,217 @@
+use std::{fmt, str::FromStr};
+
+use borrow_or_share::Bos;
+
+use crate::de::lex::{TextParseError, TokenTree};
+
+#[cfg(test)]
+mod test;
+
+/// A path to part of a [`TokenTree`], written as steps separated by `/`:
+///
+/// | step      | goes to                                                               |
+/// |-----------|-----------------------------------------------------------------------|
+/// | `N`       | the element at index `N` of a list or record, not counting its label |
+/// | `[N]`     | the element at index `N` of a list                                    |
+/// | `{key}`   | the value for `key` in a dictionary                                   |
+/// | `<label>` | stays on a record, only if it has this label                          |
+///
+/// Keys and labels are written in the [text syntax](TokenTree::parse_text), and compared in
+/// canonical form. A key or label runs up to the first `}/` or `>/` that closes it, or the end of
+/// the path, so it may contain `/` itself. Empty steps are ignored, so the empty path leads to
+/// the tree itself.
+///
+/// ```
+/// # use syrup::{TokenTree, de::TreePath};
+/// let tree: TokenTree<Vec<u8>> = r#"<op:deliver 3 ["ping" {"id": 5}]>"#.parse().unwrap();
+/// let path: TreePath = r#"<op:deliver>/1/[1]/{"id"}"#.parse().unwrap();
+/// assert_eq!(path.get(&tree).and_then(TokenTree::as_int::<u8>), Some(5));
+/// assert_eq!(path.to_string(), r#"<op:deliver>/1/[1]/{"id"}"#);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct TreePath {
+    steps: Vec<PathStep>,
+}
+
+/// One step of a [`TreePath`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathStep {
+    /// An element of a list or record, not counting the label
+    Element(usize),
+    /// An element of a list
+    ListElement(usize),
+    /// The record itself, if it has this label
+    Label(TokenTree<Vec<u8>>),
+    /// The value for this key in a dictionary
+    Key(TokenTree<Vec<u8>>),
+}
+
+/// An error from parsing a [`TreePath`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PathParseError {
+    #[error("invalid index in path step `{0}`")]
+    Index(String),
+    #[error("in path step `{step}`: {error}")]
+    Text { step: String, error: TextParseError },
+}
+
+impl TreePath {
+    #[inline]
+    #[must_use]
+    pub const fn new(steps: Vec<PathStep>) -> Self {
+        Self { steps }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn steps(&self) -> &[PathStep] {
+        &self.steps
+    }
+
+    /// Follow the path from `tree`, if every step leads somewhere.
+    #[must_use]
+    pub fn get<'t, Data: Bos<[u8]>>(
+        &self,
+        tree: &'t TokenTree<Data>,
+    ) -> Option<&'t TokenTree<Data>> {
+        self.steps
+            .iter()
+            .try_fold(tree, |tree, step| step.get(tree))
+    }
+}
+
+impl PathStep {
+    /// Take this step from `tree`, if it leads somewhere.
+    #[must_use]
+    pub fn get<'t, Data: Bos<[u8]>>(
+        &self,
+        tree: &'t TokenTree<Data>,
+    ) -> Option<&'t TokenTree<Data>> {
+        match (self, tree) {
+            (PathStep::Element(index) | PathStep::ListElement(index), TokenTree::List(list)) => {
+                list.elements.get(*index)
+            }
+            (PathStep::Element(index), TokenTree::Record(record)) => record.elements.get(*index),
+            (PathStep::Label(label), TokenTree::Record(record)) => {
+                (record.label.to_bytes() == label.to_bytes()).then_some(tree)
+            }
+            (PathStep::Key(key), TokenTree::Dictionary(dict)) => dict.get(key),
+            _ => None,
+        }
+    }
+}
+
+impl<Data> TokenTree<Data> {
+    /// Follow a [`TreePath`] written as text from this tree.
+    ///
+    /// Returns `None` if the path leads nowhere, or isn't valid; parse it as a [`TreePath`] to
+    /// find out what's wrong with it.
+    ///
+    /// ```
+    /// # use syrup::{TokenTree, symbol::Symbol};
+    /// let tree: TokenTree<Vec<u8>> = "<op:deliver 3 [ping 7]>".parse().unwrap();
+    /// let ping = tree.get_path("<op:deliver>/1/[0]");
+    /// assert_eq!(ping.and_then(TokenTree::as_symbol), Some(Symbol("ping")));
+    /// assert_eq!(tree.get_path("<op:abort>/0"), None);
+    /// assert_eq!(tree.get_path("[0]"), None);
+    /// ```
+    #[must_use]
+    pub fn get_path(&self, path: &str) -> Option<&Self>
+    where
+        Data: Bos<[u8]>,
+    {
+        path.parse::<TreePath>().ok()?.get(self)
+    }
+}
+
+impl FromStr for TreePath {
+    type Err = PathParseError;
+
+    fn from_str(mut path: &str) -> Result<Self, Self::Err> {
+        let mut steps = Vec::new();
+        while !path.is_empty() {
+            let (step, rest) = next_step(path)?;
+            steps.extend(step);
+            path = rest;
+        }
+        Ok(Self { steps })
+    }
+}
+
+/// Parse the first step of `path`, if it isn't empty, returning it along with the rest of the
+/// path.
+fn next_step(path: &str) -> Result<(Option<PathStep>, &str), PathParseError> {
+    let close = match path.as_bytes()[0] {
+        b'<' => ">/",
+        b'{' => "}/",
+        _ => {
+            let (step, rest) = path.split_once('/').unwrap_or((path, ""));
+            let step = (!step.is_empty()).then(|| step.parse()).transpose()?;
+            return Ok((step, rest));
+        }
+    };
+    // the step ends at the first closing delimiter it parses up to
+    let mut first_error = None;
+    for (end, _) in path.match_indices(close) {
+        match path[..=end].parse() {
+            Ok(step) => return Ok((Some(step), &path[end + 2..])),
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    match path.parse() {
+        Ok(step) => Ok((Some(step), "")),
+        Err(e) => Err(first_error.unwrap_or(e)),
+    }
+}
+
+impl FromStr for PathStep {
+    type Err = PathParseError;
+
+    fn from_str(step: &str) -> Result<Self, Self::Err> {
+        let text = |text: &str| {
+            TokenTree::parse_text(text).map_err(|error| PathParseError::Text {
+                step: step.to_owned(),
+                error,
+            })
+        };
+        let index = |index: &str| {
+            index
+                .parse()
+                .map_err(|_e| PathParseError::Index(step.to_owned()))
+        };
+        if let Some(label) = step.strip_prefix('<').and_then(|s| s.strip_suffix('>')) {
+            text(label).map(PathStep::Label)
+        } else if let Some(key) = step.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            text(key).map(PathStep::Key)
+        } else if let Some(i) = step.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            index(i).map(PathStep::ListElement)
+        } else {
+            index(step).map(PathStep::Element)
+        }
+    }
+}
+
+impl fmt::Display for PathStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathStep::Element(index) => write!(f, "{index}"),
+            PathStep::ListElement(index) => write!(f, "[{index}]"),
+            PathStep::Label(label) => write!(f, "<{label}>"),
+            PathStep::Key(key) => write!(f, "{{{key}}}"),
+        }
+    }
+}
+
+impl fmt::Display for TreePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, step) in self.steps.iter().enumerate() {
+            if i > 0 {
+                f.write_str("/")?;
+            }
+            step.fmt(f)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+use proptest::prelude::*;
+
+use crate::de::lex::{PathParseError, PathStep, TokenTree, TreePath, test::MaxDepth};
+
+fn parse(text: &str) -> TokenTree<Vec<u8>> {
+    text.parse()
+        .unwrap_or_else(|e| panic!("failed to parse {text:?}: {e}"))
+}
+
+#[test]
+fn follows_steps() {
+    let tree = parse(r#"<op:deliver 3 [ping {"id": 5, a/b: <x>}]>"#);
+    assert_eq!(tree.get_path(""), Some(&tree));
+    assert_eq!(tree.get_path("0"), Some(&parse("3")));
+    assert_eq!(tree.get_path("<op:deliver>/1/[0]"), Some(&parse("ping")));
+    assert_eq!(tree.get_path("/1//1/{\"id\"}/"), Some(&parse("5")));
+    // keys and labels may contain `/`
+    assert_eq!(tree.get_path("1/1/{a/b}/<x>"), Some(&parse("<x>")));
+
+    assert_eq!(tree.get_path("<op:abort>"), None);
+    assert_eq!(tree.get_path("[0]"), None);
+    assert_eq!(tree.get_path("2"), None);
+    assert_eq!(tree.get_path("1/1/{id}"), None);
+    assert_eq!(tree.get_path("1/1/0"), None);
+    assert_eq!(tree.get_path("0/0"), None);
+}
+
+#[test]
+fn parses_paths() {
+    let path: TreePath = "<op:deliver>/1/[0]/{\"id\"}".parse().unwrap();
+    assert_eq!(
+        path.steps(),
+        [
+            PathStep::Label(parse("op:deliver")),
+            PathStep::Element(1),
+            PathStep::ListElement(0),
+            PathStep::Key(parse("\"id\"")),
+        ]
+    );
+    assert_eq!(path.to_string(), "<op:deliver>/1/[0]/{\"id\"}");
+    assert_eq!("".parse::<TreePath>().unwrap(), TreePath::default());
+
+    assert_eq!(
+        "1/x".parse::<TreePath>(),
+        Err(PathParseError::Index("x".to_owned()))
+    );
+    assert_eq!(
+        "[-1]".parse::<TreePath>(),
+        Err(PathParseError::Index("[-1]".to_owned()))
+    );
+    assert!(matches!(
+        "{[1}".parse::<TreePath>(),
+        Err(PathParseError::Text { step, .. }) if step == "{[1}"
+    ));
+}
+
+/// Every path to a node in `tree`, along with the node
+fn paths<'t>(
+    tree: &'t TokenTree<Vec<u8>>,
+    path: &TreePath,
+    out: &mut Vec<(TreePath, &'t TokenTree<Vec<u8>>)>,
+) {
+    let mut step = |step: PathStep, node: &'t TokenTree<Vec<u8>>| {
+        let mut steps = path.steps().to_vec();
+        steps.push(step);
+        paths(node, &TreePath::new(steps), out);
+    };
+    match tree {
+        TokenTree::List(list) => {
+            for (i, element) in list.elements.iter().enumerate() {
+                step(PathStep::ListElement(i), element);
+            }
+        }
+        TokenTree::Record(record) => {
+            for (i, element) in record.elements.iter().enumerate() {
+                step(PathStep::Element(i), element);
+            }
+        }
+        TokenTree::Dictionary(dict) => {
+            for (key, _) in dict {
+                // the first entry with a duplicate key is the one found
+                step(PathStep::Key(key.clone()), dict.get(key).unwrap());
+            }
+        }
+        TokenTree::Set(_) | TokenTree::Literal(_) => {}
+    }
+    out.push((path.clone(), tree));
+}
+
+proptest! {
+    #[test]
+    fn reaches_every_node(tree in TokenTree::arbitrary_with(MaxDepth(2))) {
+        let mut found = Vec::new();
+        paths(&tree, &TreePath::default(), &mut found);
+        for (path, node) in found {
+            prop_assert!(path.get(&tree).is_some_and(|found| std::ptr::eq(found, node)), "{path}");
+            let text = path.to_string();
+            prop_assert!(tree.get_path(&text).is_some_and(|found| std::ptr::eq(found, node)), "{text}");
+        }
+    }
+}
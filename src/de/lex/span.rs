@@ -0,0 +1,78 @@
+use std::ops::Range;
+
+use borrow_or_share::Bos;
+
+use crate::de::lex::{LimitedParseError, LimitedParser, ParseLimits, ParseLiteralError, TokenTree};
+
+#[cfg(test)]
+mod test;
+
+/// The ranges of the input each node of a [`TokenTree`] was parsed from, shaped like the tree.
+///
+/// Set and dictionary entries are in the same order as in the tree, sorted by their canonical
+/// encodings, so the spans of any duplicates dropped from the tree are left out too.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SpanTree {
+    Literal(Range<usize>),
+    List {
+        span: Range<usize>,
+        elements: Vec<SpanTree>,
+    },
+    Record {
+        span: Range<usize>,
+        label: Box<SpanTree>,
+        elements: Vec<SpanTree>,
+    },
+    Set {
+        span: Range<usize>,
+        entries: Vec<SpanTree>,
+    },
+    Dictionary {
+        span: Range<usize>,
+        entries: Vec<(SpanTree, SpanTree)>,
+    },
+}
+
+impl SpanTree {
+    /// The range of the input the whole value was parsed from
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            SpanTree::Literal(span)
+            | SpanTree::List { span, .. }
+            | SpanTree::Record { span, .. }
+            | SpanTree::Set { span, .. }
+            | SpanTree::Dictionary { span, .. } => span.clone(),
+        }
+    }
+}
+
+impl<Data> TokenTree<Data> {
+    /// Like [`TokenTree::parse_with_limits`], but also returns the range of `i` that each node of
+    /// the tree was parsed from.
+    ///
+    /// This gives the exact bytes of any subtree, such as a signed value embedded in a message,
+    /// even if they aren't in canonical form.
+    ///
+    /// ```
+    /// # use syrup::de::{ParseLimits, SpanTree, TokenTree};
+    /// let input = b"<6'signed<4'cert3'bob003:key>3:sig>";
+    /// let (_, _tree, spans) =
+    ///     TokenTree::<&[u8]>::parse_spanned::<nom::error::Error<_>>(input, &ParseLimits::default())
+    ///         .unwrap();
+    /// let SpanTree::Record { elements, .. } = spans else {
+    ///     panic!()
+    /// };
+    /// assert_eq!(&input[elements[0].span()], b"<4'cert3'bob003:key>");
+    /// ```
+    pub fn parse_spanned<'i, E: ParseLiteralError<'i>>(
+        i: &'i [u8],
+        limits: &ParseLimits,
+    ) -> Result<(&'i [u8], Self, SpanTree), LimitedParseError<E>>
+    where
+        Data: Bos<[u8]>,
+        &'i [u8]: Into<Data>,
+    {
+        let (rem, (tree, spans)) = LimitedParser::new(i, limits, false).spanned_tree(i)?;
+        Ok((rem, tree, spans))
+    }
+}
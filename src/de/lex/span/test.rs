@@ -0,0 +1,122 @@
+use proptest::prelude::*;
+
+use crate::de::lex::{
+    Limit, LimitExceeded, LimitedParseError, ParseLimits, SpanTree, TokenTree, test::MaxDepth,
+};
+
+type E<'i> = nom::error::Error<&'i [u8]>;
+
+fn spans(input: &[u8]) -> SpanTree {
+    let (rem, _, spans) =
+        TokenTree::<&[u8]>::parse_spanned::<E<'_>>(input, &ParseLimits::default()).unwrap();
+    assert_eq!(rem, b"");
+    spans
+}
+
+/// Check that every node's span parses back to that node
+fn check_spans(input: &[u8], tree: &TokenTree<&[u8]>, spans: &SpanTree) {
+    let (rem, parsed) = TokenTree::<&[u8]>::parse::<E<'_>>(&input[spans.span()]).unwrap();
+    assert_eq!(rem, b"");
+    assert_eq!(&parsed, tree);
+    match (tree, spans) {
+        (TokenTree::Literal(_), SpanTree::Literal(_)) => {}
+        (TokenTree::List(list), SpanTree::List { elements, .. }) => {
+            assert_eq!(list.elements.len(), elements.len());
+            for (tree, spans) in list.elements.iter().zip(elements) {
+                check_spans(input, tree, spans);
+            }
+        }
+        (
+            TokenTree::Record(record),
+            SpanTree::Record {
+                label, elements, ..
+            },
+        ) => {
+            check_spans(input, &record.label, label);
+            assert_eq!(record.elements.len(), elements.len());
+            for (tree, spans) in record.elements.iter().zip(elements) {
+                check_spans(input, tree, spans);
+            }
+        }
+        (TokenTree::Set(set), SpanTree::Set { entries, .. }) => {
+            assert_eq!(set.len(), entries.len());
+            for (tree, spans) in set.into_iter().zip(entries) {
+                check_spans(input, tree, spans);
+            }
+        }
+        (TokenTree::Dictionary(dict), SpanTree::Dictionary { entries, .. }) => {
+            assert_eq!(dict.len(), entries.len());
+            for ((key, value), (key_spans, value_spans)) in dict.into_iter().zip(entries) {
+                check_spans(input, key, key_spans);
+                check_spans(input, value, value_spans);
+            }
+        }
+        _ => panic!("{tree:?} doesn't match {spans:?}"),
+    }
+}
+
+#[test]
+fn spans_each_node() {
+    assert_eq!(
+        spans(b"[1+<3'foo2\"hi>{1'at}]"),
+        SpanTree::List {
+            span: 0..21,
+            elements: vec![
+                SpanTree::Literal(1..3),
+                SpanTree::Record {
+                    span: 3..14,
+                    label: Box::new(SpanTree::Literal(4..9)),
+                    elements: vec![SpanTree::Literal(9..13)],
+                },
+                SpanTree::Dictionary {
+                    span: 14..20,
+                    entries: vec![(SpanTree::Literal(15..18), SpanTree::Literal(18..19))],
+                },
+            ],
+        }
+    );
+}
+
+#[test]
+fn keeps_non_canonical_bytes() {
+    let input = b"#2+001+{1'b0-1'a1+}$";
+    let SpanTree::Set { entries, .. } = spans(input) else {
+        panic!()
+    };
+    let entries: Vec<_> = entries.iter().map(|spans| &input[spans.span()]).collect();
+    // in the order of the set, sorted by their canonical encodings
+    assert_eq!(entries, [&b"001+"[..], b"2+", b"{1'b0-1'a1+}"]);
+}
+
+#[test]
+fn spans_only_the_value() {
+    let (rem, _, spans) =
+        TokenTree::<&[u8]>::parse_spanned::<E<'_>>(b"[t]f", &ParseLimits::default()).unwrap();
+    assert_eq!(rem, b"f");
+    assert_eq!(spans.span(), 0..3);
+}
+
+#[test]
+fn respects_limits() {
+    let limits = ParseLimits {
+        max_depth: 1,
+        ..ParseLimits::default()
+    };
+    assert!(matches!(
+        TokenTree::<&[u8]>::parse_spanned::<E<'_>>(b"[[]]", &limits),
+        Err(LimitedParseError::Limit(LimitExceeded {
+            limit: Limit::Depth,
+            offset: 1
+        }))
+    ));
+}
+
+proptest! {
+    #[test]
+    fn spans_parse_to_their_nodes(tree in TokenTree::arbitrary_with(MaxDepth(2))) {
+        let input = tree.to_bytes();
+        let (_, parsed, spans) =
+            TokenTree::<&[u8]>::parse_spanned::<E<'_>>(&input, &ParseLimits::UNLIMITED).unwrap();
+        check_spans(&input, &parsed, &spans);
+    }
+}
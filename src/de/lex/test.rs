@@ -54,7 +54,7 @@ proptest! {
         let res = TokenTree::<&[u8]>::parse::<E<'_>>(&bytes);
         prop_assert!(res.is_ok(), "encoded input: `{}`, error: {}", String::from_utf8_lossy(&bytes), res.unwrap_err());
         let (rem, res) = res.unwrap();
-        prop_assert_eq!(rem, [].as_slice());
+        prop_assert_eq!(rem, b"".as_slice());
         prop_assert_eq!(&res, &tree);
     }
 
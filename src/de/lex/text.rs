@@ -0,0 +1,685 @@
+use std::{
+    fmt::{self, Write},
+    str::FromStr,
+};
+
+use borrow_or_share::{BorrowOrShare, Bos};
+
+use crate::de::lex::{Dictionary, Int, List, Literal, ParseLimits, Record, Set, TokenTree};
+
+#[cfg(test)]
+mod test;
+
+/// Values wider than this are broken over several lines by the alternate (`{:#}`) format
+const WIDTH: usize = 80;
+const INDENT: usize = 2;
+
+/// Prints the value in the [text syntax](TokenTree::parse_text) on a single line, or, with the
+/// alternate flag (`{:#}`), indented over several lines where it doesn't fit in 80 columns.
+///
+/// Strings and symbols that aren't valid UTF-8 are printed lossily.
+///
+/// ```
+/// # use syrup::de::TokenTree;
+/// let tree: TokenTree<Vec<u8>> = r#"<op:deliver 3 ["hi" #t] {foo: 1}>"#.parse().unwrap();
+/// assert_eq!(&*tree.to_bytes(), b"<10'op:deliver3+[2\"hit]{3'foo1+}>");
+/// assert_eq!(tree.to_string(), r#"<op:deliver 3 ["hi" #t] {foo: 1}>"#);
+/// ```
+impl<Data> fmt::Display for TokenTree<Data>
+where
+    Data: Bos<[u8]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write_pretty(f, self, 0, 0)
+        } else {
+            write_tree(f, self)
+        }
+    }
+}
+
+impl<Data> fmt::Display for Literal<Data>
+where
+    Data: Bos<[u8]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Literal::Bool(b) => f.write_str(if *b { "#t" } else { "#f" }),
+            Literal::F32(fl) if fl.is_finite() => write!(f, "{fl:?}f"),
+            Literal::F32(fl) => write!(f, "#xf\"{:08x}\"", fl.to_bits()),
+            Literal::F64(fl) if fl.is_finite() => write!(f, "{fl:?}"),
+            Literal::F64(fl) => write!(f, "#xd\"{:016x}\"", fl.to_bits()),
+            Literal::Int(int) => int.fmt(f),
+            Literal::Bytes(bytes) => {
+                f.write_str("#\"")?;
+                for &b in bytes.borrow_or_share() {
+                    match b {
+                        b'"' => f.write_str("\\\"")?,
+                        b'\\' => f.write_str("\\\\")?,
+                        b'\n' => f.write_str("\\n")?,
+                        b'\r' => f.write_str("\\r")?,
+                        b'\t' => f.write_str("\\t")?,
+                        b' '..=b'~' => f.write_char(char::from(b))?,
+                        _ => write!(f, "\\x{b:02x}")?,
+                    }
+                }
+                f.write_char('"')
+            }
+            Literal::String(s) => {
+                write_quoted(f, &String::from_utf8_lossy(s.borrow_or_share()), '"')
+            }
+            Literal::Symbol(s) => {
+                let s = String::from_utf8_lossy(s.borrow_or_share());
+                if is_plain_symbol(&s) {
+                    f.write_str(&s)
+                } else {
+                    write_quoted(f, &s, '\'')
+                }
+            }
+        }
+    }
+}
+
+fn write_quoted(w: &mut dyn Write, s: &str, quote: char) -> fmt::Result {
+    w.write_char(quote)?;
+    for c in s.chars() {
+        match c {
+            '\\' => w.write_str("\\\\")?,
+            '\n' => w.write_str("\\n")?,
+            '\r' => w.write_str("\\r")?,
+            '\t' => w.write_str("\\t")?,
+            c if c == quote => {
+                w.write_char('\\')?;
+                w.write_char(c)?;
+            }
+            c if c.is_control() => write!(w, "\\u{:04x}", u32::from(c))?,
+            c => w.write_char(c)?,
+        }
+    }
+    w.write_char(quote)
+}
+
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace()
+        || matches!(
+            c,
+            '<' | '>' | '[' | ']' | '{' | '}' | '"' | '\'' | ';' | ',' | '#'
+        )
+}
+
+/// Whether `s` would be read back as a symbol without quotes
+fn is_plain_symbol(s: &str) -> bool {
+    let unsigned = s.strip_prefix(['+', '-']).unwrap_or(s);
+    !s.is_empty()
+        && !s.ends_with(':')
+        && !unsigned.starts_with(|c: char| c.is_ascii_digit())
+        && !s.chars().any(|c| is_delimiter(c) || c.is_control())
+}
+
+fn write_tree<Data: Bos<[u8]>>(w: &mut dyn Write, tree: &TokenTree<Data>) -> fmt::Result {
+    fn write_all<'t, Data: Bos<[u8]> + 't>(
+        w: &mut dyn Write,
+        elements: impl IntoIterator<Item = &'t TokenTree<Data>>,
+        mut sep: &str,
+    ) -> fmt::Result {
+        for element in elements {
+            w.write_str(sep)?;
+            write_tree(w, element)?;
+            sep = " ";
+        }
+        Ok(())
+    }
+    match tree {
+        TokenTree::Literal(literal) => write!(w, "{literal}"),
+        TokenTree::List(list) => {
+            w.write_char('[')?;
+            write_all(w, &list.elements, "")?;
+            w.write_char(']')
+        }
+        TokenTree::Record(record) => {
+            w.write_char('<')?;
+            write_tree(w, &record.label)?;
+            write_all(w, &record.elements, " ")?;
+            w.write_char('>')
+        }
+        TokenTree::Set(set) => {
+            w.write_str("#{")?;
+            write_all(w, set, "")?;
+            w.write_char('}')
+        }
+        TokenTree::Dictionary(dict) => {
+            w.write_char('{')?;
+            for (i, (key, value)) in dict.into_iter().enumerate() {
+                if i > 0 {
+                    w.write_str(", ")?;
+                }
+                write_tree(w, key)?;
+                w.write_str(": ")?;
+                write_tree(w, value)?;
+            }
+            w.write_char('}')
+        }
+    }
+}
+
+/// Counts down the columns left on a line, failing once they run out
+struct Fits(usize);
+
+impl Write for Fits {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 = self.0.checked_sub(s.chars().count()).ok_or(fmt::Error)?;
+        Ok(())
+    }
+}
+
+/// The width of `tree` on a single line, if it fits on one starting at `column`
+fn single_line_width<Data: Bos<[u8]>>(tree: &TokenTree<Data>, column: usize) -> Option<usize> {
+    let available = WIDTH.saturating_sub(column);
+    let mut fits = Fits(available);
+    write_tree(&mut fits, tree).ok()?;
+    Some(available - fits.0)
+}
+
+fn newline(w: &mut dyn Write, indent: usize) -> fmt::Result {
+    write!(w, "\n{:indent$}", "")
+}
+
+/// Write `tree`, which starts at `column` on a line indented by `indent`, breaking it over lines
+/// if it's too wide
+fn write_pretty<Data: Bos<[u8]>>(
+    w: &mut dyn Write,
+    tree: &TokenTree<Data>,
+    indent: usize,
+    column: usize,
+) -> fmt::Result {
+    fn write_lines<'t, Data: Bos<[u8]> + 't>(
+        w: &mut dyn Write,
+        elements: impl IntoIterator<Item = &'t TokenTree<Data>>,
+        indent: usize,
+    ) -> fmt::Result {
+        let inner = indent + INDENT;
+        for element in elements {
+            newline(w, inner)?;
+            write_pretty(w, element, inner, inner)?;
+        }
+        newline(w, indent)
+    }
+    if single_line_width(tree, column).is_some() {
+        return write_tree(w, tree);
+    }
+    match tree {
+        TokenTree::Literal(literal) => write!(w, "{literal}"),
+        TokenTree::List(list) => {
+            w.write_char('[')?;
+            write_lines(w, &list.elements, indent)?;
+            w.write_char(']')
+        }
+        TokenTree::Record(record) => {
+            w.write_char('<')?;
+            write_pretty(w, &record.label, indent, column + 1)?;
+            write_lines(w, &record.elements, indent)?;
+            w.write_char('>')
+        }
+        TokenTree::Set(set) => {
+            w.write_str("#{")?;
+            write_lines(w, set, indent)?;
+            w.write_char('}')
+        }
+        TokenTree::Dictionary(dict) => {
+            let inner = indent + INDENT;
+            w.write_char('{')?;
+            for (key, value) in dict {
+                newline(w, inner)?;
+                write_pretty(w, key, inner, inner)?;
+                w.write_str(": ")?;
+                let key_width = single_line_width(key, inner).unwrap_or_default();
+                write_pretty(w, value, inner, inner + key_width + 2)?;
+                w.write_char(',')?;
+            }
+            newline(w, indent)?;
+            w.write_char('}')
+        }
+    }
+}
+
+/// What went wrong in a [`TextParseError`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextErrorKind {
+    UnexpectedEnd,
+    Unexpected(char),
+    /// A token starting like a number that isn't one
+    InvalidNumber,
+    InvalidEscape,
+    /// A non-ASCII character in a byte string
+    NotAscii,
+    /// A dictionary key not followed by `:`
+    MissingColon,
+    /// Nested deeper than [`ParseLimits::default`] allows
+    TooDeep,
+    /// More input after the value
+    TrailingInput,
+}
+
+impl fmt::Display for TextErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TextErrorKind::UnexpectedEnd => f.write_str("unexpected end of input"),
+            TextErrorKind::Unexpected(c) => write!(f, "unexpected {c:?}"),
+            TextErrorKind::InvalidNumber => f.write_str("invalid number"),
+            TextErrorKind::InvalidEscape => f.write_str("invalid escape sequence"),
+            TextErrorKind::NotAscii => f.write_str("non-ascii character in byte string"),
+            TextErrorKind::MissingColon => f.write_str("expected ':' after dictionary key"),
+            TextErrorKind::TooDeep => f.write_str("values nested too deeply"),
+            TextErrorKind::TrailingInput => f.write_str("unexpected input after the value"),
+        }
+    }
+}
+
+/// An error from parsing the [text syntax](TokenTree::parse_text)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+#[error("{kind} at byte {offset}")]
+pub struct TextParseError {
+    pub kind: TextErrorKind,
+    /// Offset in bytes from the start of the text
+    pub offset: usize,
+}
+
+impl TokenTree<Vec<u8>> {
+    /// Parse a single value written in a human-readable text syntax, modelled on the
+    /// [Preserves](https://preserves.dev/preserves-text.html) text syntax. It may be surrounded by
+    /// whitespace and comments. Also available through [`FromStr`], and printed by
+    /// [`Display`](fmt::Display).
+    ///
+    /// | value      | text                                     |
+    /// |------------|------------------------------------------|
+    /// | bool       | `#t`, `#f`                               |
+    /// | f32        | `1.5f`, or `#xf"7fc00000"` if not finite |
+    /// | f64        | `1.5`, `2e-7`, or `#xd"..."`             |
+    /// | int        | `42`, `-7`                               |
+    /// | bytes      | `#"ab\x00"`, or `#x"616200"`             |
+    /// | string     | `"hi\n"`                                 |
+    /// | symbol     | `foo`, `op:deliver`, or `'needs quotes'` |
+    /// | list       | `[1 2 3]`                                |
+    /// | record     | `<label field ...>`                      |
+    /// | set        | `#{1 2 3}`                               |
+    /// | dictionary | `{key: value, key: value}`               |
+    ///
+    /// Commas are treated as whitespace, and `;` starts a comment running to the end of the line.
+    /// The `:` after a dictionary key must be followed by whitespace or a delimiter if the key is a
+    /// bare symbol, since `a:b` is a single symbol.
+    ///
+    /// ```
+    /// # use syrup::de::{TextErrorKind, TokenTree};
+    /// let tree = TokenTree::parse_text("#{1 2 'three'} ; a set").unwrap();
+    /// assert_eq!(&*tree.to_bytes(), b"#1+2+5'three$");
+    /// let err = TokenTree::parse_text("[1 2").unwrap_err();
+    /// assert_eq!((err.kind, err.offset), (TextErrorKind::UnexpectedEnd, 4));
+    /// ```
+    pub fn parse_text(text: &str) -> Result<Self, TextParseError> {
+        let mut parser = TextParser {
+            text,
+            pos: 0,
+            depth: 0,
+            max_depth: ParseLimits::default().max_depth,
+        };
+        let tree = parser.value()?;
+        parser.skip_whitespace();
+        if parser.pos < text.len() {
+            return Err(parser.error(TextErrorKind::TrailingInput));
+        }
+        Ok(tree)
+    }
+}
+
+impl FromStr for TokenTree<Vec<u8>> {
+    type Err = TextParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_text(s)
+    }
+}
+
+struct TextParser<'t> {
+    text: &'t str,
+    pos: usize,
+    depth: usize,
+    max_depth: usize,
+}
+
+type TextResult<T> = Result<T, TextParseError>;
+
+impl<'t> TextParser<'t> {
+    const fn error(&self, kind: TextErrorKind) -> TextParseError {
+        TextParseError {
+            kind,
+            offset: self.pos,
+        }
+    }
+
+    fn rest(&self) -> &'t str {
+        &self.text[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn next(&mut self) -> TextResult<char> {
+        let c = self
+            .peek()
+            .ok_or_else(|| self.error(TextErrorKind::UnexpectedEnd))?;
+        self.pos += c.len_utf8();
+        Ok(c)
+    }
+
+    fn expect(&mut self, expected: char) -> TextResult<()> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.pos += c.len_utf8();
+                Ok(())
+            }
+            Some(c) => Err(self.error(TextErrorKind::Unexpected(c))),
+            None => Err(self.error(TextErrorKind::UnexpectedEnd)),
+        }
+    }
+
+    /// Skip whitespace, commas, and comments
+    fn skip_whitespace(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+            self.pos += rest.len() - trimmed.len();
+            if !trimmed.starts_with(';') {
+                return;
+            }
+            self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+        }
+    }
+
+    fn value(&mut self) -> TextResult<TokenTree<Vec<u8>>> {
+        self.skip_whitespace();
+        let start = self.pos;
+        match self.peek() {
+            None => Err(self.error(TextErrorKind::UnexpectedEnd)),
+            Some('[') => self
+                .nested(|p| p.elements(']'))
+                .map(|elements| TokenTree::List(List::new(elements))),
+            Some('<') => self.nested(|p| {
+                let label = p.value()?;
+                let elements = p.elements('>')?;
+                Ok(TokenTree::Record(Box::new(Record::new(label, elements))))
+            }),
+            Some('{') => self.nested(TextParser::entries),
+            Some('"') => {
+                self.pos += 1;
+                Ok(TokenTree::Literal(Literal::String(self.quoted('"')?)))
+            }
+            Some('\'') => {
+                self.pos += 1;
+                Ok(TokenTree::Literal(Literal::Symbol(self.quoted('\'')?)))
+            }
+            Some('#') => {
+                self.pos += 1;
+                self.hash(start)
+            }
+            Some(_) => self.bare().map(TokenTree::Literal),
+        }
+    }
+
+    /// Parse a collection, bounding how deeply they nest
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> TextResult<T>) -> TextResult<T> {
+        if self.depth >= self.max_depth {
+            return Err(self.error(TextErrorKind::TooDeep));
+        }
+        self.pos += 1;
+        self.depth += 1;
+        let res = parse(self);
+        self.depth -= 1;
+        res
+    }
+
+    fn elements(&mut self, close: char) -> TextResult<Vec<TokenTree<Vec<u8>>>> {
+        let mut res = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(close) {
+                self.pos += 1;
+                return Ok(res);
+            }
+            res.push(self.value()?);
+        }
+    }
+
+    fn entries(&mut self) -> TextResult<TokenTree<Vec<u8>>> {
+        let mut entries = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some('}') {
+                self.pos += 1;
+                return Ok(TokenTree::Dictionary(Dictionary::new(entries)));
+            }
+            let key = self.value()?;
+            self.skip_whitespace();
+            match self.peek() {
+                Some(':') => self.pos += 1,
+                Some(_) => return Err(self.error(TextErrorKind::MissingColon)),
+                None => return Err(self.error(TextErrorKind::UnexpectedEnd)),
+            }
+            entries.push((key, self.value()?));
+        }
+    }
+
+    /// Parse whatever follows a `#`, which started at `start`
+    fn hash(&mut self, start: usize) -> TextResult<TokenTree<Vec<u8>>> {
+        let literal = match self.next()? {
+            c @ ('t' | 'f') => {
+                if self.peek().is_some_and(|c| !is_delimiter(c) && c != ':') {
+                    self.pos = start;
+                    return Err(self.error(TextErrorKind::Unexpected('#')));
+                }
+                Literal::Bool(c == 't')
+            }
+            '{' => {
+                self.pos = start;
+                return self.nested(|p| {
+                    p.pos += 1;
+                    p.elements('}')
+                        .map(|entries| TokenTree::Set(Set::new(entries)))
+                });
+            }
+            '"' => Literal::Bytes(self.byte_string()?),
+            'x' => {
+                let kind = self.next()?;
+                match kind {
+                    '"' => {}
+                    'f' | 'd' => self.expect('"')?,
+                    c => {
+                        self.pos -= c.len_utf8();
+                        return Err(self.error(TextErrorKind::Unexpected(c)));
+                    }
+                }
+                let bytes = self.hex()?;
+                match (
+                    kind,
+                    <[u8; 4]>::try_from(&*bytes),
+                    <[u8; 8]>::try_from(&*bytes),
+                ) {
+                    ('"', ..) => Literal::Bytes(bytes),
+                    ('f', Ok(b), _) => Literal::F32(f32::from_be_bytes(b)),
+                    ('d', _, Ok(b)) => Literal::F64(f64::from_be_bytes(b)),
+                    _ => {
+                        self.pos = start;
+                        return Err(self.error(TextErrorKind::InvalidNumber));
+                    }
+                }
+            }
+            c => {
+                self.pos -= c.len_utf8();
+                return Err(self.error(TextErrorKind::Unexpected(c)));
+            }
+        };
+        Ok(TokenTree::Literal(literal))
+    }
+
+    /// The contents of a string or quoted symbol, after the opening `quote`
+    fn quoted(&mut self, quote: char) -> TextResult<Vec<u8>> {
+        let mut res = String::new();
+        loop {
+            match self.next()? {
+                c if c == quote => return Ok(res.into_bytes()),
+                '\\' => {
+                    let escaped = self.escape()?;
+                    res.push(escaped);
+                }
+                c => res.push(c),
+            }
+        }
+    }
+
+    /// An escape sequence, after the `\`
+    fn escape(&mut self) -> TextResult<char> {
+        let start = self.pos - 1;
+        let invalid = |p: &mut Self| {
+            p.pos = start;
+            Err(p.error(TextErrorKind::InvalidEscape))
+        };
+        Ok(match self.next()? {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            c @ ('\\' | '"' | '\'' | '/') => c,
+            'u' => {
+                let Some(high) = self.hex_digits(4) else {
+                    return invalid(self);
+                };
+                let code = if (0xd800..0xdc00).contains(&high) {
+                    if !self.rest().starts_with("\\u") {
+                        return invalid(self);
+                    }
+                    self.pos += 2;
+                    match self.hex_digits(4) {
+                        Some(low @ 0xdc00..0xe000) => {
+                            0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+                        }
+                        _ => return invalid(self),
+                    }
+                } else {
+                    high
+                };
+                match char::from_u32(code) {
+                    Some(c) => c,
+                    None => return invalid(self),
+                }
+            }
+            _ => return invalid(self),
+        })
+    }
+
+    fn hex_digits(&mut self, len: usize) -> Option<u32> {
+        let digits = self.rest().get(..len)?;
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        self.pos += len;
+        u32::from_str_radix(digits, 16).ok()
+    }
+
+    /// The contents of a `#"..."` byte string, after the opening quote
+    fn byte_string(&mut self) -> TextResult<Vec<u8>> {
+        let mut res = Vec::new();
+        loop {
+            let start = self.pos;
+            let kind = match self.next()? {
+                '"' => return Ok(res),
+                '\\' if self.peek() == Some('x') => {
+                    self.pos += 1;
+                    if let Some(b) = self.hex_digits(2) {
+                        res.push(b as u8);
+                        continue;
+                    }
+                    TextErrorKind::InvalidEscape
+                }
+                '\\' => match self.escape()? {
+                    c if c.is_ascii() => {
+                        res.push(c as u8);
+                        continue;
+                    }
+                    _ => TextErrorKind::InvalidEscape,
+                },
+                c if c.is_ascii() => {
+                    res.push(c as u8);
+                    continue;
+                }
+                _ => TextErrorKind::NotAscii,
+            };
+            self.pos = start;
+            return Err(self.error(kind));
+        }
+    }
+
+    /// The contents of a `#x"..."` hex string; whitespace between digits is ignored
+    fn hex(&mut self) -> TextResult<Vec<u8>> {
+        let mut res = Vec::new();
+        loop {
+            let rest = self.rest();
+            self.pos += rest.len() - rest.trim_start().len();
+            if self.peek() == Some('"') {
+                self.pos += 1;
+                return Ok(res);
+            }
+            match self.hex_digits(2) {
+                Some(b) => res.push(b as u8),
+                None => {
+                    return Err(match self.peek() {
+                        Some(c) => self.error(TextErrorKind::Unexpected(c)),
+                        None => self.error(TextErrorKind::UnexpectedEnd),
+                    });
+                }
+            }
+        }
+    }
+
+    /// A number or an unquoted symbol
+    fn bare(&mut self) -> TextResult<Literal<Vec<u8>>> {
+        let start = self.pos;
+        let rest = self.rest();
+        let mut len = 0;
+        let mut chars = rest.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            let ends_key = c == ':' && chars.peek().is_none_or(|&(_, c)| is_delimiter(c));
+            if is_delimiter(c) || ends_key {
+                break;
+            }
+            len = i + c.len_utf8();
+        }
+        let token = &rest[..len];
+        if token.is_empty() {
+            let c = self.peek().unwrap_or_default();
+            return Err(self.error(TextErrorKind::Unexpected(c)));
+        }
+        self.pos += len;
+        let unsigned = token.strip_prefix(['+', '-']).unwrap_or(token);
+        if !unsigned.starts_with(|c: char| c.is_ascii_digit()) {
+            return Ok(Literal::Symbol(token.as_bytes().to_vec()));
+        }
+        if unsigned.bytes().all(|b| b.is_ascii_digit()) {
+            #[expect(unsafe_code, reason = "we just checked that they're all ascii digits")]
+            let int = unsafe { Int::new(!token.starts_with('-'), unsigned.as_bytes().to_vec()) };
+            return Ok(Literal::Int(int));
+        }
+        let float = match token.strip_suffix('f') {
+            Some(token) => token.parse().ok().map(Literal::F32),
+            None => token.parse().ok().map(Literal::F64),
+        };
+        match float {
+            // a float needs a fraction or an exponent, as with `{:?}`
+            Some(float) if unsigned.contains(['.', 'e', 'E']) => Ok(float),
+            _ => Err(TextParseError {
+                kind: TextErrorKind::InvalidNumber,
+                offset: start,
+            }),
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use proptest::prelude::*;
+
+use crate::de::lex::{TextErrorKind, TokenTree, test::MaxDepth};
+
+fn parse(text: &str) -> TokenTree<Vec<u8>> {
+    text.parse()
+        .unwrap_or_else(|e| panic!("failed to parse {text:?}: {e}"))
+}
+
+/// Parse `text` and check that it's encoded as `encoded` and printed back as `text`
+fn round_trips(text: &str, encoded: &[u8]) {
+    let tree = parse(text);
+    assert_eq!(
+        &*tree.to_bytes(),
+        encoded,
+        "{}",
+        tree.to_bytes().escape_ascii()
+    );
+    assert_eq!(tree.to_string(), text);
+}
+
+fn error(text: &str) -> (TextErrorKind, usize) {
+    let err = TokenTree::parse_text(text).unwrap_err();
+    (err.kind, err.offset)
+}
+
+#[test]
+fn prints_literals() {
+    round_trips("#t", b"t");
+    round_trips("#f", b"f");
+    round_trips("-12", b"12-");
+    round_trips("1.5", b"D\x3f\xf8\0\0\0\0\0\0");
+    round_trips("1.5f", b"F\x3f\xc0\0\0");
+    round_trips("1e100", b"D\x54\xb2\x49\xad\x25\x94\xc3\x7d");
+    round_trips("#xd\"7ff0000000000000\"", b"D\x7f\xf0\0\0\0\0\0\0");
+    round_trips("#xf\"7fc00000\"", b"F\x7f\xc0\0\0");
+    round_trips("\"hi\"", b"2\"hi");
+    round_trips("#\"hi\"", b"2:hi");
+    round_trips("hi", b"2'hi");
+}
+
+#[test]
+fn prints_collections() {
+    round_trips(
+        "<op:deliver 3 [\"hi\" #t] {foo: 1}>",
+        b"<10'op:deliver3+[2\"hit]{3'foo1+}>",
+    );
+    round_trips("[]", b"[]");
+    round_trips("<a>", b"<1'a>");
+    round_trips("#{1 2}", b"#1+2+$");
+    // entries are sorted by the encodings of their keys, and printed in that order
+    round_trips("{\"b\": [c], a: 1}", b"{1\"b[1'c]1'a1+}");
+    assert_eq!(
+        parse("{a: 1, \"b\": [c]}").to_string(),
+        "{\"b\": [c], a: 1}"
+    );
+}
+
+#[test]
+fn quotes_symbols_that_need_it() {
+    for symbol in ["", "1a", "-1", "a b", "a:", "[", "a,b", "it's", "#t"] {
+        let tree = TokenTree::Literal(crate::de::Literal::Symbol(symbol.as_bytes().to_vec()));
+        let text = tree.to_string();
+        assert!(text.starts_with('\''), "{text}");
+        assert_eq!(parse(&text), tree, "{text}");
+    }
+    round_trips("a:b", b"3'a:b");
+    round_trips("-", b"1'-");
+    round_trips("'it\\'s'", b"4'it's");
+}
+
+#[test]
+fn escapes_strings_and_bytes() {
+    round_trips(r#""a\"b\\c\n\u0000é""#, b"9\"a\"b\\c\n\0\xc3\xa9");
+    round_trips(r#"#"a\"\x00\xff""#, b"4:a\"\0\xff");
+    assert_eq!(&*parse(r#""😀""#).to_bytes(), "4\"😀".as_bytes());
+    assert_eq!(&*parse("#x\"61 62\"").to_bytes(), b"2:ab");
+}
+
+#[test]
+fn accepts_comments_and_commas() {
+    let tree = parse(
+        "; a list
+        [1, 2, ; two
+         3]
+        ",
+    );
+    assert_eq!(&*tree.to_bytes(), b"[1+2+3+]");
+}
+
+#[test]
+fn pretty_prints_large_values() {
+    let tree = parse(
+        "<message {id: 1 body: \"0123456789012345678901234567890123456789\"} \
+         [#t #f] [\"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\"]>",
+    );
+    let pretty = format!("{tree:#}");
+    assert_eq!(
+        pretty,
+        r#"<message
+  {id: 1, body: "0123456789012345678901234567890123456789"}
+  [#t #f]
+  [
+    "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"
+  ]
+>"#
+    );
+    assert_eq!(parse(&pretty), tree);
+    assert_eq!(format!("{:#}", parse("[1 2]")), "[1 2]");
+}
+
+#[test]
+fn reports_errors() {
+    assert_eq!(error(""), (TextErrorKind::UnexpectedEnd, 0));
+    assert_eq!(error("[1 2"), (TextErrorKind::UnexpectedEnd, 4));
+    assert_eq!(error("[1 ]]"), (TextErrorKind::TrailingInput, 4));
+    assert_eq!(error("[1 }"), (TextErrorKind::Unexpected('}'), 3));
+    assert_eq!(error("{a 1}"), (TextErrorKind::MissingColon, 3));
+    // `a:1` is a symbol
+    assert_eq!(error("{a:1}"), (TextErrorKind::MissingColon, 4));
+    assert_eq!(error("[12x]"), (TextErrorKind::InvalidNumber, 1));
+    assert_eq!(error("#true"), (TextErrorKind::Unexpected('#'), 0));
+    assert_eq!(error("\"a\\q\""), (TextErrorKind::InvalidEscape, 2));
+    assert_eq!(error("#\"é\""), (TextErrorKind::NotAscii, 2));
+    assert_eq!(error("#xf\"00\""), (TextErrorKind::InvalidNumber, 0));
+    assert_eq!(error(&"[".repeat(1000)), (TextErrorKind::TooDeep, 128));
+}
+
+proptest! {
+    #[test]
+    fn round_trips_arbitrary_trees(tree in TokenTree::arbitrary_with(MaxDepth(2))) {
+        // integers are printed canonically, so compare encodings
+        let parsed = parse(&tree.to_string());
+        prop_assert_eq!(parsed.to_bytes(), tree.to_bytes());
+        let parsed = parse(&format!("{tree:#}"));
+        prop_assert_eq!(parsed.to_bytes(), tree.to_bytes());
+    }
+}
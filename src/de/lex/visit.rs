@@ -0,0 +1,441 @@
+use borrow_or_share::{BorrowOrShare, Bos};
+
+use crate::de::lex::{Dictionary, Int, List, Literal, Record, Set, TokenTree};
+
+#[cfg(test)]
+mod test;
+
+/// Something that looks at each node of a [`TokenTree`], through [`walk`] or
+/// [`TokenTree::walk`].
+///
+/// Every method has a default: the ones for collections visit their children through the
+/// matching `walk_*` function, and the ones for literals do nothing. Override just the methods
+/// for the nodes of interest, calling the `walk_*` function from an override to keep going into a
+/// collection's children.
+///
+/// ```
+/// # use syrup::{TokenTree, de::{Record, Visitor, walk_record}};
+/// /// Finds the labels of all the records
+/// #[derive(Default)]
+/// struct Labels<'t>(Vec<&'t TokenTree<Vec<u8>>>);
+///
+/// impl<'t> Visitor<'t, Vec<u8>> for Labels<'t> {
+///     fn visit_record(&mut self, record: &'t Record<Vec<u8>>) {
+///         self.0.push(&record.label);
+///         walk_record(self, record);
+///     }
+/// }
+///
+/// let tree: TokenTree<Vec<u8>> = "[<a 1 <b>> {k: <c>}]".parse().unwrap();
+/// let mut labels = Labels::default();
+/// tree.walk(&mut labels);
+/// let labels: Vec<_> = labels.0.into_iter().map(ToString::to_string).collect();
+/// assert_eq!(labels, ["a", "b", "c"]);
+/// ```
+pub trait Visitor<'t, Data: Bos<[u8]> + 't> {
+    fn visit_tree(&mut self, tree: &'t TokenTree<Data>) {
+        walk(self, tree);
+    }
+
+    fn visit_literal(&mut self, literal: &'t Literal<Data>) {
+        walk_literal(self, literal);
+    }
+
+    fn visit_bool(&mut self, _value: bool) {}
+
+    fn visit_f32(&mut self, _value: f32) {}
+
+    fn visit_f64(&mut self, _value: f64) {}
+
+    fn visit_int(&mut self, _int: &'t Int<Data>) {}
+
+    fn visit_bytes(&mut self, _bytes: &'t [u8]) {}
+
+    /// Visit the contents of a string, which may not be valid UTF-8
+    fn visit_string(&mut self, _string: &'t [u8]) {}
+
+    /// Visit the contents of a symbol, which may not be valid UTF-8
+    fn visit_symbol(&mut self, _symbol: &'t [u8]) {}
+
+    fn visit_list(&mut self, list: &'t List<Data>) {
+        walk_list(self, list);
+    }
+
+    fn visit_record(&mut self, record: &'t Record<Data>) {
+        walk_record(self, record);
+    }
+
+    fn visit_set(&mut self, set: &'t Set<Data>) {
+        walk_set(self, set);
+    }
+
+    fn visit_dictionary(&mut self, dict: &'t Dictionary<Data>) {
+        walk_dictionary(self, dict);
+    }
+}
+
+/// Visit `tree` with the method for its kind of node.
+pub fn walk<'t, Data, V>(visitor: &mut V, tree: &'t TokenTree<Data>)
+where
+    Data: Bos<[u8]>,
+    V: Visitor<'t, Data> + ?Sized,
+{
+    match tree {
+        TokenTree::Literal(literal) => visitor.visit_literal(literal),
+        TokenTree::List(list) => visitor.visit_list(list),
+        TokenTree::Record(record) => visitor.visit_record(record),
+        TokenTree::Set(set) => visitor.visit_set(set),
+        TokenTree::Dictionary(dict) => visitor.visit_dictionary(dict),
+    }
+}
+
+/// Visit `literal` with the method for its kind of literal.
+pub fn walk_literal<'t, Data, V>(visitor: &mut V, literal: &'t Literal<Data>)
+where
+    Data: Bos<[u8]>,
+    V: Visitor<'t, Data> + ?Sized,
+{
+    match literal {
+        Literal::Bool(b) => visitor.visit_bool(*b),
+        Literal::F32(f) => visitor.visit_f32(*f),
+        Literal::F64(f) => visitor.visit_f64(*f),
+        Literal::Int(int) => visitor.visit_int(int),
+        Literal::Bytes(bytes) => visitor.visit_bytes(bytes.borrow_or_share()),
+        Literal::String(s) => visitor.visit_string(s.borrow_or_share()),
+        Literal::Symbol(s) => visitor.visit_symbol(s.borrow_or_share()),
+    }
+}
+
+/// Visit each element of `list`.
+pub fn walk_list<'t, Data, V>(visitor: &mut V, list: &'t List<Data>)
+where
+    Data: Bos<[u8]>,
+    V: Visitor<'t, Data> + ?Sized,
+{
+    for element in &list.elements {
+        visitor.visit_tree(element);
+    }
+}
+
+/// Visit the label of `record`, then each of its elements.
+pub fn walk_record<'t, Data, V>(visitor: &mut V, record: &'t Record<Data>)
+where
+    Data: Bos<[u8]>,
+    V: Visitor<'t, Data> + ?Sized,
+{
+    visitor.visit_tree(&record.label);
+    for element in &record.elements {
+        visitor.visit_tree(element);
+    }
+}
+
+/// Visit each entry of `set`.
+pub fn walk_set<'t, Data, V>(visitor: &mut V, set: &'t Set<Data>)
+where
+    Data: Bos<[u8]>,
+    V: Visitor<'t, Data> + ?Sized,
+{
+    for entry in set {
+        visitor.visit_tree(entry);
+    }
+}
+
+/// Visit each key of `dict`, followed by its value.
+pub fn walk_dictionary<'t, Data, V>(visitor: &mut V, dict: &'t Dictionary<Data>)
+where
+    Data: Bos<[u8]>,
+    V: Visitor<'t, Data> + ?Sized,
+{
+    for (key, value) in dict {
+        visitor.visit_tree(key);
+        visitor.visit_tree(value);
+    }
+}
+
+/// Like [`Visitor`], but able to change each node in place, through [`walk_mut`] or
+/// [`TokenTree::walk_mut`].
+///
+/// Set entries and dictionary keys can be changed too; afterwards they're sorted again, and if
+/// two of them now encode the same, only the first is kept, as with [`Set::new`] and
+/// [`Dictionary::new`].
+///
+/// ```
+/// # use syrup::{TokenTree, de::VisitorMut};
+/// /// Blanks out every byte string
+/// struct Redact;
+///
+/// impl VisitorMut<Vec<u8>> for Redact {
+///     fn visit_bytes_mut(&mut self, bytes: &mut Vec<u8>) {
+///         bytes.clear();
+///     }
+/// }
+///
+/// let mut tree: TokenTree<Vec<u8>> = r#"<login "ash" #"hunter2">"#.parse().unwrap();
+/// tree.walk_mut(&mut Redact);
+/// assert_eq!(tree.to_string(), r#"<login "ash" #"">"#);
+/// ```
+pub trait VisitorMut<Data: Bos<[u8]>> {
+    fn visit_tree_mut(&mut self, tree: &mut TokenTree<Data>) {
+        walk_mut(self, tree);
+    }
+
+    fn visit_literal_mut(&mut self, literal: &mut Literal<Data>) {
+        walk_literal_mut(self, literal);
+    }
+
+    fn visit_bool_mut(&mut self, _value: &mut bool) {}
+
+    fn visit_f32_mut(&mut self, _value: &mut f32) {}
+
+    fn visit_f64_mut(&mut self, _value: &mut f64) {}
+
+    fn visit_int_mut(&mut self, _int: &mut Int<Data>) {}
+
+    fn visit_bytes_mut(&mut self, _bytes: &mut Data) {}
+
+    fn visit_string_mut(&mut self, _string: &mut Data) {}
+
+    fn visit_symbol_mut(&mut self, _symbol: &mut Data) {}
+
+    fn visit_list_mut(&mut self, list: &mut List<Data>) {
+        walk_list_mut(self, list);
+    }
+
+    fn visit_record_mut(&mut self, record: &mut Record<Data>) {
+        walk_record_mut(self, record);
+    }
+
+    fn visit_set_mut(&mut self, set: &mut Set<Data>) {
+        walk_set_mut(self, set);
+    }
+
+    fn visit_dictionary_mut(&mut self, dict: &mut Dictionary<Data>) {
+        walk_dictionary_mut(self, dict);
+    }
+}
+
+/// Visit `tree` with the method for its kind of node.
+pub fn walk_mut<Data, V>(visitor: &mut V, tree: &mut TokenTree<Data>)
+where
+    Data: Bos<[u8]>,
+    V: VisitorMut<Data> + ?Sized,
+{
+    match tree {
+        TokenTree::Literal(literal) => visitor.visit_literal_mut(literal),
+        TokenTree::List(list) => visitor.visit_list_mut(list),
+        TokenTree::Record(record) => visitor.visit_record_mut(record),
+        TokenTree::Set(set) => visitor.visit_set_mut(set),
+        TokenTree::Dictionary(dict) => visitor.visit_dictionary_mut(dict),
+    }
+}
+
+/// Visit `literal` with the method for its kind of literal.
+pub fn walk_literal_mut<Data, V>(visitor: &mut V, literal: &mut Literal<Data>)
+where
+    Data: Bos<[u8]>,
+    V: VisitorMut<Data> + ?Sized,
+{
+    match literal {
+        Literal::Bool(b) => visitor.visit_bool_mut(b),
+        Literal::F32(f) => visitor.visit_f32_mut(f),
+        Literal::F64(f) => visitor.visit_f64_mut(f),
+        Literal::Int(int) => visitor.visit_int_mut(int),
+        Literal::Bytes(bytes) => visitor.visit_bytes_mut(bytes),
+        Literal::String(s) => visitor.visit_string_mut(s),
+        Literal::Symbol(s) => visitor.visit_symbol_mut(s),
+    }
+}
+
+/// Visit each element of `list`.
+pub fn walk_list_mut<Data, V>(visitor: &mut V, list: &mut List<Data>)
+where
+    Data: Bos<[u8]>,
+    V: VisitorMut<Data> + ?Sized,
+{
+    for element in &mut list.elements {
+        visitor.visit_tree_mut(element);
+    }
+}
+
+/// Visit the label of `record`, then each of its elements.
+pub fn walk_record_mut<Data, V>(visitor: &mut V, record: &mut Record<Data>)
+where
+    Data: Bos<[u8]>,
+    V: VisitorMut<Data> + ?Sized,
+{
+    visitor.visit_tree_mut(&mut record.label);
+    for element in &mut record.elements {
+        visitor.visit_tree_mut(element);
+    }
+}
+
+/// Visit each entry of `set`.
+pub fn walk_set_mut<Data, V>(visitor: &mut V, set: &mut Set<Data>)
+where
+    Data: Bos<[u8]>,
+    V: VisitorMut<Data> + ?Sized,
+{
+    for entry in set.entries_mut() {
+        visitor.visit_tree_mut(entry);
+    }
+    set.sort();
+}
+
+/// Visit each key of `dict`, followed by its value.
+pub fn walk_dictionary_mut<Data, V>(visitor: &mut V, dict: &mut Dictionary<Data>)
+where
+    Data: Bos<[u8]>,
+    V: VisitorMut<Data> + ?Sized,
+{
+    for (key, value) in dict.entries_mut() {
+        visitor.visit_tree_mut(key);
+        visitor.visit_tree_mut(value);
+    }
+    dict.sort();
+}
+
+/// Something that rebuilds a [`TokenTree`] node by node, through [`fold`] or [`TokenTree::fold`].
+///
+/// Each method takes a node by value and returns the tree to put in its place, which needn't be
+/// the same kind of node. By default, literals are kept as they are, and collections are rebuilt
+/// from their folded children through the matching `fold_*` function.
+///
+/// ```
+/// # use syrup::{TokenTree, de::{Fold, Record, fold_record}, symbol::Symbol};
+/// /// Replaces each `<secret ...>` record with the symbol `redacted`
+/// struct Redact;
+///
+/// impl Fold<Vec<u8>> for Redact {
+///     fn fold_record(&mut self, record: Record<Vec<u8>>) -> TokenTree<Vec<u8>> {
+///         if record.label_symbol() == Some(Symbol("secret")) {
+///             return syrup::symbol::encode(b"redacted".to_vec());
+///         }
+///         TokenTree::Record(Box::new(fold_record(self, record)))
+///     }
+/// }
+///
+/// let tree: TokenTree<Vec<u8>> = "{user: ash, key: <secret #x\"00ff\">}".parse().unwrap();
+/// assert_eq!(tree.fold(&mut Redact).to_string(), "{key: redacted, user: ash}");
+/// ```
+pub trait Fold<Data: Bos<[u8]>> {
+    fn fold_tree(&mut self, tree: TokenTree<Data>) -> TokenTree<Data> {
+        fold(self, tree)
+    }
+
+    fn fold_literal(&mut self, literal: Literal<Data>) -> TokenTree<Data> {
+        TokenTree::Literal(literal)
+    }
+
+    fn fold_list(&mut self, list: List<Data>) -> TokenTree<Data> {
+        TokenTree::List(fold_list(self, list))
+    }
+
+    fn fold_record(&mut self, record: Record<Data>) -> TokenTree<Data> {
+        TokenTree::Record(Box::new(fold_record(self, record)))
+    }
+
+    fn fold_set(&mut self, set: Set<Data>) -> TokenTree<Data> {
+        TokenTree::Set(fold_set(self, set))
+    }
+
+    fn fold_dictionary(&mut self, dict: Dictionary<Data>) -> TokenTree<Data> {
+        TokenTree::Dictionary(fold_dictionary(self, dict))
+    }
+}
+
+/// Fold `tree` with the method for its kind of node.
+pub fn fold<Data, F>(folder: &mut F, tree: TokenTree<Data>) -> TokenTree<Data>
+where
+    Data: Bos<[u8]>,
+    F: Fold<Data> + ?Sized,
+{
+    match tree {
+        TokenTree::Literal(literal) => folder.fold_literal(literal),
+        TokenTree::List(list) => folder.fold_list(list),
+        TokenTree::Record(record) => folder.fold_record(*record),
+        TokenTree::Set(set) => folder.fold_set(set),
+        TokenTree::Dictionary(dict) => folder.fold_dictionary(dict),
+    }
+}
+
+/// Fold each element of `list`.
+pub fn fold_list<Data, F>(folder: &mut F, list: List<Data>) -> List<Data>
+where
+    Data: Bos<[u8]>,
+    F: Fold<Data> + ?Sized,
+{
+    List::new(
+        list.elements
+            .into_iter()
+            .map(|element| folder.fold_tree(element))
+            .collect(),
+    )
+}
+
+/// Fold the label of `record`, then each of its elements.
+pub fn fold_record<Data, F>(folder: &mut F, record: Record<Data>) -> Record<Data>
+where
+    Data: Bos<[u8]>,
+    F: Fold<Data> + ?Sized,
+{
+    Record {
+        label: folder.fold_tree(record.label),
+        elements: record
+            .elements
+            .into_iter()
+            .map(|element| folder.fold_tree(element))
+            .collect(),
+    }
+}
+
+/// Fold each entry of `set`.
+pub fn fold_set<Data, F>(folder: &mut F, set: Set<Data>) -> Set<Data>
+where
+    Data: Bos<[u8]>,
+    F: Fold<Data> + ?Sized,
+{
+    set.into_iter()
+        .map(|entry| folder.fold_tree(entry))
+        .collect()
+}
+
+/// Fold each key of `dict`, followed by its value.
+pub fn fold_dictionary<Data, F>(folder: &mut F, dict: Dictionary<Data>) -> Dictionary<Data>
+where
+    Data: Bos<[u8]>,
+    F: Fold<Data> + ?Sized,
+{
+    dict.into_iter()
+        .map(|(key, value)| (folder.fold_tree(key), folder.fold_tree(value)))
+        .collect()
+}
+
+impl<Data> TokenTree<Data> {
+    /// Visit each node of this tree with `visitor`.
+    pub fn walk<'t, V>(&'t self, visitor: &mut V)
+    where
+        Data: Bos<[u8]>,
+        V: Visitor<'t, Data> + ?Sized,
+    {
+        visitor.visit_tree(self);
+    }
+
+    /// Visit each node of this tree with `visitor`, which may change it in place.
+    pub fn walk_mut<V>(&mut self, visitor: &mut V)
+    where
+        Data: Bos<[u8]>,
+        V: VisitorMut<Data> + ?Sized,
+    {
+        visitor.visit_tree_mut(self);
+    }
+
+    /// Rebuild this tree with `folder`.
+    #[must_use]
+    pub fn fold<F>(self, folder: &mut F) -> Self
+    where
+        Data: Bos<[u8]>,
+        F: Fold<Data> + ?Sized,
+    {
+        folder.fold_tree(self)
+    }
+}
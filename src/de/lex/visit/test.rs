@@ -0,0 +1,176 @@
+use proptest::prelude::*;
+
+use crate::{
+    de::lex::{
+        Dictionary, Fold, Int, List, Literal, Record, Set, TokenTree, Visitor, VisitorMut,
+        test::MaxDepth, walk_dictionary, walk_list, walk_record, walk_record_mut, walk_set,
+    },
+    symbol::Symbol,
+};
+
+fn parse(text: &str) -> TokenTree<Vec<u8>> {
+    text.parse()
+        .unwrap_or_else(|e| panic!("failed to parse {text:?}: {e}"))
+}
+
+/// Counts each kind of node, and how deeply lists are nested
+#[derive(Default, Debug, PartialEq, Eq)]
+struct Counts {
+    literals: usize,
+    collections: usize,
+    depth: usize,
+    max_depth: usize,
+}
+
+impl Visitor<'_, Vec<u8>> for Counts {
+    fn visit_bool(&mut self, _value: bool) {
+        self.literals += 1;
+    }
+
+    fn visit_f32(&mut self, _value: f32) {
+        self.literals += 1;
+    }
+
+    fn visit_f64(&mut self, _value: f64) {
+        self.literals += 1;
+    }
+
+    fn visit_int(&mut self, _int: &Int<Vec<u8>>) {
+        self.literals += 1;
+    }
+
+    fn visit_bytes(&mut self, _bytes: &[u8]) {
+        self.literals += 1;
+    }
+
+    fn visit_string(&mut self, _string: &[u8]) {
+        self.literals += 1;
+    }
+
+    fn visit_symbol(&mut self, _symbol: &[u8]) {
+        self.literals += 1;
+    }
+
+    fn visit_list(&mut self, list: &List<Vec<u8>>) {
+        self.collections += 1;
+        self.depth += 1;
+        self.max_depth = self.max_depth.max(self.depth);
+        walk_list(self, list);
+        self.depth -= 1;
+    }
+
+    fn visit_record(&mut self, record: &Record<Vec<u8>>) {
+        self.collections += 1;
+        walk_record(self, record);
+    }
+
+    fn visit_set(&mut self, set: &Set<Vec<u8>>) {
+        self.collections += 1;
+        walk_set(self, set);
+    }
+
+    fn visit_dictionary(&mut self, dict: &Dictionary<Vec<u8>>) {
+        self.collections += 1;
+        walk_dictionary(self, dict);
+    }
+}
+
+/// Count the nodes of `tree` by matching on it
+fn count(tree: &TokenTree<Vec<u8>>) -> (usize, usize) {
+    let children: Vec<&TokenTree<Vec<u8>>> = match tree {
+        TokenTree::Literal(_) => return (1, 0),
+        TokenTree::List(list) => list.elements.iter().collect(),
+        TokenTree::Record(record) => std::iter::once(&record.label)
+            .chain(&record.elements)
+            .collect(),
+        TokenTree::Set(set) => set.into_iter().collect(),
+        TokenTree::Dictionary(dict) => dict.into_iter().flat_map(|(k, v)| [k, v]).collect(),
+    };
+    children
+        .into_iter()
+        .map(count)
+        .fold((0, 1), |(l, c), (cl, cc)| (l + cl, c + cc))
+}
+
+#[test]
+fn visits_every_node() {
+    let mut counts = Counts::default();
+    parse(r#"[1 [#t [2.5 #{"a" #"b"}]] <x {y: 1.5f}>]"#).walk(&mut counts);
+    assert_eq!(
+        counts,
+        Counts {
+            literals: 8,
+            collections: 6,
+            depth: 0,
+            max_depth: 3,
+        }
+    );
+}
+
+/// Adds to the position of each `<desc:export N>`
+struct Renumber(u64);
+
+impl VisitorMut<Vec<u8>> for Renumber {
+    fn visit_record_mut(&mut self, record: &mut Record<Vec<u8>>) {
+        if record.label_symbol() == Some(Symbol("desc:export"))
+            && let [position] = record.elements.as_mut_slice()
+            && let Some(old) = position.as_int::<u64>()
+        {
+            *position = TokenTree::Literal(Literal::Int((old + self.0).into()));
+        }
+        walk_record_mut(self, record);
+    }
+}
+
+#[test]
+fn changes_nodes_in_place() {
+    let mut tree = parse("<op:deliver <desc:export 3> [<desc:export 0>] {<desc:export 1>: x}>");
+    tree.walk_mut(&mut Renumber(10));
+    assert_eq!(
+        tree,
+        parse("<op:deliver <desc:export 13> [<desc:export 10>] {<desc:export 11>: x}>")
+    );
+}
+
+/// Replaces every integer with its negation, and every list with its length
+struct Negate;
+
+impl Fold<Vec<u8>> for Negate {
+    fn fold_literal(&mut self, literal: Literal<Vec<u8>>) -> TokenTree<Vec<u8>> {
+        match literal {
+            Literal::Int(mut int) => {
+                int.positive = !int.positive;
+                TokenTree::Literal(Literal::Int(int))
+            }
+            literal => TokenTree::Literal(literal),
+        }
+    }
+
+    fn fold_list(&mut self, list: List<Vec<u8>>) -> TokenTree<Vec<u8>> {
+        TokenTree::Literal(Literal::Int(list.elements.len().into()))
+    }
+}
+
+#[test]
+fn rebuilds_trees() {
+    let tree = parse("<a 1 [2 3] #{4} {-5: [], x: 6}>").fold(&mut Negate);
+    assert_eq!(tree, parse("<a -1 2 #{-4} {5: 0, x: -6}>"));
+}
+
+struct Identity;
+
+impl Fold<Vec<u8>> for Identity {}
+
+proptest! {
+    #[test]
+    fn visits_as_many_nodes_as_there_are(tree in TokenTree::arbitrary_with(MaxDepth(2))) {
+        let mut counts = Counts::default();
+        tree.walk(&mut counts);
+        prop_assert_eq!((counts.literals, counts.collections), count(&tree));
+    }
+
+    #[test]
+    fn folds_to_the_same_tree(tree in TokenTree::arbitrary_with(MaxDepth(2))) {
+        prop_assert_eq!(tree.clone().fold(&mut Identity), tree);
+    }
+}
@@ -0,0 +1,447 @@
+use borrow_or_share::Bos;
+
+use crate::de::{
+    Decode, DecodeError, DecodeFrom, Limit, LimitExceeded, LimitedParseError, Literal, ParseLimits,
+    PathSegment, SyrupKind, TokenTree,
+};
+
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ReadError {
+    #[error("invalid syrup at byte {offset}: {kind:?}")]
+    Syntax {
+        offset: usize,
+        kind: nom::error::ErrorKind,
+    },
+    #[error("unexpected end of input at byte {offset}")]
+    Incomplete { offset: usize },
+    #[error("unexpected '{}' at byte {offset}", char::from(*.found))]
+    UnexpectedClose { offset: usize, found: u8 },
+    #[error("record without a label at byte {offset}")]
+    MissingLabel { offset: usize },
+    #[error("dictionary key without a value at byte {offset}")]
+    MissingValue { offset: usize },
+    /// A value read whole, as by [`Reader::read_tree`], exceeded the default [`ParseLimits`]
+    #[error(transparent)]
+    Limit(#[from] LimitExceeded),
+    #[error(transparent)]
+    Decode(#[from] DecodeError),
+}
+
+impl ReadError {
+    /// Note that this error occurred inside `segment`.
+    ///
+    /// Only [`ReadError::Decode`] records a path; syntax errors already know their offset.
+    #[must_use]
+    pub fn within(self, segment: PathSegment) -> Self {
+        match self {
+            Self::Decode(e) => Self::Decode(e.within(segment)),
+            e => e,
+        }
+    }
+
+    #[must_use]
+    pub fn at_label(self, label: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        self.within(PathSegment::Label(label.into()))
+    }
+
+    #[must_use]
+    pub fn at_field(self, field: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        self.within(PathSegment::Field(field.into()))
+    }
+
+    #[must_use]
+    pub fn at_index(self, index: usize) -> Self {
+        self.within(PathSegment::Index(index))
+    }
+
+    /// See [`DecodeError::at_key`].
+    #[must_use]
+    pub fn at_key<Data: Bos<[u8]>>(self, key: &TokenTree<Data>) -> Self {
+        match self {
+            Self::Decode(e) => Self::Decode(e.at_key(key)),
+            e => e,
+        }
+    }
+}
+
+/// A single step through syrup input, as produced by [`Reader`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event<'i> {
+    /// `[`
+    ListStart,
+    /// `<`; the next value is the record's label
+    RecordStart,
+    /// `#`
+    SetStart,
+    /// `{`; values alternate between keys and their values
+    DictStart,
+    /// The end of the innermost open list, record, set, or dictionary
+    End,
+    Literal(Literal<&'i [u8]>),
+}
+
+/// A container that has been started but not yet ended
+#[derive(Clone, Copy)]
+struct Open {
+    /// The byte that ends this container
+    close: u8,
+    /// Number of complete values read inside this container so far
+    values: usize,
+}
+
+/// Reads syrup input one [`Event`] at a time, without building a [`TokenTree`] for every
+/// collection.
+///
+/// ```
+/// # use syrup::de::{Event, Reader};
+/// let mut reader = Reader::new(b"<5'hello[1+2+3+]3\"bye>");
+/// assert_eq!(reader.next_event()?, Some(Event::RecordStart));
+/// // the label
+/// reader.skip_value()?;
+/// // skip over the list without parsing its elements into a tree
+/// reader.skip_value()?;
+/// assert_eq!(reader.decode::<String>()?, "bye");
+/// assert_eq!(reader.next_event()?, Some(Event::End));
+/// assert_eq!(reader.next_event()?, None);
+/// # Ok::<(), syrup::de::ReadError>(())
+/// ```
+pub struct Reader<'i> {
+    input: &'i [u8],
+    pos: usize,
+    stack: Vec<Open>,
+    /// Whether iterating has returned an error, after which it stops
+    failed: bool,
+}
+
+impl<'i> Reader<'i> {
+    #[must_use]
+    pub const fn new(input: &'i [u8]) -> Self {
+        Self {
+            input,
+            pos: 0,
+            stack: Vec::new(),
+            failed: false,
+        }
+    }
+
+    /// The offset of the next unread byte.
+    #[inline]
+    #[must_use]
+    pub const fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// The input that hasn't been read yet.
+    #[inline]
+    #[must_use]
+    pub fn remaining(&self) -> &'i [u8] {
+        &self.input[self.pos..]
+    }
+
+    /// The number of containers that have been started but not yet ended.
+    #[inline]
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Lex the event at the current position, along with its length in bytes.
+    fn lex(&self) -> Result<Option<(Event<'i>, usize)>, ReadError> {
+        let input = self.remaining();
+        let Some(&first) = input.first() else {
+            return match self.stack.is_empty() {
+                true => Ok(None),
+                false => Err(ReadError::Incomplete { offset: self.pos }),
+            };
+        };
+        let event = match first {
+            b'[' => Event::ListStart,
+            b'<' => Event::RecordStart,
+            b'#' => Event::SetStart,
+            b'{' => Event::DictStart,
+            b']' | b'>' | b'$' | b'}' => Event::End,
+            _ => {
+                let (rem, literal) = Literal::parse::<nom::error::Error<&[u8]>>(input)
+                    .map_err(|e| self.lex_error(e))?;
+                return Ok(Some((Event::Literal(literal), input.len() - rem.len())));
+            }
+        };
+        Ok(Some((event, 1)))
+    }
+
+    fn lex_error(&self, error: nom::Err<nom::error::Error<&[u8]>>) -> ReadError {
+        match error {
+            nom::Err::Incomplete(_) => ReadError::Incomplete {
+                offset: self.input.len(),
+            },
+            nom::Err::Error(e) | nom::Err::Failure(e) => ReadError::Syntax {
+                offset: self.input.len() - e.input.len(),
+                kind: e.code,
+            },
+        }
+    }
+
+    /// Note that a value has been completed in the innermost open container.
+    fn complete_value(&mut self) {
+        if let Some(open) = self.stack.last_mut() {
+            open.values += 1;
+        }
+    }
+
+    /// Look at the next event without consuming it.
+    pub fn peek_event(&self) -> Result<Option<Event<'i>>, ReadError> {
+        Ok(self.lex()?.map(|(event, _)| event))
+    }
+
+    /// Read the next event, or `None` once all of the input has been read.
+    ///
+    /// An error leaves the reader where it was, so reading again fails the same way.
+    pub fn next_event(&mut self) -> Result<Option<Event<'i>>, ReadError> {
+        let Some((event, len)) = self.lex()? else {
+            return Ok(None);
+        };
+        let offset = self.pos;
+        match &event {
+            Event::ListStart => self.stack.push(Open {
+                close: b']',
+                values: 0,
+            }),
+            Event::RecordStart => self.stack.push(Open {
+                close: b'>',
+                values: 0,
+            }),
+            Event::SetStart => self.stack.push(Open {
+                close: b'$',
+                values: 0,
+            }),
+            Event::DictStart => self.stack.push(Open {
+                close: b'}',
+                values: 0,
+            }),
+            Event::End => {
+                let found = self.input[offset];
+                match self.stack.last() {
+                    Some(&Open { close, values }) if close == found => {
+                        if close == b'>' && values == 0 {
+                            return Err(ReadError::MissingLabel { offset });
+                        } else if close == b'}' && !values.is_multiple_of(2) {
+                            return Err(ReadError::MissingValue { offset });
+                        }
+                        self.stack.pop();
+                        self.complete_value();
+                    }
+                    _ => return Err(ReadError::UnexpectedClose { offset, found }),
+                }
+            }
+            Event::Literal(_) => self.complete_value(),
+        }
+        self.pos += len;
+        Ok(Some(event))
+    }
+
+    /// Fail unless a value (rather than the end of a container, or of the input) starts at the
+    /// current position.
+    fn expect_value(&self) -> Result<(), ReadError> {
+        match self.remaining().first() {
+            Some(&found @ (b']' | b'>' | b'$' | b'}')) => Err(ReadError::UnexpectedClose {
+                offset: self.pos,
+                found,
+            }),
+            Some(_) => Ok(()),
+            None => Err(ReadError::Incomplete { offset: self.pos }),
+        }
+    }
+
+    /// Skip over the entire value at the current position, including everything nested inside it.
+    ///
+    /// Fails if there's no value at the current position (because the innermost container ends
+    /// there, or the input is exhausted).
+    pub fn skip_value(&mut self) -> Result<(), ReadError> {
+        self.skip_value_within(usize::MAX)
+    }
+
+    /// Like [`skip_value`](Self::skip_value), but fails with [`Limit::Depth`] if the value nests
+    /// more than `max_depth` containers.
+    pub(crate) fn skip_value_within(&mut self, max_depth: usize) -> Result<(), ReadError> {
+        self.expect_value()?;
+        let mut depth: usize = 0;
+        loop {
+            let offset = self.pos;
+            match self.next_event()? {
+                Some(
+                    Event::ListStart | Event::RecordStart | Event::SetStart | Event::DictStart,
+                ) => {
+                    if depth == max_depth {
+                        return Err(ReadError::Limit(LimitExceeded {
+                            limit: Limit::Depth,
+                            offset,
+                        }));
+                    }
+                    depth += 1;
+                }
+                // `expect_value` ensures the first event isn't an `End`
+                Some(Event::End) => depth -= 1,
+                Some(Event::Literal(_)) => {}
+                None => return Err(ReadError::Incomplete { offset }),
+            }
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Parse the entire value at the current position into a [`TokenTree`], within the default
+    /// [`ParseLimits`].
+    pub fn read_tree(&mut self) -> Result<TokenTree<&'i [u8]>, ReadError> {
+        self.expect_value()?;
+        let input = self.remaining();
+        let (rem, tree) = TokenTree::parse_with_limits::<nom::error::Error<&[u8]>>(
+            input,
+            &ParseLimits::default(),
+        )
+        .map_err(|e| match e {
+            LimitedParseError::Limit(e) => ReadError::Limit(LimitExceeded {
+                offset: self.pos + e.offset,
+                ..e
+            }),
+            // only checked when parsing canonically
+            LimitedParseError::NotCanonical(e) => ReadError::Syntax {
+                offset: self.pos + e.offset,
+                kind: nom::error::ErrorKind::Verify,
+            },
+            LimitedParseError::Parse(e) => self.lex_error(e),
+        })?;
+        self.pos += input.len() - rem.len();
+        self.complete_value();
+        Ok(tree)
+    }
+
+    /// Fail with `expected`, reporting the kind of the value at the current position instead.
+    ///
+    /// Only the first event of the value is lexed, so a large collection isn't read just to say
+    /// that it was found.
+    pub(crate) fn unexpected<T>(&self, expected: SyrupKind) -> Result<T, ReadError> {
+        self.expect_value()?;
+        let found = match self.peek_event()? {
+            Some(Event::Literal(literal)) => literal.kind(),
+            Some(Event::ListStart) => SyrupKind::List { length: None },
+            Some(Event::RecordStart) => SyrupKind::Record { label: None },
+            Some(Event::SetStart) => SyrupKind::Set,
+            Some(Event::DictStart) => SyrupKind::Dictionary,
+            // `expect_value` has already failed at the end of a container or the input
+            Some(Event::End) | None => return Err(ReadError::Incomplete { offset: self.pos }),
+        };
+        Err(DecodeError::unexpected_kind(expected, found).into())
+    }
+
+    /// Read the literal at the current position, failing with `expected` if a collection starts
+    /// there instead.
+    pub fn read_literal(&mut self, expected: SyrupKind) -> Result<Literal<&'i [u8]>, ReadError> {
+        self.expect_value()?;
+        match self.peek_event()? {
+            Some(Event::Literal(_)) => match self.next_event()? {
+                Some(Event::Literal(literal)) => Ok(literal),
+                _ => unreachable!("the peeked event was a literal"),
+            },
+            _ => self.unexpected(expected),
+        }
+    }
+
+    /// Enter the collection at the current position if it starts with `start`.
+    fn begin(&mut self, start: &Event<'_>, expected: SyrupKind) -> Result<(), ReadError> {
+        self.expect_value()?;
+        match self.peek_event()? {
+            Some(event) if event == *start => {
+                self.next_event()?;
+                Ok(())
+            }
+            _ => self.unexpected(expected),
+        }
+    }
+
+    /// Enter the list at the current position, failing with `expected` if some other value is
+    /// there instead.
+    pub fn begin_list(&mut self, expected: SyrupKind) -> Result<(), ReadError> {
+        self.begin(&Event::ListStart, expected)
+    }
+
+    /// Enter the set at the current position, failing with `expected` if some other value is
+    /// there instead.
+    pub fn begin_set(&mut self, expected: SyrupKind) -> Result<(), ReadError> {
+        self.begin(&Event::SetStart, expected)
+    }
+
+    /// Enter the dictionary at the current position, failing with `expected` if some other value
+    /// is there instead.
+    pub fn begin_dictionary(&mut self, expected: SyrupKind) -> Result<(), ReadError> {
+        self.begin(&Event::DictStart, expected)
+    }
+
+    /// Enter the record at the current position and read its label, failing with `expected` if
+    /// some other value is there instead.
+    pub fn begin_record(&mut self, expected: SyrupKind) -> Result<TokenTree<&'i [u8]>, ReadError> {
+        self.begin(&Event::RecordStart, expected)?;
+        self.read_tree()
+    }
+
+    /// Whether the innermost open container ends at the current position.
+    ///
+    /// Fails at the end of the input, since any open container is then incomplete.
+    pub fn at_end(&self) -> Result<bool, ReadError> {
+        match self.peek_event()? {
+            Some(event) => Ok(event == Event::End),
+            None => Err(ReadError::Incomplete { offset: self.pos }),
+        }
+    }
+
+    /// Skip any values left in the innermost open container, then leave it, returning the number
+    /// of values skipped.
+    pub fn skip_rest(&mut self) -> Result<usize, ReadError> {
+        let mut skipped = 0;
+        while !self.at_end()? {
+            self.skip_value()?;
+            skipped += 1;
+        }
+        self.next_event()?;
+        Ok(skipped)
+    }
+
+    /// Decode the value at the current position with [`DecodeFrom`], without building a
+    /// [`TokenTree`] for it.
+    #[inline]
+    pub fn read<T: DecodeFrom<'i>>(&mut self) -> Result<T, ReadError> {
+        T::decode_from(self)
+    }
+
+    /// Decode the value at the current position.
+    ///
+    /// Only the value at the current position is parsed into a [`TokenTree`]. The result may not
+    /// borrow from the input; for that, [`decode`](TokenTree::decode) the result of
+    /// [`read_tree`](Self::read_tree) instead.
+    pub fn decode<T>(&mut self) -> Result<T, ReadError>
+    where
+        T: for<'t> Decode<'t, &'t [u8]>,
+    {
+        let tree = self.read_tree()?;
+        Ok(T::decode(&tree)?)
+    }
+}
+
+/// Iterating stops after the first error.
+impl<'i> Iterator for Reader<'i> {
+    type Item = Result<Event<'i>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let res = self.next_event().transpose();
+        self.failed = matches!(res, Some(Err(_)));
+        res
+    }
+}
+
+impl std::iter::FusedIterator for Reader<'_> {}
@@ -0,0 +1,147 @@
+use proptest::prelude::*;
+
+use crate::de::{
+    DecodeErrorKind, Event, Limit, LimitExceeded, Literal, ReadError, Reader, SyrupKind, TokenTree,
+};
+
+#[test]
+fn reads_events() -> Result<(), ReadError> {
+    let mut reader = Reader::new(b"{3'key[t]}4\"done");
+    assert_eq!(reader.next_event()?, Some(Event::DictStart));
+    assert_eq!(
+        reader.next_event()?,
+        Some(Event::Literal(Literal::Symbol(b"key".as_slice())))
+    );
+    assert_eq!(reader.depth(), 1);
+    assert_eq!(reader.next_event()?, Some(Event::ListStart));
+    assert_eq!(
+        reader.peek_event()?,
+        Some(Event::Literal(Literal::Bool(true)))
+    );
+    assert_eq!(
+        reader.next_event()?,
+        Some(Event::Literal(Literal::Bool(true)))
+    );
+    assert_eq!(reader.next_event()?, Some(Event::End));
+    assert_eq!(reader.next_event()?, Some(Event::End));
+    assert_eq!(reader.depth(), 0);
+    assert_eq!(reader.decode::<String>()?, "done");
+    assert_eq!(reader.next_event()?, None);
+    Ok(())
+}
+
+#[test]
+fn skips_nested_values() -> Result<(), ReadError> {
+    let mut reader = Reader::new(b"[<1'a#1+2+${}>[[]]5:bytes]t");
+    assert_eq!(reader.next_event()?, Some(Event::ListStart));
+    reader.skip_value()?;
+    reader.skip_value()?;
+    assert_eq!(
+        reader.read_tree()?.to_bytes().as_ref(),
+        b"5:bytes".as_slice()
+    );
+    assert!(matches!(
+        reader.skip_value(),
+        Err(ReadError::UnexpectedClose { offset: 25, .. })
+    ));
+    assert_eq!(reader.next_event()?, Some(Event::End));
+    reader.skip_value()?;
+    assert!(reader.remaining().is_empty());
+    Ok(())
+}
+
+#[test]
+fn rejects_malformed_input() {
+    fn read_all(input: &[u8]) -> Result<(), ReadError> {
+        Reader::new(input).try_for_each(|event| event.map(drop))
+    }
+    assert!(matches!(
+        read_all(b"[1+"),
+        Err(ReadError::Incomplete { offset: 3 })
+    ));
+    assert!(matches!(
+        read_all(b"3:ab"),
+        Err(ReadError::Incomplete { .. })
+    ));
+    assert!(matches!(
+        read_all(b"[>"),
+        Err(ReadError::UnexpectedClose {
+            offset: 1,
+            found: b'>'
+        })
+    ));
+    assert!(matches!(
+        read_all(b"<>"),
+        Err(ReadError::MissingLabel { offset: 1 })
+    ));
+    assert!(matches!(
+        read_all(b"{t}"),
+        Err(ReadError::MissingValue { offset: 2 })
+    ));
+    assert!(matches!(
+        read_all(b"[x]"),
+        Err(ReadError::Syntax { offset: 1, .. })
+    ));
+}
+
+#[test]
+fn stays_put_after_errors() {
+    let mut reader = Reader::new(b"[<>]");
+    assert!(matches!(reader.next_event(), Ok(Some(Event::ListStart))));
+    assert!(matches!(reader.next_event(), Ok(Some(Event::RecordStart))));
+    for _ in 0..2 {
+        assert!(matches!(
+            reader.next_event(),
+            Err(ReadError::MissingLabel { offset: 2 })
+        ));
+        assert_eq!((reader.position(), reader.depth()), (2, 2));
+    }
+
+    let mut reader = Reader::new(b"[1+");
+    assert_eq!(reader.by_ref().count(), 3);
+    assert!(reader.next().is_none());
+    assert_eq!(Reader::new(b"{t}t").filter_map(Result::ok).count(), 2);
+}
+
+#[test]
+fn limits_trees() {
+    let mut input = b"t".to_vec();
+    input.extend(std::iter::repeat_n(b'[', 200_000));
+    let mut reader = Reader::new(&input);
+    reader.skip_value().unwrap();
+    // reporting what was found instead only looks at the start of the value
+    assert!(matches!(
+        reader.read_literal(SyrupKind::Bool),
+        Err(ReadError::Decode(e)) if matches!(
+            e.kind(),
+            DecodeErrorKind::Unexpected { found: SyrupKind::List { length: None }, .. }
+        )
+    ));
+    assert!(matches!(
+        reader.read_tree(),
+        Err(ReadError::Limit(LimitExceeded {
+            limit: Limit::Depth,
+            offset: 129
+        }))
+    ));
+    assert!(crate::from_bytes::<u64>(&input[1..]).is_err());
+}
+
+proptest! {
+    #[test]
+    fn skips_any_value(trees in proptest::collection::vec(TokenTree::arbitrary(), 1..=4)) {
+        let mut bytes = Vec::new();
+        for tree in &trees {
+            bytes.extend_from_slice(&tree.to_bytes());
+        }
+        let mut reader = Reader::new(&bytes);
+        for tree in &trees {
+            let start = reader.position();
+            let skipped = reader.skip_value();
+            prop_assert!(skipped.is_ok(), "input: `{}`, error: {}", String::from_utf8_lossy(&bytes), skipped.unwrap_err());
+            let expected = tree.to_bytes();
+            prop_assert_eq!(&bytes[start..reader.position()], expected.as_ref());
+        }
+        prop_assert!(reader.remaining().is_empty());
+    }
+}
@@ -0,0 +1,297 @@
+use nom::Needed;
+
+use crate::de::{
+    Dictionary, Limit, LimitExceeded, List, Literal, ParseLimits, Record, Set, TokenTree,
+    lex::literal_too_long,
+};
+
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum StreamDecodeError {
+    #[error("invalid syrup at byte {offset}: {kind:?}")]
+    Syntax {
+        offset: usize,
+        kind: nom::error::ErrorKind,
+    },
+    #[error("unexpected '{}' at byte {offset}", char::from(*.found))]
+    UnexpectedClose { offset: usize, found: u8 },
+    #[error("record without a label at byte {offset}")]
+    MissingLabel { offset: usize },
+    #[error("dictionary key without a value at byte {offset}")]
+    MissingValue { offset: usize },
+    #[error(transparent)]
+    Limit(#[from] LimitExceeded),
+}
+
+/// The owned token trees built by [`StreamingDecoder`]
+type Tree = TokenTree<Vec<u8>>;
+
+/// A container that has been opened but not yet closed.
+enum Frame {
+    List(Vec<Tree>),
+    Record {
+        label: Option<Tree>,
+        elements: Vec<Tree>,
+    },
+    Set(Vec<Tree>),
+    Dictionary {
+        entries: Vec<(Tree, Tree)>,
+        key: Option<Tree>,
+    },
+}
+
+impl Frame {
+    /// The byte that closes this frame
+    const fn close(&self) -> u8 {
+        match self {
+            Frame::List(_) => b']',
+            Frame::Record { .. } => b'>',
+            Frame::Set(_) => b'$',
+            Frame::Dictionary { .. } => b'}',
+        }
+    }
+
+    /// The number of elements in this frame, as counted by [`ParseLimits::max_elements`]
+    fn len(&self) -> usize {
+        match self {
+            Frame::List(elements) | Frame::Set(elements) | Frame::Record { elements, .. } => {
+                elements.len()
+            }
+            Frame::Dictionary { entries, .. } => entries.len(),
+        }
+    }
+
+    fn push(&mut self, tree: Tree) {
+        match self {
+            Frame::List(elements) | Frame::Set(elements) => elements.push(tree),
+            Frame::Record { label, elements } => match label {
+                Some(_) => elements.push(tree),
+                None => *label = Some(tree),
+            },
+            Frame::Dictionary { entries, key } => match key.take() {
+                Some(key) => entries.push((key, tree)),
+                None => *key = Some(tree),
+            },
+        }
+    }
+
+    fn finish(self, offset: usize) -> Result<Tree, StreamDecodeError> {
+        match self {
+            Frame::List(elements) => Ok(TokenTree::List(List::new(elements))),
+            Frame::Record {
+                label: Some(label),
+                elements,
+            } => Ok(TokenTree::Record(Box::new(Record::new(label, elements)))),
+            Frame::Record { label: None, .. } => Err(StreamDecodeError::MissingLabel { offset }),
+            Frame::Set(entries) => Ok(TokenTree::Set(Set::new(entries))),
+            Frame::Dictionary { entries, key: None } => {
+                Ok(TokenTree::Dictionary(Dictionary::new(entries)))
+            }
+            Frame::Dictionary { key: Some(_), .. } => {
+                Err(StreamDecodeError::MissingValue { offset })
+            }
+        }
+    }
+}
+
+/// Incrementally decodes a stream of syrup values from byte chunks, as they arrive.
+///
+/// Unlike [`TokenTree::parse`], progress is kept between chunks: every complete value inside an
+/// open list, record, set, or dictionary is only parsed once, no matter how many chunks the
+/// enclosing value is split across.
+///
+/// Each value is held to [`ParseLimits`], the defaults unless the decoder is made with
+/// [`StreamingDecoder::with_limits`], so that a peer can't exhaust the stack or memory.
+///
+/// ```
+/// # use syrup::de::StreamingDecoder;
+/// let mut decoder = StreamingDecoder::new();
+/// decoder.feed(b"[1+2");
+/// assert!(decoder.next_tree()?.is_none());
+/// decoder.feed(b"+]3\"abc");
+/// assert!(decoder.next_tree()?.is_some());
+/// assert!(decoder.next_tree()?.is_some());
+/// assert!(decoder.next_tree()?.is_none());
+/// # Ok::<(), syrup::de::StreamDecodeError>(())
+/// ```
+#[derive(Default)]
+pub struct StreamingDecoder {
+    /// Bytes fed to the decoder, from `start` on not yet consumed
+    buffer: Vec<u8>,
+    /// Where the unconsumed bytes start in `buffer`
+    start: usize,
+    /// Number of bytes drained from the front of `buffer` so far, for error offsets
+    drained: usize,
+    /// Open containers, innermost last
+    stack: Vec<Frame>,
+    /// Don't try to parse again until at least this many bytes are unconsumed; avoids re-parsing
+    /// a large incomplete literal for every chunk
+    needed: usize,
+    limits: ParseLimits,
+    /// Number of nodes in the current top-level value so far
+    nodes: usize,
+}
+
+impl StreamingDecoder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A decoder that holds each value to `limits` rather than the defaults.
+    #[must_use]
+    pub fn with_limits(limits: ParseLimits) -> Self {
+        Self {
+            limits,
+            ..Self::default()
+        }
+    }
+
+    /// Append `bytes` to the input.
+    pub fn feed(&mut self, bytes: &[u8]) {
+        // only move the unconsumed bytes down once they're at most half of the buffer, so each
+        // byte is moved a bounded number of times
+        if self.start > self.buffer.len() / 2 {
+            self.buffer.drain(..self.start);
+            self.drained += self.start;
+            self.start = 0;
+        }
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// The number of bytes that have been fed but not yet consumed.
+    #[must_use]
+    pub fn buffered(&self) -> usize {
+        self.buffer.len() - self.start
+    }
+
+    /// Whether the decoder is between top-level values, with no partially decoded input.
+    #[must_use]
+    pub fn is_idle(&self) -> bool {
+        self.buffered() == 0 && self.stack.is_empty()
+    }
+
+    /// Decode the next complete top-level value, or return `None` if more input is needed.
+    ///
+    /// After an error, the decoder's state is unspecified and it should be discarded.
+    pub fn next_tree(&mut self) -> Result<Option<TokenTree<Vec<u8>>>, StreamDecodeError> {
+        loop {
+            if self.buffered() < self.needed {
+                return Ok(None);
+            }
+            let input = &self.buffer[self.start..];
+            let Some(&first) = input.first() else {
+                return Ok(None);
+            };
+            let offset = self.drained + self.start;
+            let exceeded = |limit| Err(LimitExceeded { limit, offset }.into());
+            if !matches!(first, b']' | b'>' | b'$' | b'}') {
+                if self
+                    .stack
+                    .last()
+                    .is_some_and(|frame| frame.len() >= self.limits.max_elements)
+                {
+                    return exceeded(Limit::Elements);
+                }
+                if matches!(first, b'[' | b'<' | b'#' | b'{') {
+                    if self.stack.len() >= self.limits.max_depth {
+                        return exceeded(Limit::Depth);
+                    }
+                    count_node(&mut self.nodes, &self.limits, offset)?;
+                } else if literal_too_long(input, &self.limits) {
+                    return exceeded(Limit::LiteralLength);
+                }
+            }
+            let tree = match first {
+                b'[' => {
+                    self.stack.push(Frame::List(Vec::new()));
+                    self.start += 1;
+                    continue;
+                }
+                b'<' => {
+                    self.stack.push(Frame::Record {
+                        label: None,
+                        elements: Vec::new(),
+                    });
+                    self.start += 1;
+                    continue;
+                }
+                b'#' => {
+                    self.stack.push(Frame::Set(Vec::new()));
+                    self.start += 1;
+                    continue;
+                }
+                b'{' => {
+                    self.stack.push(Frame::Dictionary {
+                        entries: Vec::new(),
+                        key: None,
+                    });
+                    self.start += 1;
+                    continue;
+                }
+                b']' | b'>' | b'$' | b'}' => match self.stack.pop() {
+                    Some(frame) if frame.close() == first => {
+                        self.start += 1;
+                        frame.finish(offset)?
+                    }
+                    _ => {
+                        return Err(StreamDecodeError::UnexpectedClose {
+                            offset,
+                            found: first,
+                        });
+                    }
+                },
+                _ => match Literal::<Vec<u8>>::parse::<nom::error::Error<&[u8]>>(input) {
+                    Ok((rem, literal)) => {
+                        // counted only once it's complete, rather than on every attempt
+                        count_node(&mut self.nodes, &self.limits, offset)?;
+                        self.start += input.len() - rem.len();
+                        TokenTree::Literal(literal)
+                    }
+                    Err(nom::Err::Incomplete(needed)) => {
+                        let needed = match needed {
+                            Needed::Size(amt) => amt.get(),
+                            Needed::Unknown => 1,
+                        };
+                        // the literal starts at `start`, so the next attempt begins there too
+                        self.needed = input.len() + needed;
+                        return Ok(None);
+                    }
+                    Err(nom::Err::Error(e) | nom::Err::Failure(e)) => {
+                        return Err(StreamDecodeError::Syntax {
+                            offset,
+                            kind: e.code,
+                        });
+                    }
+                },
+            };
+            self.needed = 0;
+            match self.stack.last_mut() {
+                Some(frame) => frame.push(tree),
+                None => {
+                    self.nodes = 0;
+                    return Ok(Some(tree));
+                }
+            }
+        }
+    }
+}
+
+/// Count another node of the current value, which starts at `offset`.
+fn count_node(
+    nodes: &mut usize,
+    limits: &ParseLimits,
+    offset: usize,
+) -> Result<(), StreamDecodeError> {
+    *nodes += 1;
+    if *nodes > limits.max_nodes {
+        return Err(LimitExceeded {
+            limit: Limit::Nodes,
+            offset,
+        }
+        .into());
+    }
+    Ok(())
+}
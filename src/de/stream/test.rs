@@ -0,0 +1,148 @@
+use proptest::prelude::*;
+
+use crate::de::{
+    Limit, LimitExceeded, ParseLimits, StreamDecodeError, StreamingDecoder, TokenTree,
+};
+
+fn decode_all(
+    decoder: &mut StreamingDecoder,
+) -> Result<Vec<TokenTree<Vec<u8>>>, StreamDecodeError> {
+    let mut res = Vec::new();
+    while let Some(tree) = decoder.next_tree()? {
+        res.push(tree);
+    }
+    Ok(res)
+}
+
+#[test]
+fn waits_for_more_input() -> Result<(), StreamDecodeError> {
+    let mut decoder = StreamingDecoder::new();
+    decoder.feed(b"<5'hello[1+");
+    assert!(decoder.next_tree()?.is_none());
+    // the completed elements have been consumed; only the open containers remain
+    assert_eq!(decoder.buffered(), 0);
+    decoder.feed(b"10:abcde");
+    assert!(decoder.next_tree()?.is_none());
+    assert_eq!(decoder.buffered(), 8);
+    decoder.feed(b"fghij]>t");
+    let tree = decoder.next_tree()?.expect("record should be complete");
+    assert_eq!(tree.to_bytes().as_ref(), b"<5'hello[1+10:abcdefghij]>");
+    assert_eq!(
+        decoder
+            .next_tree()?
+            .map(|tree| tree.to_bytes().into_owned()),
+        Some(b"t".to_vec())
+    );
+    assert!(decoder.is_idle());
+    Ok(())
+}
+
+#[test]
+fn rejects_invalid_input() {
+    let mut decoder = StreamingDecoder::new();
+    decoder.feed(b"[1+>");
+    assert!(matches!(
+        decoder.next_tree(),
+        Err(StreamDecodeError::UnexpectedClose {
+            offset: 3,
+            found: b'>'
+        })
+    ));
+
+    let mut decoder = StreamingDecoder::new();
+    decoder.feed(b"<>");
+    assert!(matches!(
+        decoder.next_tree(),
+        Err(StreamDecodeError::MissingLabel { offset: 1 })
+    ));
+
+    let mut decoder = StreamingDecoder::new();
+    decoder.feed(b"{1+}");
+    assert!(matches!(
+        decoder.next_tree(),
+        Err(StreamDecodeError::MissingValue { offset: 3 })
+    ));
+
+    let mut decoder = StreamingDecoder::new();
+    decoder.feed(b"[x]");
+    assert!(matches!(
+        decoder.next_tree(),
+        Err(StreamDecodeError::Syntax { offset: 1, .. })
+    ));
+}
+
+#[test]
+fn reports_offsets_across_chunks() -> Result<(), StreamDecodeError> {
+    let mut decoder = StreamingDecoder::new();
+    decoder.feed(b"1+2+3+[");
+    assert_eq!(decode_all(&mut decoder)?.len(), 3);
+    assert_eq!(decoder.buffered(), 0);
+    // the consumed bytes are dropped here, but offsets still count them
+    decoder.feed(b"4+x]");
+    assert_eq!(decoder.buffered(), 4);
+    assert!(matches!(
+        decoder.next_tree(),
+        Err(StreamDecodeError::Syntax { offset: 9, .. })
+    ));
+    Ok(())
+}
+
+/// The limit `input` exceeds, and where, when fed to a decoder with `limits` in one chunk
+fn exceeded(input: &[u8], limits: ParseLimits) -> Option<LimitExceeded> {
+    let mut decoder = StreamingDecoder::with_limits(limits);
+    decoder.feed(input);
+    match decode_all(&mut decoder) {
+        Err(StreamDecodeError::Limit(exceeded)) => Some(exceeded),
+        _ => None,
+    }
+}
+
+#[test]
+fn respects_limits() {
+    let limits = ParseLimits {
+        max_depth: 2,
+        max_literal_len: 3,
+        max_elements: 2,
+        max_nodes: 5,
+    };
+    let at = |limit, offset| Some(LimitExceeded { limit, offset });
+    assert_eq!(exceeded(b"[[1+]][]", limits), None);
+    assert_eq!(exceeded(b"[[[1+]]]", limits), at(Limit::Depth, 2));
+    assert_eq!(exceeded(b"[1+2+3+]", limits), at(Limit::Elements, 5));
+    assert_eq!(exceeded(b"<1'a1+2+>", limits), None);
+    assert_eq!(exceeded(b"{1+2+3+4+}", limits), None);
+    assert_eq!(exceeded(b"t[[tt][t]]", limits), at(Limit::Nodes, 7));
+    // the length is checked before waiting for the rest of the literal
+    assert_eq!(exceeded(b"[4:ab", limits), at(Limit::LiteralLength, 1));
+    assert_eq!(exceeded(b"1234", limits), at(Limit::LiteralLength, 0));
+
+    // deep enough to overflow the stack without a limit
+    let deep = vec![b'['; 1 << 20];
+    assert_eq!(
+        exceeded(&deep, ParseLimits::default()),
+        at(Limit::Depth, 128)
+    );
+}
+
+proptest! {
+    #[test]
+    fn decodes_chunked_input(
+        trees in proptest::collection::vec(TokenTree::arbitrary(), 1..=4),
+        chunk_size in 1..16usize,
+    ) {
+        let mut bytes = Vec::new();
+        for tree in &trees {
+            bytes.extend_from_slice(&tree.to_bytes());
+        }
+        let mut decoder = StreamingDecoder::new();
+        let mut res = Vec::with_capacity(trees.len());
+        for chunk in bytes.chunks(chunk_size) {
+            decoder.feed(chunk);
+            let decoded = decode_all(&mut decoder);
+            prop_assert!(decoded.is_ok(), "input: `{}`, error: {}", String::from_utf8_lossy(&bytes), decoded.unwrap_err());
+            res.extend(decoded.unwrap());
+        }
+        prop_assert!(decoder.is_idle());
+        prop_assert_eq!(res, trees);
+    }
+}
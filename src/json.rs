@@ -0,0 +1,342 @@
+//! Conversion between [`TokenTree`]s and JSON, as [`serde_json::Value`]s.
+//!
+//! [`to_json`] and [`from_json`] use a tagged mapping, which keeps everything needed to get the
+//! same syrup value back:
+//!
+//! | syrup                  | JSON                                                           |
+//! |------------------------|----------------------------------------------------------------|
+//! | boolean                | boolean                                                        |
+//! | integer                | number, or `{"@int": "digits"}` if it doesn't fit in 64 bits   |
+//! | `F64`                  | number, or `{"@f64": "bits in hex"}` if it isn't finite        |
+//! | `F32`                  | `{"@f32": number}`, or `{"@f32": "bits in hex"}`               |
+//! | string                 | string                                                         |
+//! | symbol                 | `{"@symbol": "name"}`                                          |
+//! | byte string            | `{"@bytes": "standard base64"}`                                |
+//! | list                   | array                                                          |
+//! | record                 | `{"@record": label, "fields": [elements]}`                     |
+//! | set                    | `{"@set": [entries]}`                                          |
+//! | dictionary             | `{"@dict": [[key, value], ...]}`                               |
+//!
+//! Strings and symbols must be valid UTF-8 to be written as JSON strings. Integers are written in
+//! canonical form, and set and dictionary entries in the order they're stored in the tree.
+//! [`from_json`] also reads any object that isn't one of the tags above as a dictionary with string
+//! keys, and rejects `null`.
+//!
+//! ```
+//! # use serde_json::json;
+//! # use syrup::{TokenTree, json::{from_json, to_json}};
+//! let tree: TokenTree<Vec<u8>> = r#"<op:deliver 3 [#"hi" 1.5f] {id: 1}>"#.parse().unwrap();
+//! let value = to_json(&tree)?;
+//! assert_eq!(
+//!     value,
+//!     json!({
+//!         "@record": {"@symbol": "op:deliver"},
+//!         "fields": [
+//!             3,
+//!             [{"@bytes": "aGk="}, {"@f32": 1.5}],
+//!             {"@dict": [[{"@symbol": "id"}, 1]]},
+//!         ],
+//!     })
+//! );
+//! assert_eq!(from_json(&value)?, tree);
+//! # Ok::<(), syrup::json::JsonError>(())
+//! ```
+//!
+//! [`to_json_natural`] is meant for people rather than programs: it gives up the distinctions
+//! above for plain JSON, and can't be converted back.
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use borrow_or_share::{BorrowOrShare, Bos};
+use serde_json::{Map, Number, Value};
+
+use crate::de::{Dictionary, Int, List, Literal, Record, Set, TokenTree};
+
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum JsonError {
+    #[error("{0} isn't valid UTF-8, so it can't be written as a JSON string")]
+    NotUtf8(&'static str),
+    #[error("`null` has no syrup equivalent")]
+    Null,
+    #[error("invalid `{tag}` value: {found}")]
+    InvalidTag { tag: &'static str, found: Value },
+}
+
+fn tagged(tag: &str, value: Value) -> Value {
+    Value::Object(Map::from_iter([(tag.to_owned(), value)]))
+}
+
+fn utf8(bytes: &[u8], kind: &'static str) -> Result<String, JsonError> {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => Ok(s.to_owned()),
+        Err(_e) => Err(JsonError::NotUtf8(kind)),
+    }
+}
+
+/// The integer as a JSON number, if it fits in one.
+fn int_number<Data: Bos<[u8]>>(int: &Int<Data>) -> Option<Number> {
+    i64::try_from(int)
+        .map(Number::from)
+        .or_else(|_e| u64::try_from(int).map(Number::from))
+        .ok()
+}
+
+/// Convert `tree` to JSON with the lossless, tagged mapping described in the [module
+/// docs](self).
+pub fn to_json<Data: Bos<[u8]>>(tree: &TokenTree<Data>) -> Result<Value, JsonError> {
+    let array = |trees: &[TokenTree<Data>]| -> Result<Value, JsonError> {
+        trees
+            .iter()
+            .map(to_json)
+            .collect::<Result<_, _>>()
+            .map(Value::Array)
+    };
+    Ok(match tree {
+        TokenTree::Literal(literal) => literal_to_json(literal)?,
+        TokenTree::List(list) => array(&list.elements)?,
+        TokenTree::Record(record) => Value::Object(Map::from_iter([
+            ("@record".to_owned(), to_json(&record.label)?),
+            ("fields".to_owned(), array(&record.elements)?),
+        ])),
+        TokenTree::Set(set) => tagged(
+            "@set",
+            set.into_iter()
+                .map(to_json)
+                .collect::<Result<_, _>>()
+                .map(Value::Array)?,
+        ),
+        TokenTree::Dictionary(dict) => tagged(
+            "@dict",
+            dict.into_iter()
+                .map(|(key, value)| Ok(Value::Array(vec![to_json(key)?, to_json(value)?])))
+                .collect::<Result<_, _>>()
+                .map(Value::Array)?,
+        ),
+    })
+}
+
+fn literal_to_json<Data: Bos<[u8]>>(literal: &Literal<Data>) -> Result<Value, JsonError> {
+    Ok(match literal {
+        Literal::Bool(b) => Value::Bool(*b),
+        Literal::Int(int) => match int_number(int) {
+            Some(number) => Value::Number(number),
+            None => tagged("@int", Value::String(int.to_string())),
+        },
+        Literal::F64(f) => match Number::from_f64(*f) {
+            Some(number) => Value::Number(number),
+            None => tagged("@f64", Value::String(format!("{:016x}", f.to_bits()))),
+        },
+        Literal::F32(f) => tagged(
+            "@f32",
+            match Number::from_f64(f64::from(*f)) {
+                Some(number) => Value::Number(number),
+                None => Value::String(format!("{:08x}", f.to_bits())),
+            },
+        ),
+        Literal::String(s) => Value::String(utf8(s.borrow_or_share(), "string")?),
+        Literal::Symbol(s) => tagged(
+            "@symbol",
+            Value::String(utf8(s.borrow_or_share(), "symbol")?),
+        ),
+        Literal::Bytes(b) => tagged("@bytes", Value::String(BASE64.encode(b.borrow_or_share()))),
+    })
+}
+
+/// Convert `tree` to plain JSON, for people to read.
+///
+/// Unlike [`to_json`], this can't fail, and can't be undone:
+///
+/// - numbers are numbers, except integers that don't fit in 64 bits, which are strings of
+///   digits, and floats that aren't finite, which are `null`;
+/// - strings and symbols are both strings, with invalid UTF-8 replaced;
+/// - byte strings are base64 strings;
+/// - lists and sets are arrays;
+/// - records are objects with one key, the label, whose value is an array of the elements;
+/// - dictionaries are objects.
+///
+/// Record labels and dictionary keys that aren't strings or symbols are written in the [text
+/// syntax](TokenTree::parse_text). If several keys end up the same, only the last is kept.
+///
+/// ```
+/// # use serde_json::json;
+/// # use syrup::{TokenTree, json::to_json_natural};
+/// let tree: TokenTree<Vec<u8>> = r#"<op:deliver 3 [#"hi" 1.5f] {id: 1, [2]: "two"}>"#.parse().unwrap();
+/// assert_eq!(
+///     to_json_natural(&tree),
+///     json!({"op:deliver": [3, ["aGk=", 1.5], {"id": 1, "[2]": "two"}]})
+/// );
+/// ```
+pub fn to_json_natural<Data: Bos<[u8]>>(tree: &TokenTree<Data>) -> Value {
+    let array =
+        |trees: &[TokenTree<Data>]| Value::Array(trees.iter().map(to_json_natural).collect());
+    match tree {
+        TokenTree::Literal(literal) => literal_to_json_natural(literal),
+        TokenTree::List(list) => array(&list.elements),
+        TokenTree::Record(record) => Value::Object(Map::from_iter([(
+            natural_key(&record.label),
+            array(&record.elements),
+        )])),
+        TokenTree::Set(set) => Value::Array(set.into_iter().map(to_json_natural).collect()),
+        TokenTree::Dictionary(dict) => Value::Object(
+            dict.into_iter()
+                .map(|(key, value)| (natural_key(key), to_json_natural(value)))
+                .collect(),
+        ),
+    }
+}
+
+fn literal_to_json_natural<Data: Bos<[u8]>>(literal: &Literal<Data>) -> Value {
+    match literal {
+        Literal::Bool(b) => Value::Bool(*b),
+        Literal::Int(int) => match int_number(int) {
+            Some(number) => Value::Number(number),
+            None => Value::String(int.to_string()),
+        },
+        // widening would show the error in the `f32`, as in `0.10000000149011612`
+        Literal::F32(f) => f
+            .to_string()
+            .parse()
+            .ok()
+            .and_then(Number::from_f64)
+            .map_or(Value::Null, Value::Number),
+        Literal::F64(f) => Number::from_f64(*f).map_or(Value::Null, Value::Number),
+        Literal::String(s) | Literal::Symbol(s) => {
+            Value::String(String::from_utf8_lossy(s.borrow_or_share()).into_owned())
+        }
+        Literal::Bytes(b) => Value::String(BASE64.encode(b.borrow_or_share())),
+    }
+}
+
+/// `key` as the key of a JSON object
+fn natural_key<Data: Bos<[u8]>>(key: &TokenTree<Data>) -> String {
+    match key {
+        TokenTree::Literal(Literal::String(s) | Literal::Symbol(s)) => {
+            String::from_utf8_lossy(s.borrow_or_share()).into_owned()
+        }
+        key => key.to_string(),
+    }
+}
+
+/// Convert JSON in the tagged mapping described in the [module docs](self) to a [`TokenTree`].
+pub fn from_json(value: &Value) -> Result<TokenTree<Vec<u8>>, JsonError> {
+    Ok(match value {
+        Value::Null => return Err(JsonError::Null),
+        Value::Bool(b) => TokenTree::Literal(Literal::Bool(*b)),
+        Value::Number(number) => TokenTree::Literal(number_to_literal(number)),
+        Value::String(s) => TokenTree::Literal(Literal::String(s.clone().into_bytes())),
+        Value::Array(values) => TokenTree::List(List::new(array_from_json(values)?)),
+        Value::Object(object) => object_from_json(object)?,
+    })
+}
+
+fn number_to_literal(number: &Number) -> Literal<Vec<u8>> {
+    if let Some(i) = number.as_i64() {
+        Literal::Int(i.into())
+    } else if let Some(u) = number.as_u64() {
+        Literal::Int(u.into())
+    } else {
+        Literal::F64(number.as_f64().unwrap_or(f64::NAN))
+    }
+}
+
+fn array_from_json(values: &[Value]) -> Result<Vec<TokenTree<Vec<u8>>>, JsonError> {
+    values.iter().map(from_json).collect()
+}
+
+/// The tags of objects standing for a single value, other than records
+const TAGS: [&str; 7] = ["@symbol", "@bytes", "@int", "@f64", "@f32", "@set", "@dict"];
+
+fn object_from_json(object: &Map<String, Value>) -> Result<TokenTree<Vec<u8>>, JsonError> {
+    if object.len() == 2
+        && let (Some(label), Some(fields)) = (object.get("@record"), object.get("fields"))
+    {
+        let Value::Array(fields) = fields else {
+            return Err(JsonError::InvalidTag {
+                tag: "@record",
+                found: fields.clone(),
+            });
+        };
+        return Ok(TokenTree::Record(Box::new(Record::new(
+            from_json(label)?,
+            array_from_json(fields)?,
+        ))));
+    }
+    let mut entries = object.iter();
+    if let (Some((tag, value)), None) = (entries.next(), entries.next())
+        && let Some(&tag) = TAGS.iter().find(|&&t| t == tag)
+    {
+        return untag(tag, value)?.ok_or_else(|| JsonError::InvalidTag {
+            tag,
+            found: value.clone(),
+        });
+    }
+    object
+        .iter()
+        .map(|(key, value)| {
+            Ok((
+                TokenTree::Literal(Literal::String(key.clone().into_bytes())),
+                from_json(value)?,
+            ))
+        })
+        .collect::<Result<_, _>>()
+        .map(|entries| TokenTree::Dictionary(Dictionary::new(entries)))
+}
+
+/// The value of an object tagged with `tag`, or `None` if `value` isn't valid for the tag.
+fn untag(tag: &str, value: &Value) -> Result<Option<TokenTree<Vec<u8>>>, JsonError> {
+    let literal = match (tag, value) {
+        ("@symbol", Value::String(s)) => Some(Literal::Symbol(s.clone().into_bytes())),
+        ("@bytes", Value::String(s)) => BASE64.decode(s).ok().map(Literal::Bytes),
+        ("@int", Value::String(s)) => int_from_str(s).map(Literal::Int),
+        ("@int", Value::Number(number)) if !number.is_f64() => Some(number_to_literal(number)),
+        ("@f64", Value::Number(number)) => number.as_f64().map(Literal::F64),
+        ("@f64", Value::String(bits)) => hex_bits(bits, 16).map(f64::from_bits).map(Literal::F64),
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "it was widened from an `f32`"
+        )]
+        ("@f32", Value::Number(number)) => number.as_f64().map(|f| Literal::F32(f as f32)),
+        ("@f32", Value::String(bits)) => hex_bits(bits, 8)
+            .and_then(|bits| u32::try_from(bits).ok())
+            .map(f32::from_bits)
+            .map(Literal::F32),
+        ("@set", Value::Array(values)) => {
+            return Ok(Some(TokenTree::Set(Set::new(array_from_json(values)?))));
+        }
+        ("@dict", Value::Array(entries)) => {
+            let mut pairs = Vec::with_capacity(entries.len());
+            for entry in entries {
+                let Value::Array(pair) = entry else {
+                    return Ok(None);
+                };
+                let [key, value] = &pair[..] else {
+                    return Ok(None);
+                };
+                pairs.push((from_json(key)?, from_json(value)?));
+            }
+            return Ok(Some(TokenTree::Dictionary(Dictionary::new(pairs))));
+        }
+        _ => None,
+    };
+    Ok(literal.map(TokenTree::Literal))
+}
+
+/// Parse a decimal integer, with an optional `-` sign.
+fn int_from_str(s: &str) -> Option<Int<Vec<u8>>> {
+    let unsigned = s.strip_prefix('-').unwrap_or(s);
+    if unsigned.is_empty() || !unsigned.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    #[expect(unsafe_code, reason = "we just checked that they're all ascii digits")]
+    Some(unsafe { Int::new(!s.starts_with('-'), unsigned.as_bytes().to_vec()) })
+}
+
+/// Parse exactly `len` hex digits.
+fn hex_bits(bits: &str, len: usize) -> Option<u64> {
+    if bits.len() != len || !bits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    u64::from_str_radix(bits, 16).ok()
+}
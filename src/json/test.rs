@@ -0,0 +1,154 @@
+use proptest::prelude::*;
+use serde_json::{Value, json};
+
+use crate::{
+    de::{Literal, TokenTree},
+    json::{JsonError, from_json, to_json, to_json_natural},
+};
+
+fn parse(text: &str) -> TokenTree<Vec<u8>> {
+    text.parse()
+        .unwrap_or_else(|e| panic!("failed to parse {text:?}: {e}"))
+}
+
+fn encoded(json: &Value) -> Vec<u8> {
+    from_json(json).unwrap().to_bytes().into_owned()
+}
+
+/// Check that `text` converts to `json`, and back to the same value, both directly and through
+/// JSON text
+fn round_trips(text: &str, json: &Value) {
+    let tree = parse(text);
+    assert_eq!(&to_json(&tree).unwrap(), json, "{text}");
+    assert_eq!(
+        from_json(json).unwrap().to_bytes(),
+        tree.to_bytes(),
+        "{text}"
+    );
+    let reparsed: Value = serde_json::from_str(&json.to_string()).unwrap();
+    assert_eq!(
+        from_json(&reparsed).unwrap().to_bytes(),
+        tree.to_bytes(),
+        "{text}"
+    );
+}
+
+#[test]
+fn maps_literals() {
+    round_trips("#t", &json!(true));
+    round_trips("-12", &json!(-12));
+    round_trips("18446744073709551615", &json!(u64::MAX));
+    round_trips(
+        "-18446744073709551616",
+        &json!({"@int": "-18446744073709551616"}),
+    );
+    round_trips("1.5", &json!(1.5));
+    round_trips("-0.0", &json!(-0.0));
+    round_trips("3.0", &json!(3.0));
+    round_trips(
+        "#xd\"7ff8000000000001\"",
+        &json!({"@f64": "7ff8000000000001"}),
+    );
+    round_trips("0.1f", &json!({"@f32": f64::from(0.1f32)}));
+    round_trips("#xf\"ff800000\"", &json!({"@f32": "ff800000"}));
+    round_trips("\"hi\"", &json!("hi"));
+    round_trips("hi", &json!({"@symbol": "hi"}));
+    round_trips("#\"\\x00\\xff\"", &json!({"@bytes": "AP8="}));
+}
+
+#[test]
+fn maps_collections() {
+    round_trips("[1 [] \"a\"]", &json!([1, [], "a"]));
+    round_trips(
+        "<point 1 2>",
+        &json!({"@record": {"@symbol": "point"}, "fields": [1, 2]}),
+    );
+    round_trips("<\"label\">", &json!({"@record": "label", "fields": []}));
+    round_trips("#{a 1}", &json!({"@set": [{"@symbol": "a"}, 1]}));
+    round_trips(
+        "{b: 1, [2]: #t}",
+        &json!({"@dict": [[{"@symbol": "b"}, 1], [[2], true]]}),
+    );
+}
+
+#[test]
+fn reads_plain_objects_as_dictionaries() {
+    let tree = from_json(&json!({"b": 1, "a": {"c": null}}));
+    assert!(matches!(tree, Err(JsonError::Null)));
+    let tree = from_json(&json!({"b": 1, "a": {"@set": []}, "@x": 2})).unwrap();
+    assert_eq!(tree, parse("{\"@x\": 2, \"a\": #{}, \"b\": 1}"));
+    // an object that merely looks like a record
+    let tree = from_json(&json!({"@record": "a", "fields": [], "extra": 1})).unwrap();
+    assert_eq!(
+        tree.to_bytes(),
+        parse("{\"@record\": \"a\", \"extra\": 1, \"fields\": []}").to_bytes()
+    );
+}
+
+#[test]
+fn rejects_invalid_tags() {
+    let invalid = |value: Value| match from_json(&value) {
+        Err(JsonError::InvalidTag { tag, .. }) => tag,
+        res => panic!("{value} gave {res:?}"),
+    };
+    assert_eq!(invalid(json!({"@bytes": "not base64!"})), "@bytes");
+    assert_eq!(invalid(json!({"@symbol": 1})), "@symbol");
+    assert_eq!(invalid(json!({"@int": "12a"})), "@int");
+    assert_eq!(invalid(json!({"@int": "-"})), "@int");
+    assert_eq!(invalid(json!({"@int": 1.5})), "@int");
+    assert_eq!(invalid(json!({"@f64": "7ff8"})), "@f64");
+    assert_eq!(invalid(json!({"@f32": "+0000000"})), "@f32");
+    assert_eq!(invalid(json!({"@dict": [[1]]})), "@dict");
+    assert_eq!(invalid(json!({"@record": "a", "fields": 1})), "@record");
+    assert!(matches!(
+        to_json(&TokenTree::Literal(Literal::String(vec![0xff]))),
+        Err(JsonError::NotUtf8("string"))
+    ));
+    assert!(matches!(
+        to_json(&TokenTree::Literal(Literal::Symbol(vec![0xff]))),
+        Err(JsonError::NotUtf8("symbol"))
+    ));
+}
+
+#[test]
+fn maps_naturally() {
+    let natural = |text| to_json_natural(&parse(text));
+    assert_eq!(
+        natural("[#t 1 0.1f 0.1 #xd\"7ff0000000000000\"]"),
+        json!([true, 1, 0.1, 0.1, null])
+    );
+    assert_eq!(natural("[\"a\" b #\"c\"]"), json!(["a", "b", "Yw=="]));
+    assert_eq!(
+        natural("-18446744073709551616"),
+        json!("-18446744073709551616")
+    );
+    assert_eq!(natural("#{1 2}"), json!([1, 2]));
+    assert_eq!(natural("<pos 1 2>"), json!({"pos": [1, 2]}));
+    assert_eq!(natural("<[1] #t>"), json!({"[1]": [true]}));
+    assert_eq!(
+        natural("{a: 1, \"b\": 2, 3: 4, #\"k\": 5}"),
+        json!({"a": 1, "b": 2, "3": 4, "#\"k\"": 5})
+    );
+    assert_eq!(
+        to_json_natural(&TokenTree::Literal(Literal::String(vec![b'a', 0xff]))),
+        json!("a\u{fffd}")
+    );
+}
+
+proptest! {
+    #[test]
+    fn round_trips_every_literal(literal in any::<Literal<Vec<u8>>>()) {
+        let tree = TokenTree::Literal(literal);
+        let json = to_json(&tree).unwrap();
+        prop_assert_eq!(encoded(&json), tree.to_bytes().into_owned());
+        let reparsed: Value = serde_json::from_str(&json.to_string()).unwrap();
+        prop_assert_eq!(encoded(&reparsed), tree.to_bytes().into_owned());
+    }
+
+    #[test]
+    fn round_trips_trees(tree in any::<TokenTree<Vec<u8>>>()) {
+        let json = to_json(&tree).unwrap();
+        let reparsed: Value = serde_json::from_str(&json.to_string()).unwrap();
+        prop_assert_eq!(encoded(&reparsed), tree.to_bytes().into_owned());
+    }
+}
@@ -1,9 +1,48 @@
 #![cfg_attr(feature = "decode-array", feature(maybe_uninit_array_assume_init))]
 // #![feature(int_from_ascii, trait_alias)]
 
-pub use syrup_derive::{Decode, Encode};
+pub use syrup_derive::{Decode, DecodeFrom, Encode};
 
-pub use de::{Decode, DecodeError, TokenTree};
+/// Build a [`TokenTree<Vec<u8>>`](TokenTree) from the [text syntax](TokenTree::parse_text),
+/// checked at compile time.
+///
+/// `#(expr)` interpolates any expression whose type implements [`Encode`]. Since the input is made
+/// of Rust tokens, bytes are written `b"..."`, and symbols that would need quotes in the text
+/// syntax, such as `'has spaces'`, must be interpolated. Tokens written without spaces between
+/// them form one symbol, as in `op:deliver`.
+/// Invoke it with braces, as `syrup! { ... }`, so that rustfmt leaves the notation alone.
+///
+/// ```
+/// # use syrup::{TokenTree, syrup};
+/// let pos = 3u64;
+/// let tree = syrup! {
+///     <desc:export #(pos) [b"ab" -1.5f] {greeting: "hi", #t: #{1 2}}>
+/// };
+/// let text: TokenTree<Vec<u8>> =
+///     r#"<desc:export 3 [#"ab" -1.5f] {greeting: "hi", #t: #{1 2}}>"#.parse().unwrap();
+/// assert_eq!(tree, text);
+/// ```
+///
+/// Mistakes are reported where they're made:
+///
+/// ```compile_fail
+/// syrup::syrup! { {key 1} } // expected `:` after the key
+/// ```
+pub use syrup_proc::syrup;
+
+/// Like [`syrup!`], but encodes the value in canonical form at compile time, as a
+/// `&'static [u8]`. Values can't be interpolated.
+///
+/// ```
+/// const HELLO: &[u8] = syrup::syrup_bytes! { <hello {to: "world", from: 007}> };
+/// assert_eq!(HELLO, b"<5'hello{2'to5\"world4'from7+}>");
+/// ```
+pub use syrup_proc::syrup_bytes;
+
+pub use de::{
+    Decode, DecodeError, DecodeFrom, TokenTree, canonicalize, from_bytes, from_bytes_partial,
+};
+pub use raw::RawValue;
 pub use ser::Encode;
 
 pub use borrow_or_share;
@@ -12,10 +51,25 @@ pub use nom;
 pub mod de;
 pub mod ser;
 
+// lets the derive macros' `::syrup` paths resolve in this crate's own tests
+#[cfg(test)]
+extern crate self as syrup;
+
+#[cfg(test)]
+mod test;
+// the serde dev-dependency is only used by the `serde` feature's tests
+#[cfg(all(test, not(feature = "serde")))]
+use serde as _;
+
 /// Decode/encode functions for byte string literals.
 pub mod bytes;
+#[cfg(feature = "json")]
+pub mod json;
 /// Decode/encode functions for optional collections (where empty collections are encoded as `f`)
 pub mod optional_collection;
+mod raw;
+#[cfg(feature = "serde")]
+pub mod serde;
 /// Decode/encode functions for symbol literals.
 pub mod symbol;
 
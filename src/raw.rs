@@ -0,0 +1,187 @@
+use std::ops::Deref;
+
+use borrow_or_share::{BorrowOrShare, Bos};
+
+use crate::{
+    Decode, DecodeError, DecodeFrom, Encode, TokenTree,
+    de::{DecodeBytesError, Literal, ParseLimits, ReadError, Reader},
+    ser::EncodeInto,
+};
+
+#[cfg(test)]
+mod test;
+
+/// A single value kept in its encoded form, for fields that are only passed along, or decoded
+/// later.
+///
+/// Read through [`DecodeFrom`], a `RawValue` holds the exact bytes of the value, which
+/// [`EncodeInto`] writes back unchanged, even if they aren't in canonical form.
+/// `RawValue<&[u8]>` borrows them from the input; `RawValue<Vec<u8>>` owns a copy.
+///
+/// ```
+/// # use std::collections::BTreeMap;
+/// # use syrup::{DecodeFrom, RawValue, symbol::Symbol};
+/// #[derive(DecodeFrom)]
+/// struct Forward<'a> {
+///     to: Symbol<&'a str>,
+///     payload: RawValue<&'a [u8]>,
+/// }
+///
+/// let input = b"<7'Forward5'carol{1'b02+1'a1+}>";
+/// let forward = syrup::from_bytes::<Forward<'_>>(input)?;
+/// // the dictionary isn't sorted, and `02+` has a leading zero, but the bytes are kept as they were
+/// assert_eq!(forward.payload.as_bytes(), b"{1'b02+1'a1+}");
+/// let payload: BTreeMap<Symbol<&str>, u8> = forward.payload.decode().unwrap();
+/// assert_eq!(payload[&Symbol("b")], 2);
+/// # Ok::<(), syrup::de::DecodeBytesError<'_>>(())
+/// ```
+///
+/// A [`TokenTree`] doesn't keep the bytes it was parsed from, so a `RawValue` [`Decode`]d from one
+/// holds its canonical encoding instead. Likewise, [`Encode`] produces a tree from the stored
+/// bytes, which is only written back unchanged if they were canonical.
+///
+/// A value read from bytes may nest no deeper than the default [`ParseLimits`] allow, so that it
+/// can always be parsed again to [`Encode`] it.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct RawValue<B>(B);
+
+impl<B: Bos<[u8]>> RawValue<B> {
+    /// Wrap `bytes`, checking that they hold exactly one value.
+    ///
+    /// ```
+    /// # use syrup::RawValue;
+    /// assert!(RawValue::new(&b"[1+2+]"[..]).is_ok());
+    /// assert!(RawValue::new(&b"[1+2+"[..]).is_err());
+    /// assert!(RawValue::new(&b"1+2+"[..]).is_err());
+    /// ```
+    pub fn new(bytes: B) -> Result<Self, ReadError> {
+        let mut reader = Reader::new(bytes.borrow_or_share());
+        reader.skip_value_within(encode_limits().max_depth)?;
+        if !reader.remaining().is_empty() {
+            return Err(ReadError::Syntax {
+                offset: reader.position(),
+                kind: nom::error::ErrorKind::Eof,
+            });
+        }
+        Ok(Self(bytes))
+    }
+
+    /// The encoded value
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.borrow_or_share()
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> B {
+        self.0
+    }
+
+    /// Decode the value.
+    pub fn decode<'i, T: DecodeFrom<'i>>(&'i self) -> Result<T, DecodeBytesError<'i>> {
+        crate::from_bytes(self.as_bytes())
+    }
+
+    /// Parse the value into a [`TokenTree`] borrowing from `self`, within the default
+    /// [`ParseLimits`].
+    pub fn to_tree(&self) -> Result<TokenTree<&[u8]>, ReadError> {
+        Reader::new(self.as_bytes()).read_tree()
+    }
+}
+
+/// The limits a `RawValue` is parsed within to [`Encode`] it. Only nesting, which is what could
+/// overflow the stack, is limited, and every `RawValue` read from bytes is checked against it.
+fn encode_limits() -> ParseLimits {
+    ParseLimits {
+        max_depth: ParseLimits::default().max_depth,
+        ..ParseLimits::UNLIMITED
+    }
+}
+
+impl<B: Bos<[u8]>> Deref for RawValue<B> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_bytes()
+    }
+}
+
+impl<B: Bos<[u8]>> AsRef<[u8]> for RawValue<B> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<B: Bos<[u8]>> std::fmt::Debug for RawValue<B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RawValue(b\"{}\")", self.as_bytes().escape_ascii())
+    }
+}
+
+impl<'a> From<RawValue<&'a [u8]>> for RawValue<Vec<u8>> {
+    fn from(value: RawValue<&'a [u8]>) -> Self {
+        Self(value.0.to_vec())
+    }
+}
+
+impl<'i, 'o, IData, B> From<&'i TokenTree<IData>> for RawValue<B>
+where
+    IData: BorrowOrShare<'i, 'o, [u8]>,
+    Vec<u8>: Into<B>,
+{
+    fn from(value: &'i TokenTree<IData>) -> Self {
+        Self(value.to_bytes().into_owned().into())
+    }
+}
+
+impl<'i, B> DecodeFrom<'i> for RawValue<B>
+where
+    &'i [u8]: Into<B>,
+{
+    fn decode_from(reader: &mut Reader<'i>) -> Result<Self, ReadError> {
+        let input = reader.remaining();
+        reader.skip_value_within(encode_limits().max_depth)?;
+        let len = input.len() - reader.remaining().len();
+        Ok(Self(input[..len].into()))
+    }
+}
+
+/// Holds the canonical encoding of the tree; see the [type-level docs](RawValue).
+impl<'tree, IData, B> Decode<'tree, IData> for RawValue<B>
+where
+    IData: Bos<[u8]>,
+    Vec<u8>: Into<B>,
+{
+    fn decode(input: &'tree TokenTree<IData>) -> Result<Self, DecodeError> {
+        Ok(Self(input.to_bytes().into_owned().into()))
+    }
+}
+
+/// Only a value [`Decode`]d from (or converted from) a tree nested deeper than the default
+/// [`ParseLimits`] allow can't be parsed again; it's encoded as a byte string of its bytes instead.
+impl<'i, 'o, B, OData> Encode<'i, OData> for RawValue<B>
+where
+    B: BorrowOrShare<'i, 'o, [u8]>,
+    &'o [u8]: Into<OData>,
+{
+    fn encode(&'i self) -> TokenTree<OData> {
+        let bytes: &'o [u8] = self.0.borrow_or_share();
+        match TokenTree::<&'o [u8]>::parse_with_limits::<nom::error::Error<_>>(
+            bytes,
+            &encode_limits(),
+        ) {
+            Ok((_, tree)) => (&tree).into(),
+            Err(_e) => TokenTree::Literal(Literal::Bytes(bytes.into())),
+        }
+    }
+}
+
+/// Writes the stored bytes unchanged.
+impl<B: Bos<[u8]>> EncodeInto<'_> for RawValue<B> {
+    fn encode_into(&self, w: &mut impl std::io::Write) -> std::io::Result<usize> {
+        let bytes = self.as_bytes();
+        w.write_all(bytes)?;
+        Ok(bytes.len())
+    }
+}
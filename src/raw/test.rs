@@ -0,0 +1,134 @@
+use proptest::prelude::*;
+
+use crate::{
+    Decode, DecodeFrom, Encode, RawValue, TokenTree,
+    de::{Limit, LimitExceeded, List, Literal, ReadError, Reader},
+    ser::EncodeIntoExt,
+};
+
+#[derive(Decode, DecodeFrom, Encode, Debug, PartialEq)]
+#[syrup(label = "deliver")]
+struct Deliver {
+    target: u64,
+    payload: RawValue<Vec<u8>>,
+}
+
+#[test]
+fn keeps_the_exact_bytes() {
+    // neither the leading zero nor the unsorted entries are canonical
+    let input = b"{1'b02+1'a1+}";
+    let raw = crate::from_bytes::<RawValue<&[u8]>>(input).unwrap();
+    assert_eq!(raw.as_bytes(), input);
+    assert_eq!(raw.encode_bytes(), input);
+    let owned = RawValue::<Vec<u8>>::from(raw);
+    assert_eq!(owned.as_bytes(), input);
+}
+
+#[test]
+fn reads_only_its_value() {
+    let mut reader = Reader::new(b"[3'foo<1'a[]>2+]");
+    reader.next_event().unwrap();
+    reader.skip_value().unwrap();
+    let raw = reader.read::<RawValue<&[u8]>>().unwrap();
+    assert_eq!(raw.as_bytes(), b"<1'a[]>");
+    assert_eq!(reader.read::<u8>().unwrap(), 2);
+}
+
+#[test]
+fn decodes_lazily() {
+    let raw = RawValue::new(&b"<7'deliver5+<3'pos1+2+>>"[..]).unwrap();
+    let deliver = raw.decode::<Deliver>().unwrap();
+    assert_eq!(deliver.target, 5);
+    assert_eq!(deliver.payload.as_bytes(), b"<3'pos1+2+>");
+    assert!(raw.decode::<Vec<u8>>().is_err());
+    assert_eq!(raw.to_tree().unwrap().to_bytes(), raw.as_bytes());
+}
+
+#[test]
+fn forwards_fields_unchanged() {
+    let input = b"<7'deliver5+[1\"a03+]>";
+    let deliver = crate::from_bytes::<Deliver>(input).unwrap();
+    assert_eq!(deliver.target, 5);
+    assert_eq!(deliver.payload.as_bytes(), b"[1\"a03+]");
+    assert_eq!(
+        deliver.payload.decode::<(String, u8)>().unwrap(),
+        ("a".into(), 3)
+    );
+    // encoding goes through a `TokenTree`, so the payload is written canonically
+    assert_eq!(&*deliver.encode().to_bytes(), b"<7'deliver5+[1\"a3+]>");
+}
+
+#[test]
+fn decodes_from_trees_canonically() {
+    let (_, tree) = TokenTree::<&[u8]>::parse_located(b"<7'deliver5+[1\"a03+]>").unwrap();
+    let deliver = tree.decode::<Deliver>().unwrap();
+    assert_eq!(deliver.payload.as_bytes(), b"[1\"a3+]");
+}
+
+#[test]
+fn rejects_anything_but_one_value() {
+    assert!(matches!(
+        RawValue::new(b"1+2+".to_vec()),
+        Err(ReadError::Syntax { offset: 2, .. })
+    ));
+    assert!(matches!(
+        RawValue::new(&b"[1+"[..]),
+        Err(ReadError::Incomplete { .. })
+    ));
+    assert!(matches!(
+        RawValue::new(&b""[..]),
+        Err(ReadError::Incomplete { offset: 0 })
+    ));
+    assert!(matches!(
+        crate::from_bytes::<RawValue<&[u8]>>(b"]"),
+        Err(crate::de::DecodeBytesError::Read(
+            ReadError::UnexpectedClose { .. }
+        ))
+    ));
+}
+
+#[test]
+fn limits_nesting() {
+    let mut deep = vec![b'['; 129];
+    deep.extend([b']'; 129]);
+    assert!(matches!(
+        RawValue::new(&deep[..]),
+        Err(ReadError::Limit(LimitExceeded {
+            limit: Limit::Depth,
+            offset: 128
+        }))
+    ));
+    assert!(RawValue::new(&deep[1..deep.len() - 1]).is_ok());
+    assert!(matches!(
+        crate::from_bytes::<RawValue<&[u8]>>(&deep),
+        Err(crate::de::DecodeBytesError::Limit(LimitExceeded {
+            limit: Limit::Depth,
+            offset: 128
+        }))
+    ));
+
+    // a tree can be deeper than its bytes could have been read, so it's encoded as bytes instead
+    let tree = (0..129).fold(TokenTree::<Vec<u8>>::List(List::new(vec![])), |tree, _| {
+        TokenTree::List(List::new(vec![tree]))
+    });
+    let raw = tree.decode::<RawValue<Vec<u8>>>().unwrap();
+    assert!(matches!(raw.to_tree(), Err(ReadError::Limit(_))));
+    assert_eq!(
+        Encode::<Vec<u8>>::encode(&raw),
+        TokenTree::Literal(Literal::Bytes(raw.as_bytes().to_vec()))
+    );
+}
+
+proptest! {
+    #[test]
+    fn round_trips_values(value in prop::collection::vec((".{0,8}", any::<i64>()), 0..8)) {
+        let tree: TokenTree<Vec<u8>> = value.encode();
+        let bytes = tree.to_bytes();
+        let raw = crate::from_bytes::<RawValue<&[u8]>>(&bytes).unwrap();
+        prop_assert_eq!(raw.as_bytes(), &*bytes);
+        prop_assert_eq!(&Encode::<Vec<u8>>::encode(&raw), &tree);
+        prop_assert_eq!(raw.decode::<Vec<(String, i64)>>().unwrap(), value);
+        let decoded = tree.decode::<RawValue<Vec<u8>>>().unwrap();
+        prop_assert_eq!(decoded.as_bytes(), &*bytes);
+    }
+}
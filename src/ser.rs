@@ -16,6 +16,16 @@ pub trait EncodeInto<'input> {
 
 mod private {
     /// Exists only to prevent external implementation of certain traits
+    #[expect(
+        clippy::allow_attributes,
+        reason = "whether these lints fire depends on where the crate is built from, so an \
+                  `expect` can go unfulfilled"
+    )]
+    #[allow(
+        dead_code,
+        unreachable_pub,
+        reason = "no `Encode` extension trait is sealed with this yet"
+    )]
     pub trait EncodeSealed<'input, OData> {}
 
     pub trait EncodeIntoSealed<'input> {}
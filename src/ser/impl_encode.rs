@@ -1,13 +1,17 @@
 use std::{
     borrow::Cow,
+    cell::{Cell, RefCell},
     collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque},
+    num::{Saturating, Wrapping},
+    rc::Rc,
+    sync::{Arc, Mutex, PoisonError},
 };
 
 use borrow_or_share::{BorrowOrShare, Bos};
 
 use crate::{
     de::{
-        Dictionary, Int, List, Literal, Set, TokenTree, dict_encoded_entries, encode_into_as_dict,
+        Int, List, Literal, TokenTree, dict_encoded_entries, encode_into_as_dict,
         encode_into_as_set, set_encoded_entries,
     },
     ser::{Encode, EncodeInto, EncodeIntoExt},
@@ -42,6 +46,115 @@ where
     }
 }
 
+/// `None` encodes as `false`, to match the [`Decode`](crate::de::Decode) impl; this means that
+/// `Some(false)` (and `Some(None)`) won't round-trip.
+impl<'i, OData, T: Encode<'i, OData>> Encode<'i, OData> for Option<T> {
+    fn encode(&'i self) -> TokenTree<OData> {
+        match self {
+            Some(value) => value.encode(),
+            None => TokenTree::Literal(Literal::Bool(false)),
+        }
+    }
+}
+
+impl<'i, T: EncodeInto<'i>> EncodeInto<'i> for Option<T> {
+    fn encode_into(&'i self, w: &mut impl std::io::Write) -> std::io::Result<usize> {
+        match self {
+            Some(value) => value.encode_into(w),
+            None => false.encode_into(w),
+        }
+    }
+}
+
+macro_rules! impl_encode_for_pointer {
+    ($($Pointer:ident),+) => {
+        $(
+        impl<'i, OData, T: Encode<'i, OData> + ?Sized> Encode<'i, OData> for $Pointer<T> {
+            #[inline]
+            fn encode(&'i self) -> TokenTree<OData> {
+                (**self).encode()
+            }
+        }
+
+        impl<'i, T: EncodeInto<'i> + ?Sized> EncodeInto<'i> for $Pointer<T> {
+            #[inline]
+            fn encode_into(&'i self, w: &mut impl std::io::Write) -> std::io::Result<usize> {
+                (**self).encode_into(w)
+            }
+        }
+        )+
+    };
+}
+
+impl_encode_for_pointer!(Box, Rc, Arc);
+
+// the contents of these are only borrowed (or copied) for the duration of the call, so the
+// contents must be encodable from any borrow
+
+impl<OData, T: Copy + for<'t> Encode<'t, OData>> Encode<'_, OData> for Cell<T> {
+    fn encode(&self) -> TokenTree<OData> {
+        self.get().encode()
+    }
+}
+
+impl<T: Copy + for<'t> EncodeInto<'t>> EncodeInto<'_> for Cell<T> {
+    fn encode_into(&self, w: &mut impl std::io::Write) -> std::io::Result<usize> {
+        self.get().encode_into(w)
+    }
+}
+
+impl<OData, T: for<'t> Encode<'t, OData>> Encode<'_, OData> for RefCell<T> {
+    /// Panics if the value is currently mutably borrowed.
+    fn encode(&self) -> TokenTree<OData> {
+        self.borrow().encode()
+    }
+}
+
+impl<T: for<'t> EncodeInto<'t>> EncodeInto<'_> for RefCell<T> {
+    /// Panics if the value is currently mutably borrowed.
+    fn encode_into(&self, w: &mut impl std::io::Write) -> std::io::Result<usize> {
+        self.borrow().encode_into(w)
+    }
+}
+
+impl<OData, T: for<'t> Encode<'t, OData>> Encode<'_, OData> for Mutex<T> {
+    /// Blocks until the lock is acquired; a poisoned lock is encoded anyway.
+    fn encode(&self) -> TokenTree<OData> {
+        self.lock().unwrap_or_else(PoisonError::into_inner).encode()
+    }
+}
+
+impl<T: for<'t> EncodeInto<'t>> EncodeInto<'_> for Mutex<T> {
+    /// Blocks until the lock is acquired; a poisoned lock is encoded anyway.
+    fn encode_into(&self, w: &mut impl std::io::Write) -> std::io::Result<usize> {
+        self.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .encode_into(w)
+    }
+}
+
+macro_rules! impl_encode_for_newtype {
+    ($($Newtype:ident),+) => {
+        $(
+        impl<'i, OData, T: Encode<'i, OData>> Encode<'i, OData> for $Newtype<T> {
+            #[inline]
+            fn encode(&'i self) -> TokenTree<OData> {
+                self.0.encode()
+            }
+        }
+
+        impl<'i, T: EncodeInto<'i>> EncodeInto<'i> for $Newtype<T> {
+            #[inline]
+            fn encode_into(&'i self, w: &mut impl std::io::Write) -> std::io::Result<usize> {
+                self.0.encode_into(w)
+            }
+        }
+        )+
+    };
+}
+
+impl_encode_for_newtype!(Wrapping, Saturating);
+
 macro_rules! impl_encode_copy {
     ($Ty:ty, $Id:ident) => {
         impl<'i, OData> Encode<'i, OData> for $Ty {
@@ -165,7 +278,7 @@ impl_encode_list! {T; Vec<T>}
 impl_encode_list! {T; VecDeque<T>}
 impl_encode_list! {T; LinkedList<T>}
 
-impl<'i, OData, T: Encode<'i, OData>> Encode<'i, OData> for Cow<'i, [T]>
+impl<'i, OData, T: Encode<'i, OData>> Encode<'i, OData> for Cow<'_, [T]>
 where
     [T]: std::borrow::ToOwned,
 {
@@ -174,7 +287,7 @@ where
     }
 }
 
-impl<'i, T> EncodeInto<'i> for Cow<'i, [T]>
+impl<'i, T> EncodeInto<'i> for Cow<'_, [T]>
 where
     [T]: std::borrow::ToOwned,
     T: EncodeInto<'i>,
@@ -190,19 +303,18 @@ where
     }
 }
 
-// TODO :: is there a more efficient way to encode to dictionaries & sets?
-
 impl<'i, OData, K, V, S> Encode<'i, OData> for HashMap<K, V, S>
 where
+    OData: Bos<[u8]>,
     K: Encode<'i, OData>,
     V: Encode<'i, OData>,
 {
     fn encode(&'i self) -> TokenTree<OData> {
-        TokenTree::Dictionary(Dictionary::new(
+        TokenTree::Dictionary(
             self.iter()
                 .map(|(key, value)| (key.encode(), value.encode()))
                 .collect(),
-        ))
+        )
     }
 }
 
@@ -222,10 +334,11 @@ where
 
 impl<'i, OData, T, S> Encode<'i, OData> for HashSet<T, S>
 where
+    OData: Bos<[u8]>,
     T: Encode<'i, OData>,
 {
     fn encode(&'i self) -> TokenTree<OData> {
-        TokenTree::Set(Set::new(self.iter().map(<T>::encode).collect()))
+        TokenTree::Set(self.iter().map(<T>::encode).collect())
     }
 }
 
@@ -241,15 +354,16 @@ where
 
 impl<'i, OData, K, V> Encode<'i, OData> for BTreeMap<K, V>
 where
+    OData: Bos<[u8]>,
     K: Encode<'i, OData>,
     V: Encode<'i, OData>,
 {
     fn encode(&'i self) -> TokenTree<OData> {
-        TokenTree::Dictionary(Dictionary::new(
+        TokenTree::Dictionary(
             self.iter()
                 .map(|(key, value)| (key.encode(), value.encode()))
                 .collect(),
-        ))
+        )
     }
 }
 
@@ -269,10 +383,11 @@ where
 
 impl<'i, OData, T> Encode<'i, OData> for BTreeSet<T>
 where
+    OData: Bos<[u8]>,
     T: Encode<'i, OData>,
 {
     fn encode(&'i self) -> TokenTree<OData> {
-        TokenTree::Set(Set::new(self.iter().map(<T>::encode).collect()))
+        TokenTree::Set(self.iter().map(<T>::encode).collect())
     }
 }
 
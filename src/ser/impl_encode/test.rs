@@ -102,7 +102,7 @@ mod int {
                     #[test]
                     fn $test_name(i: $Int) {
                         let mut expected = i.unsigned_abs().to_string().into_bytes();
-                        expected.push(if i.is_positive() { b'+' } else { b'-' });
+                        expected.push(if i.is_negative() { b'-' } else { b'+' });
                         prop_assert_eq!(i.encode_bytes(), expected);
                     }
                 )+
@@ -140,3 +140,83 @@ mod int {
         std::num::NonZeroI128 => encodes_non_zero_i128
     }
 }
+
+mod round_trip {
+    use std::{
+        borrow::Cow,
+        cell::{Cell, RefCell},
+        collections::{LinkedList, VecDeque},
+        num::{Saturating, Wrapping},
+        rc::Rc,
+        sync::{Arc, Mutex},
+    };
+
+    use proptest::{prelude::*, test_runner::TestCaseError};
+
+    use crate::{
+        de::{Decode, TokenTree},
+        ser::{Encode, EncodeInto, EncodeIntoExt},
+    };
+
+    /// Check that `value` encodes the same way through [`Encode`] and [`EncodeInto`], then decode
+    /// it again
+    fn round_trip<T>(value: &T) -> Result<T, TestCaseError>
+    where
+        T: for<'i> Encode<'i, Vec<u8>> + for<'i> EncodeInto<'i> + for<'t> Decode<'t, &'t [u8]>,
+    {
+        let bytes = value.encode_bytes();
+        let tree: TokenTree<Vec<u8>> = value.encode();
+        prop_assert_eq!(&*tree.to_bytes(), bytes.as_slice());
+        let (rem, tree) = TokenTree::<&[u8]>::parse_located(&bytes)
+            .map_err(|e| TestCaseError::fail(e.to_string()))?;
+        prop_assert!(rem.is_empty());
+        T::decode(&tree).map_err(|e| TestCaseError::fail(e.to_string()))
+    }
+
+    proptest! {
+        #[test]
+        fn round_trips_options(value: Option<u32>) {
+            prop_assert_eq!(round_trip(&value)?, value);
+        }
+
+        #[test]
+        fn round_trips_pointers(s: String) {
+            let boxed = Box::new(s.clone());
+            prop_assert_eq!(round_trip(&boxed)?, boxed);
+            let boxed_str: Box<str> = s.as_str().into();
+            prop_assert_eq!(round_trip(&boxed_str)?, boxed_str);
+            let rc = Rc::new(s.clone());
+            prop_assert_eq!(round_trip(&rc)?, rc);
+            let arc: Arc<str> = s.as_str().into();
+            prop_assert_eq!(round_trip(&arc)?, arc);
+        }
+
+        #[test]
+        fn round_trips_cells(i: i64, s: String) {
+            let cell = Cell::new(i);
+            prop_assert_eq!(round_trip(&cell)?, cell);
+            let ref_cell = RefCell::new(s.clone());
+            prop_assert_eq!(round_trip(&ref_cell)?, ref_cell);
+            let mutex = Mutex::new(s.clone());
+            prop_assert_eq!(round_trip(&mutex)?.into_inner().unwrap(), s);
+        }
+
+        #[test]
+        fn round_trips_int_wrappers(i: u16) {
+            prop_assert_eq!(round_trip(&Wrapping(i))?, Wrapping(i));
+            prop_assert_eq!(round_trip(&Saturating(i))?, Saturating(i));
+        }
+
+        #[test]
+        fn round_trips_lists(elements: Vec<i32>) {
+            let boxed: Box<[i32]> = elements.clone().into();
+            prop_assert_eq!(round_trip(&boxed)?, boxed);
+            let deque: VecDeque<i32> = elements.iter().copied().collect();
+            prop_assert_eq!(round_trip(&deque)?, deque);
+            let list: LinkedList<i32> = elements.iter().copied().collect();
+            prop_assert_eq!(round_trip(&list)?, list);
+            let cow: Cow<'_, [i32]> = Cow::Owned(elements);
+            prop_assert_eq!(round_trip(&cow)?, cow);
+        }
+    }
+}
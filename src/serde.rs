@@ -0,0 +1,107 @@
+//! [`serde`](::serde) support, for types that derive `Serialize`/`Deserialize` rather than
+//! [`Encode`](crate::Encode)/[`Decode`](crate::Decode).
+//!
+//! The serde data model maps onto syrup as follows:
+//!
+//! | serde                                   | syrup                                        |
+//! |-----------------------------------------|----------------------------------------------|
+//! | `bool`, integers, `f32`, `f64`          | the matching literal                         |
+//! | `char`, `str`                           | string                                       |
+//! | bytes                                   | byte string                                  |
+//! | `None` / `Some(value)`                  | `f` / `value`, as with [`Option`]'s `Encode` |
+//! | unit                                    | `[]`                                         |
+//! | sequence, tuple                         | list                                         |
+//! | map                                     | dictionary                                   |
+//! | unit, newtype, tuple, or field struct   | a record labelled with the struct's name     |
+//! | enum variant                            | a record labelled with the variant's name    |
+//!
+//! Record labels are symbols, and a struct's fields are the record's elements, in declaration
+//! order. Field names aren't written, so fields are identified by position alone, and skipping
+//! one when serializing (as with `#[serde(skip_serializing_if = "...")]`) is an error. Dictionaries are written with their entries sorted canonically, as in
+//! [`Dictionary::encode`](crate::de::Dictionary::encode).
+//!
+//! ```
+//! #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+//! struct Point {
+//!     x: i32,
+//!     y: i32,
+//! }
+//!
+//! let bytes = syrup::serde::to_bytes(&Point { x: 1, y: -2 })?;
+//! assert_eq!(bytes, b"<5'Point1+2->");
+//! let point: Point = syrup::serde::from_bytes(&bytes).unwrap();
+//! assert_eq!(point, Point { x: 1, y: -2 });
+//! # Ok::<(), syrup::serde::EncodeError>(())
+//! ```
+
+use borrow_or_share::BorrowOrShare;
+
+use crate::de::{
+    DecodeBytesError, DecodeError, LimitedParseError, ParseError, ParseLimits, TokenTree,
+};
+
+mod de;
+pub use de::*;
+
+mod ser;
+pub use ser::*;
+
+#[cfg(test)]
+mod test;
+
+/// Convert `value` to a [`TokenTree`].
+pub fn to_token_tree<T>(value: &T) -> Result<TokenTree<Vec<u8>>, EncodeError>
+where
+    T: ::serde::Serialize + ?Sized,
+{
+    value.serialize(Serializer)
+}
+
+/// Encode `value` as syrup data.
+pub fn to_bytes<T>(value: &T) -> Result<Vec<u8>, EncodeError>
+where
+    T: ::serde::Serialize + ?Sized,
+{
+    Ok(to_token_tree(value)?.to_bytes().into_owned())
+}
+
+/// Write `value`, as syrup data, to the given writer, returning the number of bytes written.
+pub fn to_writer<T>(w: &mut impl std::io::Write, value: &T) -> Result<usize, EncodeError>
+where
+    T: ::serde::Serialize + ?Sized,
+{
+    Ok(to_token_tree(value)?.write_bytes(w)?)
+}
+
+/// Deserialize a `T` from a [`TokenTree`], borrowing strings and bytes from it where possible.
+pub fn from_token_tree<'t, 'de, T, Data>(tree: &'t TokenTree<Data>) -> Result<T, DecodeError>
+where
+    T: ::serde::Deserialize<'de>,
+    Data: BorrowOrShare<'t, 'de, [u8]>,
+{
+    T::deserialize(Deserializer::new(tree))
+}
+
+/// Deserialize a `T` from syrup data, borrowing strings and bytes from `input` where possible.
+///
+/// `input` must hold exactly one value; anything after it is an error. It's parsed within the
+/// default [`ParseLimits`], so this is suitable for untrusted input.
+pub fn from_bytes<'de, T>(input: &'de [u8]) -> Result<T, DecodeBytesError<'de>>
+where
+    T: ::serde::Deserialize<'de>,
+{
+    let (rem, tree) = TokenTree::<&'de [u8]>::parse_with_limits::<ParseError<'de>>(
+        input,
+        &ParseLimits::default(),
+    )
+    .map_err(|e| match e {
+        LimitedParseError::Parse(e) => LimitedParseError::Parse(e.map(|e| e.with_origin(input))),
+        e => e,
+    })?;
+    if !rem.is_empty() {
+        return Err(DecodeBytesError::TrailingInput {
+            offset: input.len() - rem.len(),
+        });
+    }
+    Ok(from_token_tree(&tree)?)
+}
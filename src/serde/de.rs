@@ -0,0 +1,483 @@
+use borrow_or_share::{BorrowOrShare, Bos};
+use serde::de::{self, DeserializeSeed, Unexpected, Visitor};
+
+use crate::de::{
+    Decode, DecodeError, DecodeErrorKind, Dictionary, List, Literal, Record, Set, SyrupKind,
+    TokenTree, str_from_utf8,
+};
+
+impl de::Error for DecodeError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        DecodeErrorKind::Custom(msg.to_string()).into()
+    }
+
+    fn unknown_variant(variant: &str, expected: &'static [&'static str]) -> Self {
+        DecodeError::unexpected_label(expected, variant.as_bytes())
+    }
+
+    fn unknown_field(field: &str, expected: &'static [&'static str]) -> Self {
+        DecodeError::unknown_key(expected, field.as_bytes())
+    }
+}
+
+/// A [`serde::Deserializer`](de::Deserializer) over a borrowed [`TokenTree`]; see the
+/// [module documentation](crate::serde) for how values are represented.
+///
+/// Strings and bytes are borrowed for `'de`, which is the lifetime of the tree's data if it's
+/// borrowed (as from [`TokenTree::parse`]), or of the tree itself if it owns its data.
+pub struct Deserializer<'t, Data> {
+    input: &'t TokenTree<Data>,
+}
+
+impl<'t, Data> Deserializer<'t, Data> {
+    #[inline]
+    pub const fn new(input: &'t TokenTree<Data>) -> Self {
+        Self { input }
+    }
+}
+
+impl<'t, Data: Bos<[u8]>> Deserializer<'t, Data> {
+    fn decode<T: Decode<'t, Data>>(&self) -> Result<T, DecodeError> {
+        T::decode(self.input)
+    }
+
+    /// The elements of a record labelled `name`.
+    fn record(&self, name: &'static str) -> Result<&'t [TokenTree<Data>], DecodeError> {
+        match self.input {
+            TokenTree::Record(record) if is_label(&record.label, name) => Ok(&record.elements),
+            _ => Err(DecodeError::unexpected(
+                SyrupKind::Record { label: Some(name) },
+                self.input,
+            )),
+        }
+    }
+}
+
+fn is_label<Data: Bos<[u8]>>(label: &TokenTree<Data>, name: &str) -> bool {
+    matches!(label, TokenTree::Literal(Literal::Symbol(sym)) if sym.borrow_or_share() == name.as_bytes())
+}
+
+macro_rules! deserialize_decodable {
+    ($($deserialize:ident => $visit:ident),+$(,)?) => {
+        $(
+        fn $deserialize<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            visitor.$visit(self.decode()?)
+        }
+        )+
+    };
+}
+
+impl<'t, 'de, Data> de::Deserializer<'de> for Deserializer<'t, Data>
+where
+    Data: BorrowOrShare<'t, 'de, [u8]>,
+{
+    type Error = DecodeError;
+
+    /// Records are visited as a sequence of their elements.
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.input {
+            TokenTree::Dictionary(dict) => visit_dictionary(dict, visitor),
+            TokenTree::List(List { elements }) => visit_elements(elements, None, visitor),
+            TokenTree::Record(record) => visit_elements(&record.elements, None, visitor),
+            TokenTree::Set(set) => visit_set(set, visitor),
+            TokenTree::Literal(Literal::Bool(b)) => visitor.visit_bool(*b),
+            TokenTree::Literal(Literal::F32(f)) => visitor.visit_f32(*f),
+            TokenTree::Literal(Literal::F64(f)) => visitor.visit_f64(*f),
+            TokenTree::Literal(Literal::Int(int)) => {
+                if let Ok(i) = i64::try_from(int) {
+                    visitor.visit_i64(i)
+                } else if let Ok(u) = u64::try_from(int) {
+                    visitor.visit_u64(u)
+                } else if let Ok(i) = i128::try_from(int) {
+                    visitor.visit_i128(i)
+                } else {
+                    visitor.visit_u128(self.decode()?)
+                }
+            }
+            TokenTree::Literal(Literal::Bytes(bytes)) => {
+                visitor.visit_borrowed_bytes(bytes.borrow_or_share())
+            }
+            TokenTree::Literal(Literal::String(s) | Literal::Symbol(s)) => {
+                visitor.visit_borrowed_str(str_from_utf8(s.borrow_or_share())?)
+            }
+        }
+    }
+
+    deserialize_decodable! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let s = <&'de str>::decode(self.input)?;
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => visitor.visit_char(c),
+            _ => Err(de::Error::invalid_value(Unexpected::Str(s), &visitor)),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_borrowed_str(<&'de str>::decode(self.input)?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.input {
+            TokenTree::Literal(Literal::Bytes(bytes)) => {
+                visitor.visit_borrowed_bytes(bytes.borrow_or_share())
+            }
+            _ => Err(DecodeError::unexpected(
+                SyrupKind::Bytes { length: None },
+                self.input,
+            )),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.input {
+            TokenTree::Literal(Literal::Bool(false)) => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.decode::<()>()?;
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let elements = self.record(name)?;
+        if !elements.is_empty() {
+            return Err(DecodeError::extra_elements(0, elements.len()).at_label(name));
+        }
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.record(name)? {
+            [value] => visitor
+                .visit_newtype_struct(Deserializer::new(value))
+                .map_err(|e| e.at_index(0).at_label(name)),
+            [] => Err(DecodeError::missing(SyrupKind::Unknown("newtype field")).at_label(name)),
+            elements => Err(DecodeError::extra_elements(1, elements.len()).at_label(name)),
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.input {
+            TokenTree::List(List { elements }) => visit_elements(elements, None, visitor),
+            TokenTree::Set(set) => visit_set(set, visitor),
+            _ => Err(DecodeError::unexpected(
+                SyrupKind::List { length: None },
+                self.input,
+            )),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.input {
+            TokenTree::List(List { elements }) => visit_elements(elements, None, visitor),
+            _ => Err(DecodeError::unexpected(
+                SyrupKind::List { length: Some(len) },
+                self.input,
+            )),
+        }
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visit_elements(self.record(name)?, None, visitor).map_err(|e| e.at_label(name))
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.input {
+            TokenTree::Dictionary(dict) => visit_dictionary(dict, visitor),
+            _ => Err(DecodeError::unexpected(SyrupKind::Dictionary, self.input)),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visit_elements(self.record(name)?, Some(fields), visitor).map_err(|e| e.at_label(name))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.input {
+            TokenTree::Record(record) => visitor.visit_enum(EnumAccess { record }),
+            _ => Err(DecodeError::unexpected(
+                SyrupKind::Record {
+                    label: variants.first().copied(),
+                },
+                self.input,
+            )),
+        }
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.input {
+            TokenTree::Literal(Literal::String(s) | Literal::Symbol(s)) => {
+                visitor.visit_borrowed_str(str_from_utf8(s.borrow_or_share())?)
+            }
+            _ => Err(DecodeError::unexpected(SyrupKind::Symbol(None), self.input)),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+}
+
+/// Visit `elements` as a sequence, failing if the visitor doesn't consume them all.
+///
+/// Errors are located by field name if `fields` are given, or by index otherwise.
+fn visit_elements<'t, 'de, Data, V>(
+    elements: &'t [TokenTree<Data>],
+    fields: Option<&'static [&'static str]>,
+    visitor: V,
+) -> Result<V::Value, DecodeError>
+where
+    Data: BorrowOrShare<'t, 'de, [u8]>,
+    V: Visitor<'de>,
+{
+    let mut seq = SeqAccess {
+        elements: elements.iter(),
+        index: 0,
+        fields,
+    };
+    let value = visitor.visit_seq(&mut seq)?;
+    match seq.elements.len() {
+        0 => Ok(value),
+        rest => Err(DecodeError::extra_elements(seq.index, seq.index + rest)),
+    }
+}
+
+fn visit_set<'t, 'de, Data, V>(set: &'t Set<Data>, visitor: V) -> Result<V::Value, DecodeError>
+where
+    Data: BorrowOrShare<'t, 'de, [u8]>,
+    V: Visitor<'de>,
+{
+    let mut seq = SeqAccess {
+        elements: set.into_iter(),
+        index: 0,
+        fields: None,
+    };
+    visitor.visit_seq(&mut seq)
+}
+
+fn visit_dictionary<'t, 'de, Data, V>(
+    dict: &'t Dictionary<Data>,
+    visitor: V,
+) -> Result<V::Value, DecodeError>
+where
+    Data: BorrowOrShare<'t, 'de, [u8]>,
+    V: Visitor<'de>,
+{
+    visitor.visit_map(MapAccess {
+        entries: dict.into_iter(),
+        value: None,
+    })
+}
+
+struct SeqAccess<'t, Data> {
+    elements: std::slice::Iter<'t, TokenTree<Data>>,
+    index: usize,
+    fields: Option<&'static [&'static str]>,
+}
+
+impl<'t, 'de, Data> de::SeqAccess<'de> for SeqAccess<'t, Data>
+where
+    Data: BorrowOrShare<'t, 'de, [u8]>,
+{
+    type Error = DecodeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        let Some(element) = self.elements.next() else {
+            return Ok(None);
+        };
+        let index = self.index;
+        self.index += 1;
+        seed.deserialize(Deserializer::new(element))
+            .map(Some)
+            .map_err(|e| match self.fields.and_then(|fields| fields.get(index)) {
+                Some(&field) => e.at_field(field),
+                None => e.at_index(index),
+            })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.elements.len())
+    }
+}
+
+struct MapAccess<'t, Data> {
+    entries: std::slice::Iter<'t, (TokenTree<Data>, TokenTree<Data>)>,
+    /// The entry whose key was just visited
+    value: Option<&'t (TokenTree<Data>, TokenTree<Data>)>,
+}
+
+impl<'t, 'de, Data> de::MapAccess<'de> for MapAccess<'t, Data>
+where
+    Data: BorrowOrShare<'t, 'de, [u8]>,
+{
+    type Error = DecodeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some(entry) = self.entries.next() else {
+            return Ok(None);
+        };
+        self.value = Some(entry);
+        let (key, _) = entry;
+        seed.deserialize(Deserializer::new(key))
+            .map(Some)
+            .map_err(|e| e.at_key(key))
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let Some((key, value)) = self.value.take() else {
+            return Err(de::Error::custom("map value visited before its key"));
+        };
+        seed.deserialize(Deserializer::new(value))
+            .map_err(|e| e.at_key(key))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+struct EnumAccess<'t, Data> {
+    record: &'t Record<Data>,
+}
+
+impl<'t, 'de, Data> de::EnumAccess<'de> for EnumAccess<'t, Data>
+where
+    Data: BorrowOrShare<'t, 'de, [u8]>,
+{
+    type Error = DecodeError;
+    type Variant = VariantAccess<'t, Data>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let variant = seed.deserialize(Deserializer::new(&self.record.label))?;
+        Ok((
+            variant,
+            VariantAccess {
+                record: self.record,
+            },
+        ))
+    }
+}
+
+struct VariantAccess<'t, Data> {
+    record: &'t Record<Data>,
+}
+
+impl<Data: Bos<[u8]>> VariantAccess<'_, Data> {
+    /// Locate `error` within this variant's record.
+    fn within(&self, error: DecodeError) -> DecodeError {
+        match &self.record.label {
+            TokenTree::Literal(Literal::Symbol(label)) => {
+                error.at_label(String::from_utf8_lossy(label.borrow_or_share()).into_owned())
+            }
+            _ => error,
+        }
+    }
+}
+
+impl<'t, 'de, Data> de::VariantAccess<'de> for VariantAccess<'t, Data>
+where
+    Data: BorrowOrShare<'t, 'de, [u8]>,
+{
+    type Error = DecodeError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        match self.record.elements.len() {
+            0 => Ok(()),
+            found => Err(self.within(DecodeError::extra_elements(0, found))),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        match self.record.elements.as_slice() {
+            [value] => seed
+                .deserialize(Deserializer::new(value))
+                .map_err(|e| self.within(e.at_index(0))),
+            [] => Err(self.within(DecodeError::missing(SyrupKind::Unknown("newtype field")))),
+            elements => Err(self.within(DecodeError::extra_elements(1, elements.len()))),
+        }
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visit_elements(&self.record.elements, None, visitor).map_err(|e| self.within(e))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visit_elements(&self.record.elements, Some(fields), visitor).map_err(|e| self.within(e))
+    }
+}
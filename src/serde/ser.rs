@@ -0,0 +1,372 @@
+use serde::ser::{self, Serialize};
+
+use crate::{
+    Encode,
+    de::{Dictionary, List, Literal, Record, TokenTree},
+};
+
+/// An error from serializing a value with [`Serializer`].
+#[derive(Debug, thiserror::Error)]
+pub enum EncodeError {
+    /// A message from a `Serialize` implementation
+    #[error("{0}")]
+    Custom(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+impl EncodeError {
+    /// Fields are identified only by their position in the record, so none can be left out
+    fn skipped_field(key: &'static str) -> Self {
+        Self::Custom(format!(
+            "field `{key}` can't be skipped, as fields are identified by position"
+        ))
+    }
+}
+
+impl ser::Error for EncodeError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+/// A [`serde::Serializer`](ser::Serializer) that converts values to [`TokenTree`]s; see the
+/// [module documentation](crate::serde) for how values are represented.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Serializer;
+
+fn label(name: &'static str) -> TokenTree<Vec<u8>> {
+    TokenTree::Literal(Literal::Symbol(name.as_bytes().to_vec()))
+}
+
+fn record(name: &'static str, elements: Vec<TokenTree<Vec<u8>>>) -> TokenTree<Vec<u8>> {
+    TokenTree::Record(Box::new(Record::new(label(name), elements)))
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = TokenTree<Vec<u8>>;
+    type Error = EncodeError;
+
+    type SerializeSeq = SerializeElements;
+    type SerializeTuple = SerializeElements;
+    type SerializeTupleStruct = SerializeElements;
+    type SerializeTupleVariant = SerializeElements;
+    type SerializeMap = SerializeDictionary;
+    type SerializeStruct = SerializeElements;
+    type SerializeStructVariant = SerializeElements;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        Ok(v.encode())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        Ok(v.encode())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        Ok(v.encode())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        Ok(v.encode())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        Ok(v.encode())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        Ok(v.encode())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        Ok(v.encode())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        Ok(v.encode())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        Ok(v.encode())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        Ok(v.encode())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        Ok(v.encode())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        Ok(TokenTree::Literal(Literal::F32(v)))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        Ok(TokenTree::Literal(Literal::F64(v)))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        Ok(TokenTree::Literal(Literal::String(v.as_bytes().to_vec())))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        Ok(TokenTree::Literal(Literal::Bytes(v.to_vec())))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        self.serialize_bool(false)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Ok(TokenTree::List(List::new(Vec::new())))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(record(name, Vec::new()))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Ok(record(variant, Vec::new()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        Ok(record(name, vec![value.serialize(self)?]))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        Ok(record(variant, vec![value.serialize(self)?]))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(SerializeElements::new(None, len.unwrap_or(0)))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Ok(SerializeElements::new(None, len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Ok(SerializeElements::new(Some(name), len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(SerializeElements::new(Some(variant), len))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(SerializeDictionary {
+            entries: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(SerializeElements::new(Some(name), len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(SerializeElements::new(Some(variant), len))
+    }
+}
+
+/// Collects the elements of a list, or of a record if it has a label.
+#[derive(Debug)]
+pub struct SerializeElements {
+    label: Option<&'static str>,
+    elements: Vec<TokenTree<Vec<u8>>>,
+}
+
+impl SerializeElements {
+    fn new(label: Option<&'static str>, len: usize) -> Self {
+        Self {
+            label,
+            elements: Vec::with_capacity(len),
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EncodeError> {
+        self.elements.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> TokenTree<Vec<u8>> {
+        match self.label {
+            Some(name) => record(name, self.elements),
+            None => TokenTree::List(List::new(self.elements)),
+        }
+    }
+}
+
+impl ser::SerializeSeq for SerializeElements {
+    type Ok = TokenTree<Vec<u8>>;
+    type Error = EncodeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTuple for SerializeElements {
+    type Ok = TokenTree<Vec<u8>>;
+    type Error = EncodeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeElements {
+    type Ok = TokenTree<Vec<u8>>;
+    type Error = EncodeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeElements {
+    type Ok = TokenTree<Vec<u8>>;
+    type Error = EncodeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeStruct for SerializeElements {
+    type Ok = TokenTree<Vec<u8>>;
+    type Error = EncodeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+        Err(EncodeError::skipped_field(key))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeElements {
+    type Ok = TokenTree<Vec<u8>>;
+    type Error = EncodeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+        Err(EncodeError::skipped_field(key))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.finish())
+    }
+}
+
+type Entry = (TokenTree<Vec<u8>>, TokenTree<Vec<u8>>);
+
+/// Collects the entries of a dictionary.
+///
+/// Entries are kept in the order they were serialized until the end, when [`Dictionary::new`]
+/// sorts them, keeping the first of any duplicate keys.
+#[derive(Debug)]
+pub struct SerializeDictionary {
+    entries: Vec<Entry>,
+    /// The key of an entry whose value hasn't been serialized yet
+    key: Option<TokenTree<Vec<u8>>>,
+}
+
+impl ser::SerializeMap for SerializeDictionary {
+    type Ok = TokenTree<Vec<u8>>;
+    type Error = EncodeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+        self.key = Some(key.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let Some(key) = self.key.take() else {
+            return Err(ser::Error::custom("map value serialized before its key"));
+        };
+        self.entries.push((key, value.serialize(Serializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(TokenTree::Dictionary(Dictionary::new(self.entries)))
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use proptest::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    de::{DecodeBytesError, DecodeErrorKind, Limit, LimitExceeded},
+    ser::EncodeIntoExt,
+    serde::{from_bytes, from_token_tree, to_bytes, to_token_tree, to_writer},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Unit;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Meters(f64);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Pair(u8, String);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Empty,
+    Circle(u32),
+    Line(Point, Point),
+    Rect { width: u32, height: u32 },
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Borrowed<'a> {
+    name: &'a str,
+    #[serde(with = "bytes")]
+    data: &'a [u8],
+}
+
+/// Serialize a byte slice with `serialize_bytes`, rather than as a sequence of `u8`s
+mod bytes {
+    use serde::Deserialize;
+
+    pub(super) fn serialize<S: serde::Serializer>(v: &[u8], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_bytes(v)
+    }
+
+    pub(super) fn deserialize<'de, D: serde::Deserializer<'de>>(
+        d: D,
+    ) -> Result<&'de [u8], D::Error> {
+        <&'de [u8]>::deserialize(d)
+    }
+}
+
+#[derive(Serialize)]
+struct BorrowedOut<'a> {
+    name: &'a str,
+    #[serde(with = "bytes")]
+    data: &'a [u8],
+}
+
+fn assert_bytes<T: Serialize + ?Sized>(value: &T, expected: &[u8]) {
+    let bytes = to_bytes(value).unwrap();
+    assert_eq!(
+        bytes,
+        expected,
+        "{} != {}",
+        bytes.escape_ascii(),
+        expected.escape_ascii()
+    );
+}
+
+#[test]
+fn serializes_structs_as_records() {
+    assert_bytes(&Point { x: 1, y: -2 }, b"<5'Point1+2->");
+    assert_bytes(&Unit, b"<4'Unit>");
+    assert_bytes(&Pair(3, "hi".to_owned()), b"<4'Pair3+2\"hi>");
+    assert_bytes(&Meters(0.0), b"<6'MetersD\0\0\0\0\0\0\0\0>");
+}
+
+#[test]
+fn serializes_enums_as_records() {
+    assert_bytes(&Shape::Empty, b"<5'Empty>");
+    assert_bytes(&Shape::Circle(4), b"<6'Circle4+>");
+    assert_bytes(
+        &Shape::Rect {
+            width: 2,
+            height: 3,
+        },
+        b"<4'Rect2+3+>",
+    );
+    assert_bytes(
+        &Shape::Line(Point { x: 2, y: 2 }, Point { x: 1, y: 1 }),
+        b"<4'Line<5'Point2+2+><5'Point1+1+>>",
+    );
+}
+
+#[test]
+fn serializes_literals() {
+    assert_bytes(&true, b"t");
+    assert_bytes(&Option::<u8>::None, b"f");
+    assert_bytes(&Some(5u8), b"5+");
+    assert_bytes(&'x', b"1\"x");
+    assert_bytes(&(), b"[]");
+    assert_bytes(&(1u8, "a"), b"[1+1\"a]");
+    assert_bytes(
+        &BorrowedOut {
+            name: "n",
+            data: b"\x01\x02",
+        },
+        b"<11'BorrowedOut1\"n2:\x01\x02>",
+    );
+}
+
+#[test]
+fn sorts_maps_canonically() {
+    let map = HashMap::from([("zz", 1u8), ("a", 2), ("m", 3)]);
+    assert_bytes(&map, b"{1\"a2+1\"m3+2\"zz1+}");
+
+    let mut bytes = Vec::new();
+    let written = to_writer(&mut bytes, &map).unwrap();
+    assert_eq!(written, bytes.len());
+    assert_eq!(bytes, to_bytes(&map).unwrap());
+}
+
+#[test]
+fn round_trips_derived_types() {
+    for shape in [
+        Shape::Empty,
+        Shape::Circle(7),
+        Shape::Line(Point { x: -1, y: 2 }, Point { x: 3, y: -4 }),
+        Shape::Rect {
+            width: 5,
+            height: 6,
+        },
+    ] {
+        let bytes = to_bytes(&shape).unwrap();
+        assert_eq!(from_bytes::<Shape>(&bytes).unwrap(), shape);
+        let tree = to_token_tree(&shape).unwrap();
+        assert_eq!(from_token_tree::<Shape, _>(&tree).unwrap(), shape);
+    }
+    assert_eq!(from_bytes::<Unit>(b"<4'Unit>").unwrap(), Unit);
+    assert_eq!(
+        from_bytes::<Meters>(&to_bytes(&Meters(1.5)).unwrap()).unwrap(),
+        Meters(1.5)
+    );
+}
+
+#[test]
+fn deserializes_sets_as_sequences() {
+    let set: HashSet<u8> = from_bytes(b"#1+2+3+$").unwrap();
+    assert_eq!(set, HashSet::from([1, 2, 3]));
+}
+
+#[test]
+fn borrows_from_input() {
+    let input = b"<8'Borrowed4\"name3:\x00\x01\x02>";
+    let value: Borrowed<'_> = from_bytes(input).unwrap();
+    assert_eq!(
+        value,
+        Borrowed {
+            name: "name",
+            data: b"\x00\x01\x02"
+        }
+    );
+    assert!(input.as_ptr_range().contains(&value.name.as_ptr()));
+}
+
+#[test]
+fn reports_paths() {
+    let err = from_bytes::<Shape>(b"<4'Line<5'Point2+2+><5'Point1+1\"x>>").unwrap_err();
+    let DecodeBytesError::Decode(err) = err else {
+        panic!("{err:?}")
+    };
+    assert!(matches!(err.kind(), DecodeErrorKind::Unexpected { .. }));
+    assert_eq!(
+        err.to_string(),
+        "Line[1].<Point>.y: expected: i4, found: string"
+    );
+
+    let err = from_bytes::<Point>(b"<5'Point1+2+3+>").unwrap_err();
+    assert!(
+        matches!(&err, DecodeBytesError::Decode(e) if matches!(e.kind(), DecodeErrorKind::ExtraElements { expected: 2, found: 3 })),
+        "{err:?}"
+    );
+
+    let err = from_bytes::<Shape>(b"<6'Square>").unwrap_err();
+    assert!(
+        matches!(&err, DecodeBytesError::Decode(e) if matches!(e.kind(), DecodeErrorKind::UnexpectedLabel { found, .. } if found == "Square")),
+        "{err:?}"
+    );
+
+    let err = from_bytes::<BTreeMap<String, u8>>(b"{1\"a1-}").unwrap_err();
+    assert!(
+        err.to_string().starts_with("{\"a\"}: failed conversion"),
+        "{err}"
+    );
+}
+
+#[test]
+fn rejects_trailing_input() {
+    assert!(matches!(
+        from_bytes::<u8>(b"1+2+"),
+        Err(DecodeBytesError::TrailingInput { offset: 2 })
+    ));
+}
+
+#[test]
+fn rejects_skipped_fields() {
+    #[derive(Serialize)]
+    struct Sparse {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        a: Option<u8>,
+        b: u8,
+    }
+
+    let err = to_bytes(&Sparse { a: None, b: 1 }).unwrap_err();
+    assert!(
+        err.to_string().contains("field `a` can't be skipped"),
+        "{err}"
+    );
+    assert_eq!(
+        to_bytes(&Sparse { a: Some(0), b: 1 }).unwrap(),
+        b"<6'Sparse0+1+>"
+    );
+}
+
+#[test]
+fn limits_nesting() {
+    let input = vec![b'['; 1 << 20];
+    assert!(matches!(
+        from_bytes::<Vec<u8>>(&input),
+        Err(DecodeBytesError::Limit(LimitExceeded {
+            limit: Limit::Depth,
+            offset: 128
+        }))
+    ));
+}
+
+proptest! {
+    #[test]
+    fn maps_match_encode(map: HashMap<String, Vec<i64>>) {
+        prop_assert_eq!(to_bytes(&map).unwrap(), map.encode_bytes());
+    }
+
+    #[test]
+    fn round_trips_maps(map: BTreeMap<String, (u64, bool, Option<i16>)>) {
+        let bytes = to_bytes(&map).unwrap();
+        prop_assert_eq!(from_bytes::<BTreeMap<String, (u64, bool, Option<i16>)>>(&bytes).unwrap(), map);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::de::{DecodeBytesError, DecodeError, Literal, SyrupKind, TokenTree};
+use crate::de::{DecodeBytesError, DecodeError, Literal, SyrupKind, TokenTree, str_from_utf8};
 
 use borrow_or_share::BorrowOrShare;
 
@@ -36,9 +36,7 @@ where
     &'output str: Into<Output>,
 {
     match input {
-        TokenTree::Literal(Literal::Symbol(s)) => {
-            Ok(std::str::from_utf8(s.borrow_or_share())?.into())
-        }
+        TokenTree::Literal(Literal::Symbol(s)) => Ok(str_from_utf8(s.borrow_or_share())?.into()),
         _ => Err(DecodeError::unexpected(SyrupKind::Symbol(None), input)),
     }
 }
@@ -49,7 +47,7 @@ pub fn decode_bytes<'i, 'err, OStr>(
 where
     &'i str: Into<OStr>,
 {
-    let (rem, tree) = TokenTree::<&'i [u8]>::parse::<nom::error::Error<&'i [u8]>>(input)?;
+    let (rem, tree) = TokenTree::<&'i [u8]>::parse_located(input)?;
     let decoded = decode::<&'i [u8], &'i str>(&tree)?;
     Ok((rem, decoded.into()))
 }
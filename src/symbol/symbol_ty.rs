@@ -4,7 +4,7 @@ use borrow_or_share::{BorrowOrShare, Bos};
 
 use crate::{
     Decode, Encode,
-    de::{DecodeError, Literal, SyrupKind, TokenTree},
+    de::{DecodeError, Literal, SyrupKind, TokenTree, str_from_utf8},
 };
 
 /// A wrapper around [`Cow<'_, str>`] that encodes/decodes as a symbol literal
@@ -117,12 +117,9 @@ where
     fn decode(input: &'i TokenTree<IData>) -> Result<Self, DecodeError> {
         match input {
             TokenTree::Literal(Literal::Symbol(s)) => {
-                Ok(Self(std::str::from_utf8(s.borrow_or_share())?.into()))
+                Ok(Self(str_from_utf8(s.borrow_or_share())?.into()))
             }
-            _ => Err(DecodeError::Unexpected {
-                expected: SyrupKind::Symbol(None),
-                found: input.kind(),
-            }),
+            _ => Err(DecodeError::unexpected(SyrupKind::Symbol(None), input)),
         }
     }
 }
@@ -0,0 +1,870 @@
+//! Tests for `syrup_derive`, which can't be tested from within its own crate.
+
+use crate::{Decode, Encode, de::DecodeErrorKind, decode_bytes};
+
+fn encoded<'i, T: Encode<'i, Vec<u8>>>(value: &'i T) -> Vec<u8> {
+    value.encode().to_bytes().into_owned()
+}
+
+mod derive_enum {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    enum Op {
+        #[syrup(label = "op:deliver")]
+        Deliver {
+            target: u64,
+            args: Vec<String>,
+        },
+        Abort(String),
+        #[syrup(symbol)]
+        Ping,
+        Pong,
+    }
+
+    #[test]
+    fn encodes_variants_as_records() {
+        assert_eq!(
+            encoded(&Op::Deliver {
+                target: 3,
+                args: vec!["hi".to_owned()]
+            }),
+            b"<10'op:deliver3+[2\"hi]>"
+        );
+        assert_eq!(encoded(&Op::Abort("oops".to_owned())), b"<5'Abort4\"oops>");
+        assert_eq!(encoded(&Op::Pong), b"<4'Pong>");
+    }
+
+    #[test]
+    fn encodes_symbol_variants_as_symbols() {
+        assert_eq!(encoded(&Op::Ping), b"4'Ping");
+    }
+
+    #[test]
+    fn round_trips_variants() {
+        for op in [
+            Op::Deliver {
+                target: 12,
+                args: vec!["a".to_owned(), "b".to_owned()],
+            },
+            Op::Abort("reason".to_owned()),
+            Op::Ping,
+            Op::Pong,
+        ] {
+            let bytes = encoded(&op);
+            let (rem, decoded) = decode_bytes!(&bytes => Op).expect("encoded value should decode");
+            assert!(rem.is_empty());
+            assert_eq!(decoded, op);
+        }
+    }
+
+    #[test]
+    fn decodes_unit_variants_from_either_form() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(decode_bytes!(b"<4'Ping>" => Op)?.1, Op::Ping);
+        assert_eq!(decode_bytes!(b"4'Pong" => Op)?.1, Op::Pong);
+        Ok(())
+    }
+
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    enum Name<'a> {
+        #[syrup(label = "name")]
+        Borrowed(crate::symbol::Symbol<&'a str>),
+        Anonymous,
+    }
+
+    #[test]
+    fn decodes_borrowing_variants() {
+        let (_, tree) =
+            crate::TokenTree::<&[u8]>::parse::<nom::error::Error<_>>(b"<4'name5'alice>")
+                .expect("should parse");
+        let name = tree.decode::<Name<'_>>().expect("should decode");
+        assert_eq!(name, Name::Borrowed(crate::symbol::Symbol("alice")));
+        assert_eq!(encoded(&name), b"<4'name5'alice>");
+    }
+
+    #[test]
+    fn rejects_unknown_labels() {
+        let err = decode_bytes!(b"<4'Nope>" => Op).unwrap_err();
+        match &err {
+            crate::de::DecodeBytesError::Decode(e)
+                if let DecodeErrorKind::UnexpectedLabel { expected, found } = e.kind() =>
+            {
+                assert_eq!(*expected, ["op:deliver", "Abort", "Ping", "Pong"]);
+                assert_eq!(found, "Nope");
+            }
+            _ => panic!("expected an unexpected label error, got {err:?}"),
+        }
+    }
+}
+
+mod derive_container {
+    use super::*;
+    use crate::TokenTree;
+
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    #[syrup(transparent)]
+    struct ExportId(u64);
+
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    #[syrup(transparent)]
+    struct Nickname {
+        name: String,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    #[syrup(label = "desc:export")]
+    struct DescExport(ExportId);
+
+    /// Encodes as a string of its decimal digits
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    #[syrup(with = digits, encode_where = { __OData: From<Vec<u8>> })]
+    struct Port(u16);
+
+    mod digits {
+        use borrow_or_share::Bos;
+
+        use crate::{
+            TokenTree,
+            de::{DecodeError, Literal},
+        };
+
+        pub(super) fn encode<OData: From<Vec<u8>>>(port: &super::Port) -> TokenTree<OData> {
+            TokenTree::Literal(Literal::String(port.0.to_string().into_bytes().into()))
+        }
+
+        pub(super) fn decode<IData: Bos<[u8]>>(
+            input: &TokenTree<IData>,
+        ) -> Result<super::Port, DecodeError> {
+            let digits: String = input.decode()?;
+            digits
+                .parse()
+                .map(super::Port)
+                .map_err(|e: std::num::ParseIntError| DecodeError::int::<u16>(*e.kind()))
+        }
+    }
+
+    #[test]
+    fn transparent_structs_encode_as_their_field() {
+        assert_eq!(encoded(&ExportId(5)), b"5+");
+        assert_eq!(
+            encoded(&Nickname {
+                name: "bob".to_owned()
+            }),
+            b"3\"bob"
+        );
+        assert_eq!(encoded(&DescExport(ExportId(5))), b"<11'desc:export5+>");
+    }
+
+    #[test]
+    fn transparent_structs_decode_from_their_field() {
+        assert_eq!(decode_bytes!(b"7+" => ExportId).unwrap().1, ExportId(7));
+        assert_eq!(
+            decode_bytes!(b"3\"bob" => Nickname).unwrap().1,
+            Nickname {
+                name: "bob".to_owned()
+            }
+        );
+        assert_eq!(
+            decode_bytes!(b"<11'desc:export5+>" => DescExport)
+                .unwrap()
+                .1,
+            DescExport(ExportId(5))
+        );
+    }
+
+    #[test]
+    fn container_with_forwards_to_module() {
+        let tree: TokenTree<Vec<u8>> = Port(8080).encode();
+        assert_eq!(tree.to_bytes().as_ref(), b"4\"8080");
+        assert_eq!(tree.decode::<Port>().unwrap(), Port(8080));
+        let not_digits: TokenTree<Vec<u8>> = crate::literal![Bool; false];
+        assert!(not_digits.decode::<Port>().is_err());
+    }
+}
+
+mod derive_dictionary {
+    use super::*;
+    use crate::de::SyrupKind;
+
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    #[syrup(dictionary)]
+    struct Deliver {
+        #[syrup(rename = "to")]
+        target: u64,
+        method: String,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    #[syrup(dictionary, deny_unknown_keys)]
+    struct Strict {
+        id: u64,
+    }
+
+    #[test]
+    fn encodes_as_symbol_keyed_dictionary() {
+        assert_eq!(
+            encoded(&Deliver {
+                target: 3,
+                method: "ping".to_owned()
+            }),
+            b"{2'to3+6'method4\"ping}"
+        );
+        assert_eq!(encoded(&Strict { id: 1 }), b"{2'id1+}");
+    }
+
+    #[test]
+    fn decodes_keys_in_any_order() {
+        let expected = Deliver {
+            target: 3,
+            method: "ping".to_owned(),
+        };
+        assert_eq!(
+            decode_bytes!(b"{2'to3+6'method4\"ping}" => Deliver)
+                .unwrap()
+                .1,
+            expected
+        );
+        assert_eq!(
+            decode_bytes!(b"{6'method4\"ping2'to3+}" => Deliver)
+                .unwrap()
+                .1,
+            expected
+        );
+    }
+
+    #[test]
+    fn reports_missing_keys() {
+        let err = decode_bytes!(b"{2'to3+}" => Deliver).unwrap_err();
+        assert!(
+            matches!(
+                &err,
+                crate::de::DecodeBytesError::Decode(e)
+                    if matches!(e.kind(), DecodeErrorKind::Missing(SyrupKind::Symbol(Some("method"))))
+            ),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn ignores_unknown_keys_by_default() {
+        assert_eq!(
+            decode_bytes!(b"{2'to3+5'extra1+6'method4\"ping1\"x0+}" => Deliver)
+                .unwrap()
+                .1,
+            Deliver {
+                target: 3,
+                method: "ping".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn denies_unknown_keys_when_asked() {
+        assert_eq!(
+            decode_bytes!(b"{2'id1+}" => Strict).unwrap().1,
+            Strict { id: 1 }
+        );
+        let err = decode_bytes!(b"{2'id1+5'extra1+}" => Strict).unwrap_err();
+        assert!(
+            matches!(
+                &err,
+                crate::de::DecodeBytesError::Decode(e)
+                    if matches!(e.kind(), DecodeErrorKind::UnknownKey { found, .. } if found == "extra")
+            ),
+            "{err:?}"
+        );
+        assert!(decode_bytes!(b"{2'id1+1\"x0+}" => Strict).is_err());
+    }
+
+    #[test]
+    fn rejects_non_dictionaries() {
+        assert!(decode_bytes!(b"<7'Deliver3+4\"ping>" => Deliver).is_err());
+    }
+}
+
+mod derive_default {
+    use super::*;
+    use crate::de::SyrupKind;
+
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Greeting {
+        name: String,
+        #[syrup(default)]
+        times: u64,
+        #[syrup(default = "!".to_owned())]
+        suffix: String,
+        #[syrup(skip)]
+        cached: Option<u64>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    enum Msg {
+        Hello(#[syrup(skip)] u64, #[syrup(from = u8)] u64),
+    }
+
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    #[syrup(dictionary)]
+    struct Options {
+        #[syrup(default = 30)]
+        timeout: u64,
+        #[syrup(skip)]
+        scratch: Vec<u8>,
+    }
+
+    #[test]
+    fn missing_trailing_fields_use_defaults() {
+        assert_eq!(
+            decode_bytes!(b"<8'Greeting3\"bob>" => Greeting).unwrap().1,
+            Greeting {
+                name: "bob".to_owned(),
+                times: 0,
+                suffix: "!".to_owned(),
+                cached: None,
+            }
+        );
+        assert_eq!(
+            decode_bytes!(b"<8'Greeting3\"bob2+>" => Greeting)
+                .unwrap()
+                .1,
+            Greeting {
+                name: "bob".to_owned(),
+                times: 2,
+                suffix: "!".to_owned(),
+                cached: None,
+            }
+        );
+        let err = decode_bytes!(b"<8'Greeting>" => Greeting).unwrap_err();
+        assert!(
+            matches!(
+                &err,
+                crate::de::DecodeBytesError::Decode(e)
+                    if matches!(e.kind(), DecodeErrorKind::Missing(SyrupKind::Unknown("name")))
+            ),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn skipped_fields_are_not_encoded() {
+        let greeting = Greeting {
+            name: "bob".to_owned(),
+            times: 2,
+            suffix: "?".to_owned(),
+            cached: Some(5),
+        };
+        assert_eq!(encoded(&greeting), b"<8'Greeting3\"bob2+1\"?>");
+        assert_eq!(encoded(&Msg::Hello(7, 8)), b"<5'Hello8+>");
+        assert_eq!(
+            encoded(&Options {
+                timeout: 5,
+                scratch: vec![1]
+            }),
+            b"{7'timeout5+}"
+        );
+    }
+
+    #[test]
+    fn skipped_fields_decode_as_default() {
+        assert_eq!(
+            decode_bytes!(b"<5'Hello8+>" => Msg).unwrap().1,
+            Msg::Hello(0, 8)
+        );
+        assert!(decode_bytes!(b"<5'Hello300+>" => Msg).is_err());
+        assert_eq!(
+            decode_bytes!(b"{}" => Options).unwrap().1,
+            Options {
+                timeout: 30,
+                scratch: vec![]
+            }
+        );
+        assert_eq!(
+            decode_bytes!(b"{7'scratch3:abc}" => Options).unwrap().1,
+            Options {
+                timeout: 30,
+                scratch: vec![]
+            }
+        );
+    }
+}
+
+mod derive_extra {
+    use super::*;
+    use crate::TokenTree;
+
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Loose(u64, u64);
+
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    #[syrup(label = "Loose", deny_extra)]
+    struct Strict(u64, #[syrup(default)] u64);
+
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    #[syrup(deny_extra)]
+    enum Cmd {
+        Stop,
+        Go(u64),
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    #[syrup(label = "Loose")]
+    struct Forward {
+        first: u64,
+        #[syrup(rest)]
+        extra: Vec<TokenTree<Vec<u8>>>,
+    }
+
+    #[test]
+    fn ignores_extra_elements_by_default() {
+        assert_eq!(
+            decode_bytes!(b"<5'Loose1+2+3+4+>" => Loose).unwrap().1,
+            Loose(1, 2)
+        );
+    }
+
+    #[test]
+    fn denies_extra_elements_when_asked() {
+        assert_eq!(
+            decode_bytes!(b"<5'Loose1+>" => Strict).unwrap().1,
+            Strict(1, 0)
+        );
+        assert_eq!(
+            decode_bytes!(b"<5'Loose1+2+>" => Strict).unwrap().1,
+            Strict(1, 2)
+        );
+        let err = decode_bytes!(b"<5'Loose1+2+3+4+>" => Strict).unwrap_err();
+        assert!(
+            matches!(
+                &err,
+                crate::de::DecodeBytesError::Decode(e)
+                    if matches!(e.kind(), DecodeErrorKind::ExtraElements { expected: 2, found: 4 })
+            ),
+            "{err:?}"
+        );
+        assert_eq!(decode_bytes!(b"<2'Go1+>" => Cmd).unwrap().1, Cmd::Go(1));
+        assert!(decode_bytes!(b"<2'Go1+2+>" => Cmd).is_err());
+        assert!(decode_bytes!(b"<4'Stop1+>" => Cmd).is_err());
+    }
+
+    #[test]
+    fn rest_captures_and_forwards_extra_elements() {
+        let input = b"<5'Loose1+2\"hi[3+]>";
+        let (_, forward) = decode_bytes!(input => Forward).unwrap();
+        assert_eq!(forward.first, 1);
+        assert_eq!(forward.extra.len(), 2);
+        assert_eq!(encoded(&forward), input);
+
+        let (_, empty) = decode_bytes!(b"<5'Loose1+>" => Forward).unwrap();
+        assert!(empty.extra.is_empty());
+        assert_eq!(encoded(&empty), b"<5'Loose1+>");
+    }
+}
+
+mod decode_paths {
+    use super::*;
+    use crate::de::{DecodeBytesError, DecodeError, PathSegment, SyrupKind};
+
+    #[derive(Debug, PartialEq, Eq, Decode)]
+    #[syrup(label = "desc:export")]
+    struct Export(u64);
+
+    #[derive(Debug, PartialEq, Eq, Decode)]
+    enum Op {
+        #[syrup(label = "op:deliver")]
+        Deliver { target: u64, args: Vec<Export> },
+    }
+
+    #[derive(Debug, PartialEq, Eq, Decode)]
+    #[syrup(dictionary)]
+    struct Options {
+        #[syrup(rename = "max-size")]
+        max_size: u64,
+    }
+
+    fn decode_err(res: Result<(&[u8], impl std::fmt::Debug), DecodeBytesError<'_>>) -> DecodeError {
+        match res {
+            Err(DecodeBytesError::Decode(e)) => e,
+            res => panic!("expected a decode error, got {res:?}"),
+        }
+    }
+
+    #[test]
+    fn reports_nested_paths() {
+        let err = decode_err(
+            decode_bytes!(b"<10'op:deliver1+[<11'desc:export1+><11'desc:export2\"xx>]>" => Op),
+        );
+        assert!(matches!(err.kind(), DecodeErrorKind::Unexpected { .. }));
+        assert_eq!(
+            err.path(),
+            [
+                PathSegment::Label("op:deliver".into()),
+                PathSegment::Field("args".into()),
+                PathSegment::Index(1),
+                PathSegment::Label("desc:export".into()),
+                PathSegment::Field("0".into()),
+            ]
+        );
+        assert!(
+            err.to_string()
+                .starts_with("op:deliver.args[1].<desc:export>.0: expected: "),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn reports_missing_fields_in_their_record() {
+        let err = decode_err(decode_bytes!(b"<10'op:deliver1+>" => Op));
+        assert!(matches!(err.kind(), DecodeErrorKind::Missing(_)));
+        assert_eq!(err.path(), [PathSegment::Label("op:deliver".into())]);
+    }
+
+    #[test]
+    fn reports_dictionary_keys() {
+        let err = decode_err(decode_bytes!(b"{8'max-size1\"x}" => Options));
+        assert_eq!(err.path(), [PathSegment::Field("max-size".into())]);
+    }
+
+    #[test]
+    fn reports_found_kind() {
+        let err = decode_err(decode_bytes!(b"<11'desc:export3\"abc>" => Export));
+        assert!(matches!(
+            err.kind(),
+            DecodeErrorKind::Unexpected {
+                found: SyrupKind::String,
+                ..
+            }
+        ));
+    }
+}
+
+mod derive_decode_from {
+    use super::*;
+    use crate::{
+        DecodeFrom,
+        de::{PathSegment, ReadError, Reader},
+    };
+
+    #[derive(Debug, PartialEq, Eq, DecodeFrom)]
+    #[syrup(label = "greet")]
+    struct Greeting<'a> {
+        name: &'a str,
+        #[syrup(default)]
+        times: u8,
+    }
+
+    #[derive(Debug, PartialEq, Eq, DecodeFrom)]
+    enum Op<'a> {
+        #[syrup(label = "op:deliver")]
+        Deliver {
+            target: u64,
+            args: Vec<Greeting<'a>>,
+        },
+        #[syrup(symbol)]
+        Ping,
+        Tail(u8, #[syrup(rest)] Vec<String>),
+    }
+
+    #[derive(Debug, PartialEq, Eq, DecodeFrom)]
+    #[syrup(dictionary)]
+    struct Options {
+        #[syrup(rename = "max-size")]
+        max_size: u64,
+        #[syrup(default)]
+        verbose: bool,
+    }
+
+    #[derive(Debug, PartialEq, Eq, DecodeFrom)]
+    #[syrup(dictionary, deny_unknown_keys)]
+    struct Strict {
+        a: u8,
+    }
+
+    #[derive(Debug, PartialEq, Eq, DecodeFrom)]
+    #[syrup(deny_extra)]
+    struct Exact(u8);
+
+    #[derive(Debug, PartialEq, Eq, DecodeFrom)]
+    #[syrup(transparent)]
+    struct Id(#[syrup(as = u32)] u64);
+
+    fn read<'i, T: DecodeFrom<'i>>(input: &'i [u8]) -> Result<T, ReadError> {
+        let mut reader = Reader::new(input);
+        let res = reader.read()?;
+        assert_eq!(reader.position(), input.len());
+        Ok(res)
+    }
+
+    #[test]
+    fn borrows_from_input() {
+        let input = b"<5'greet5\"world3+>";
+        let greeting: Greeting<'_> = read(input).unwrap();
+        assert_eq!(
+            greeting,
+            Greeting {
+                name: "world",
+                times: 3
+            }
+        );
+        assert!(input.as_ptr_range().contains(&greeting.name.as_ptr()));
+        assert_eq!(
+            read::<Greeting<'_>>(b"<5'greet2\"hi>").unwrap(),
+            Greeting {
+                name: "hi",
+                times: 0
+            }
+        );
+    }
+
+    #[test]
+    fn reads_variants() {
+        assert_eq!(
+            read::<Op<'_>>(b"<10'op:deliver4+[<5'greet1\"a1+>]>").unwrap(),
+            Op::Deliver {
+                target: 4,
+                args: vec![Greeting {
+                    name: "a",
+                    times: 1,
+                }],
+            }
+        );
+        assert_eq!(
+            read::<Op<'_>>(b"<4'Tail1+1\"x1\"y>").unwrap(),
+            Op::Tail(1, vec!["x".to_owned(), "y".to_owned()])
+        );
+        assert_eq!(read::<Op<'_>>(b"4'Ping").unwrap(), Op::Ping);
+        assert_eq!(read::<Op<'_>>(b"<4'Ping>").unwrap(), Op::Ping);
+    }
+
+    #[test]
+    fn field_overrides_decode_from_a_tree() {
+        assert_eq!(read::<Vec<Id>>(b"[1+2+]").unwrap(), [Id(1), Id(2)]);
+    }
+
+    #[test]
+    fn skips_what_it_doesnt_need() {
+        let greeting: Greeting<'_> = read(b"<5'greet1\"a1+[<1'x>#{}$]3:abc>").unwrap();
+        assert_eq!(greeting.name, "a");
+        let options: Options = read(b"{7'unknown[1+2+]8'max-size5+}").unwrap();
+        assert_eq!(
+            options,
+            Options {
+                max_size: 5,
+                verbose: false
+            }
+        );
+    }
+
+    #[test]
+    fn denies_when_asked() {
+        let err = read::<Strict>(b"{1'a1+1'b2+}").unwrap_err();
+        assert!(
+            matches!(&err, ReadError::Decode(e) if matches!(e.kind(), DecodeErrorKind::UnknownKey { .. })),
+            "{err:?}"
+        );
+        let err = read::<Exact>(b"<5'Exact1+2+3+>").unwrap_err();
+        assert!(
+            matches!(&err, ReadError::Decode(e) if matches!(e.kind(), DecodeErrorKind::ExtraElements { expected: 1, found: 3 })),
+            "{err:?}"
+        );
+        assert_eq!(read::<Exact>(b"<5'Exact1+>").unwrap(), Exact(1));
+    }
+
+    #[test]
+    fn reports_paths() {
+        let err = read::<Op<'_>>(b"<10'op:deliver1+[<5'greet1\"a><5'greet1+>]>").unwrap_err();
+        let ReadError::Decode(err) = err else {
+            panic!("{err:?}")
+        };
+        assert_eq!(
+            err.path(),
+            [
+                PathSegment::Label("op:deliver".into()),
+                PathSegment::Field("args".into()),
+                PathSegment::Index(1),
+                PathSegment::Label("greet".into()),
+                PathSegment::Field("name".into()),
+            ]
+        );
+
+        let err = read::<Options>(b"{8'max-size1\"x}").unwrap_err();
+        let ReadError::Decode(err) = err else {
+            panic!("{err:?}")
+        };
+        assert_eq!(err.path(), [PathSegment::Field("max-size".into())]);
+
+        let err = read::<Op<'_>>(b"<6'Square>").unwrap_err();
+        assert!(
+            matches!(&err, ReadError::Decode(e) if matches!(e.kind(), DecodeErrorKind::UnexpectedLabel { .. })),
+            "{err:?}"
+        );
+    }
+}
+
+mod from_bytes {
+    use crate::{
+        Decode, DecodeFrom,
+        de::{DecodeBytesError, DecodeFromBytes, ReadError},
+        from_bytes, from_bytes_partial,
+        symbol::Symbol,
+    };
+
+    #[derive(Debug, PartialEq, Eq, DecodeFrom)]
+    #[syrup(label = "desc:import")]
+    struct Import<'a> {
+        name: Symbol<&'a str>,
+        #[syrup(with = crate::bytes)]
+        key: &'a [u8],
+    }
+
+    #[derive(Debug, PartialEq, Eq, DecodeFrom)]
+    enum Message<'a> {
+        Call {
+            method: Symbol<&'a str>,
+            imports: Vec<Import<'a>>,
+        },
+        Note(&'a str),
+    }
+
+    #[test]
+    fn borrows_from_a_local_buffer() {
+        let input = b"<4'Call4'ping[<11'desc:import1'a2:\x01\x02>]>".to_vec();
+        let message: Message<'_> = from_bytes(&input).unwrap();
+        let Message::Call { method, imports } = &message else {
+            panic!("{message:?}")
+        };
+        assert_eq!(method.0, "ping");
+        assert_eq!(
+            imports,
+            &[Import {
+                name: Symbol("a"),
+                key: b"\x01\x02"
+            }]
+        );
+        assert!(input.as_ptr_range().contains(&method.0.as_ptr()));
+        assert!(input.as_ptr_range().contains(&imports[0].key.as_ptr()));
+    }
+
+    #[test]
+    fn returns_the_remainder() {
+        let input = b"<4'Note2\"hi><4'Note3\"you>";
+        let (rem, first) = from_bytes_partial::<Message<'_>>(input).unwrap();
+        assert_eq!(first, Message::Note("hi"));
+        assert_eq!(rem, b"<4'Note3\"you>");
+        assert_eq!(
+            from_bytes::<Message<'_>>(rem).unwrap(),
+            Message::Note("you")
+        );
+    }
+
+    #[test]
+    fn rejects_trailing_input() {
+        assert!(matches!(
+            from_bytes::<Message<'_>>(b"<4'Note2\"hi>t"),
+            Err(DecodeBytesError::TrailingInput { offset: 12 })
+        ));
+    }
+
+    #[test]
+    fn still_decodes_decode_only_types() {
+        #[derive(Debug, PartialEq, Eq, Decode)]
+        struct Owned {
+            name: String,
+        }
+
+        assert_eq!(
+            Owned::decode_bytes(b"<5'Owned2\"hi>t").unwrap(),
+            (
+                b"t".as_slice(),
+                Owned {
+                    name: "hi".to_owned()
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn separates_syntax_and_decode_errors() {
+        assert!(matches!(
+            from_bytes::<Message<'_>>(b"<4'Note2\"hi"),
+            Err(DecodeBytesError::Read(ReadError::Incomplete { offset: 11 }))
+        ));
+        assert!(matches!(
+            from_bytes::<Message<'_>>(b"<4'Note1+>"),
+            Err(DecodeBytesError::Decode(_))
+        ));
+    }
+}
+
+mod syrup_macro {
+    use crate::{Encode, TokenTree, symbol::Symbol, syrup, syrup_bytes};
+
+    #[derive(Encode)]
+    #[syrup(label = "pos")]
+    struct Pos {
+        x: i32,
+        y: i32,
+    }
+
+    fn text(text: &str) -> TokenTree<Vec<u8>> {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn builds_literals() {
+        assert_eq!(syrup! {#t}, text("#t"));
+        assert_eq!(syrup! {-12}, text("-12"));
+        assert_eq!(syrup! {0x10}, text("16"));
+        assert_eq!(syrup! {2.5}, text("2.5"));
+        assert_eq!(syrup! {-2.5f32}, text("-2.5f"));
+        assert_eq!(syrup! {"a\n"}, text("\"a\\n\""));
+        assert_eq!(syrup! {b"\xff"}, text("#\"\\xff\""));
+        assert_eq!(syrup! {op:deliver}, text("op:deliver"));
+        assert_eq!(syrup! {a-b.c}, text("a-b.c"));
+    }
+
+    #[test]
+    fn builds_collections() {
+        assert_eq!(
+            syrup! {<op:deliver 3 ["hi" #t] {foo: 1}>},
+            text("<op:deliver 3 [\"hi\" #t] {foo: 1}>")
+        );
+        assert_eq!(syrup! {[1, 2, 3]}, text("[1 2 3]"));
+        assert_eq!(syrup! {#{a b}}, text("#{a b}"));
+        assert_eq!(
+            syrup! {{a: b, c:d: [], <x>: #f}},
+            text("{a: b, c:d: [], <x>: #f}")
+        );
+    }
+
+    #[test]
+    fn only_joins_adjacent_tokens() {
+        assert_eq!(syrup! {[a - b, - 1, x::y]}, syrup! {[a, -, b, -, 1, x::y]});
+        assert_eq!(syrup! {x::y}, text("x::y"));
+        assert_eq!(syrup! {{x: :y}}, text("{x: ':y'}"));
+    }
+
+    #[test]
+    fn interpolates_values() {
+        let pos = Pos { x: 1, y: -2 };
+        let name = "ada".to_owned();
+        assert_eq!(
+            syrup! {<desc:export #(pos) #(name) #(Symbol("has space"))>},
+            text("<desc:export <pos 1 -2> \"ada\" 'has space'>")
+        );
+        let items = vec![1u8, 2];
+        assert_eq!(syrup! {{items: #(items)}}, text("{items: [1 2]}"));
+    }
+
+    #[test]
+    fn encodes_canonically() {
+        const BYTES: &[u8] = syrup_bytes! {#{2 1 1 {b: 1, a: -0}}};
+        assert_eq!(BYTES, b"#1+2+{1'a0+1'b1+}$");
+        assert_eq!(
+            syrup_bytes! {<op:deliver 3 ["hi" #t] {foo: 1}>},
+            &*syrup! {<op:deliver 3 ["hi" #t] {foo: 1}>}.to_bytes()
+        );
+        assert_eq!(
+            syrup_bytes! {123456789012345678901234567890123456789012345},
+            b"123456789012345678901234567890123456789012345+"
+        );
+    }
+}